@@ -0,0 +1,341 @@
+// 自动玩家：智能体根据玩家看到的游戏状态决定下一步。
+// 游戏中按 G 让设置中选择的智能体接管移动，命令行 maze_game bots 在生成的迷宫上批量比较。
+
+use crate::generator::{self, GeneratorParams};
+use crate::i18n::{tr, Language, Text};
+use crate::{Cell, Facing, MazeGame, Position};
+use macroquad::rand::RandGenerator;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+// 智能体能看到的状态：迷宫尺寸、自己的位置和看到过的格子（MazeGame::explored），
+// 物品和终点只有看到过才知道。观测是从游戏中复制出来的，智能体接触不到完整的迷宫
+pub struct View {
+    pub width: usize,
+    pub height: usize,
+    player: Position,
+    cells: Vec<Vec<Option<Cell>>>, // 没看到过的格子为 None
+    exit_locked: bool,
+}
+
+impl View {
+    pub fn observe(game: &MazeGame) -> Self {
+        let cells = game
+            .grid
+            .iter()
+            .zip(&game.explored)
+            .map(|(row, seen)| row.iter().zip(seen).map(|(&cell, &seen)| seen.then_some(cell)).collect())
+            .collect();
+        View { width: game.width, height: game.height, player: game.player_pos, cells, exit_locked: game.exit_locked() }
+    }
+
+    pub fn player(&self) -> Position {
+        self.player
+    }
+
+    // 还有物品没收集，终点不能进入（界面上显示了剩余物品数，玩家也知道）
+    pub fn exit_locked(&self) -> bool {
+        self.exit_locked
+    }
+
+    // 看到过的格子返回其内容，没看到过的返回 None
+    pub fn cell(&self, pos: Position) -> Option<Cell> {
+        self.cells[pos.y][pos.x]
+    }
+
+    // 看到过的格子中满足条件的位置
+    fn find(&self, wanted: fn(Cell) -> bool) -> Vec<Position> {
+        let mut found = Vec::new();
+        for (y, row) in self.cells.iter().enumerate() {
+            for (x, cell) in row.iter().enumerate() {
+                if cell.is_some_and(wanted) {
+                    found.push(Position { x, y });
+                }
+            }
+        }
+        found
+    }
+
+    // 终点，还没看到时返回 None
+    pub fn exit(&self) -> Option<Position> {
+        self.find(|cell| cell == Cell::End).first().copied()
+    }
+
+    // 看到过、还没收集的物品
+    pub fn items(&self) -> Vec<Position> {
+        self.find(|cell| matches!(cell, Cell::Coin | Cell::Gem))
+    }
+
+    // 从 pos 向 facing 走一步到达的格子，走出迷宫时返回 None
+    pub fn neighbor(&self, pos: Position, facing: Facing) -> Option<Position> {
+        let (dx, dy) = facing.delta();
+        let x = pos.x.checked_add_signed(dx as isize)?;
+        let y = pos.y.checked_add_signed(dy as isize)?;
+        (x < self.width && y < self.height).then_some(Position { x, y })
+    }
+
+    // 看到过并且可以走进
+    pub fn is_open(&self, pos: Position) -> bool {
+        match self.cell(pos) {
+            Some(Cell::Wall) | None => false,
+            Some(Cell::End) => !self.exit_locked,
+            Some(_) => true,
+        }
+    }
+}
+
+pub trait Agent {
+    // 下一步的方向；无路可走时返回 None
+    fn next_move(&mut self, view: &View) -> Option<Facing>;
+}
+
+// 每步随机选一个能走的方向
+struct RandomWalk {
+    rng: RandGenerator,
+}
+
+impl Agent for RandomWalk {
+    fn next_move(&mut self, view: &View) -> Option<Facing> {
+        let open: Vec<Facing> = Facing::ALL
+            .into_iter()
+            .filter(|&f| view.neighbor(view.player(), f).is_some_and(|n| view.is_open(n)))
+            .collect();
+        if open.is_empty() {
+            return None;
+        }
+        Some(open[self.rng.gen_range(0, open.len())])
+    }
+}
+
+// 右手扶墙：依次尝试右转、直行、左转、掉头
+#[derive(Default)]
+struct WallFollower {
+    heading: Option<Facing>,
+}
+
+impl Agent for WallFollower {
+    fn next_move(&mut self, view: &View) -> Option<Facing> {
+        let heading = self.heading.unwrap_or(Facing::Up) as usize;
+        let next = [1, 0, 3, 2]
+            .into_iter()
+            .map(|turn| Facing::ALL[(heading + turn) % 4])
+            .find(|&f| view.neighbor(view.player(), f).is_some_and(|n| view.is_open(n)))?;
+        self.heading = Some(next);
+        Some(next)
+    }
+}
+
+// 每一步重新做 BFS，把没看到过的格子当作可以走：
+// 终点开启且已经看到时走向终点，终点锁住时走向最近的已知物品，否则走向最近的未知格子
+struct BfsFollower;
+
+impl Agent for BfsFollower {
+    fn next_move(&mut self, view: &View) -> Option<Facing> {
+        let items = view.items();
+        let exit = view.exit().filter(|_| !view.exit_locked());
+        let goal = |pos: Position| match exit {
+            Some(exit) => pos == exit,
+            None if view.exit_locked() && !items.is_empty() => items.contains(&pos),
+            None => view.cell(pos).is_none(),
+        };
+        let passable = |pos: Position| view.cell(pos).is_none() || view.is_open(pos);
+
+        // 队列中记录从玩家出发的第一步
+        let start = view.player();
+        let mut visited = vec![vec![false; view.width]; view.height];
+        visited[start.y][start.x] = true;
+        let mut queue = VecDeque::new();
+        for facing in Facing::ALL {
+            if let Some(next) = view.neighbor(start, facing).filter(|&n| passable(n)) {
+                visited[next.y][next.x] = true;
+                queue.push_back((next, facing));
+            }
+        }
+        while let Some((pos, first)) = queue.pop_front() {
+            if goal(pos) {
+                return Some(first);
+            }
+            for facing in Facing::ALL {
+                if let Some(next) = view.neighbor(pos, facing).filter(|&n| passable(n) && !visited[n.y][n.x]) {
+                    visited[next.y][next.x] = true;
+                    queue.push_back((next, first));
+                }
+            }
+        }
+        None
+    }
+}
+
+// 内置的智能体
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AgentKind {
+    Random,
+    WallFollower,
+    Bfs,
+}
+
+impl AgentKind {
+    pub const ALL: [AgentKind; 3] = [AgentKind::Random, AgentKind::WallFollower, AgentKind::Bfs];
+
+    // 命令行中使用的名字，与设置文件中的相同
+    pub fn parse(name: &str) -> Option<Self> {
+        AgentKind::ALL.into_iter().find(|kind| kind.name() == name)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            AgentKind::Random => "random",
+            AgentKind::WallFollower => "wall_follower",
+            AgentKind::Bfs => "bfs",
+        }
+    }
+
+    pub fn display_name(self, language: Language) -> &'static str {
+        let text = match self {
+            AgentKind::Random => Text::AgentRandom,
+            AgentKind::WallFollower => Text::AgentWallFollower,
+            AgentKind::Bfs => Text::AgentBfs,
+        };
+        tr(language, text)
+    }
+
+    // seed 只影响随机游走
+    pub fn create(self, seed: u64) -> Box<dyn Agent> {
+        match self {
+            AgentKind::Random => {
+                let rng = RandGenerator::new();
+                rng.srand(seed);
+                Box::new(RandomWalk { rng })
+            }
+            AgentKind::WallFollower => Box::new(WallFollower::default()),
+            AgentKind::Bfs => Box::new(BfsFollower),
+        }
+    }
+}
+
+// 游戏中的自动游戏：按设置的速度让智能体一步步移动
+pub struct AutoPlay {
+    pub kind: AgentKind,
+    agent: Box<dyn Agent>,
+    timer: f32, // 距离下一步的时间
+}
+
+impl AutoPlay {
+    pub fn new(kind: AgentKind) -> Self {
+        AutoPlay { kind, agent: kind.create(macroquad::miniquad::date::now() as u64), timer: 0.0 }
+    }
+
+    // speed 为每秒步数；到了走下一步的时间时返回移动方向
+    pub fn update(&mut self, game: &MazeGame, dt: f32, speed: f32) -> Option<(i32, i32)> {
+        self.timer -= dt;
+        if self.timer > 0.0 {
+            return None;
+        }
+        self.timer = self.timer.max(0.0) + 1.0 / speed;
+        self.agent.next_move(&View::observe(game)).map(Facing::delta)
+    }
+}
+
+// 让智能体走一张迷宫，最多尝试 max_moves 步（撞墙也算）；到达终点时返回步数
+pub fn play(agent: &mut dyn Agent, game: &mut MazeGame, max_moves: usize) -> Option<usize> {
+    for _ in 0..max_moves {
+        let (dx, dy) = agent.next_move(&View::observe(game))?.delta();
+        game.move_player(dx, dy);
+        if game.has_won() {
+            return Some(game.moves);
+        }
+    }
+    None
+}
+
+// 一种智能体在一批迷宫上的成绩
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Summary {
+    pub agent: AgentKind,
+    pub mazes: usize,
+    pub solved: usize,
+    pub success_rate: f64,
+    pub average_moves: Option<f64>, // 只统计走到终点的迷宫
+}
+
+// 在种子 params.seed 起的 count 张迷宫上依次运行各个智能体
+pub fn batch(kinds: &[AgentKind], params: &GeneratorParams, count: u64, max_moves: usize) -> Vec<Summary> {
+    let grids: Vec<(u64, Vec<Vec<Cell>>)> = (0..count)
+        .map_while(|i| params.seed.checked_add(i))
+        .map(|seed| (seed, generator::generate(&GeneratorParams { seed, ..params.clone() })))
+        .collect();
+    kinds
+        .iter()
+        .map(|&kind| {
+            let moves: Vec<usize> = grids
+                .iter()
+                .filter_map(|(seed, grid)| {
+                    let mut game = MazeGame::from_grid(grid.clone()).expect("生成的迷宫应当有效");
+                    play(kind.create(*seed).as_mut(), &mut game, max_moves)
+                })
+                .collect();
+            let solved = moves.len();
+            Summary {
+                agent: kind,
+                mazes: grids.len(),
+                solved,
+                success_rate: if grids.is_empty() { 0.0 } else { solved as f64 / grids.len() as f64 },
+                average_moves: (solved > 0).then(|| moves.iter().sum::<usize>() as f64 / solved as f64),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::{Algorithm, PostProcess};
+
+    #[test]
+    fn test_agents_solve_mazes() {
+        // 右侧的死路里有一枚金币，拿到之后终点才会开启
+        let maze = "#######\n#S  $ #\n# #####\n#    E#\n#######";
+        // 一开始只看得到起点所在的走廊，终点还不知道
+        let view = View::observe(&MazeGame::from_text(maze).unwrap());
+        assert_eq!((view.exit(), view.items()), (None, vec![Position { x: 4, y: 1 }]));
+        assert_eq!(view.cell(Position { x: 5, y: 3 }), None);
+        for kind in AgentKind::ALL {
+            let mut game = MazeGame::from_text(maze).unwrap();
+            let moves = play(kind.create(1).as_mut(), &mut game, 1000);
+            assert!(moves.is_some(), "{:?}", kind);
+            assert_eq!(game.coins_collected, 1);
+        }
+        // BFS 看到金币后先去拿，再折回终点：3 + 3 + 2 + 4
+        let mut game = MazeGame::from_text(maze).unwrap();
+        assert_eq!(play(AgentKind::Bfs.create(1).as_mut(), &mut game, 100), Some(12));
+
+        let mut walled = MazeGame::from_text("#####\n#S#E#\n#####").unwrap();
+        assert_eq!(play(AgentKind::Bfs.create(1).as_mut(), &mut walled, 100), None);
+        assert_eq!(AgentKind::parse("wall_follower"), Some(AgentKind::WallFollower));
+    }
+
+    #[test]
+    fn test_batch_summary() {
+        let params = GeneratorParams {
+            algorithm: Algorithm::Backtracker,
+            width: 15,
+            height: 11,
+            seed: 1,
+            coins: 0,
+            gems: 0,
+            post: PostProcess::default(),
+        };
+        let summaries = batch(&[AgentKind::Bfs, AgentKind::WallFollower], &params, 5, 5000);
+        assert_eq!(summaries.len(), 2);
+        assert_eq!((summaries[0].mazes, summaries[0].solved), (5, 5));
+        assert_eq!(summaries[0].success_rate, 1.0);
+        assert!(summaries[0].average_moves.unwrap() > 10.0);
+        // 生成的迷宫没有环路，扶墙总能走遍所有格子
+        assert_eq!(summaries[1].solved, 5);
+
+        // 最后一个种子可以是 u64::MAX
+        let last = GeneratorParams { seed: u64::MAX, ..params };
+        assert_eq!(batch(&[AgentKind::Bfs], &last, 1, 5000)[0].mazes, 1);
+    }
+}
@@ -0,0 +1,222 @@
+// 资源管理
+//
+// 资源名是相对于项目根目录的路径（如 "assets/FiraSans-Regular.ttf"）。
+// 查找顺序：可执行文件所在目录及其上两级（cargo run 时为 target/debug → 项目根目录），
+// 然后是当前工作目录；都找不到时使用编译时内嵌的数据（需开启 embed-assets 特性）。
+// 最终仍找不到的资源会记录下来，在界面上提示。
+
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+pub const FONT_LATIN: &str = "assets/FiraSans-Regular.ttf";
+pub const FONT_CJK: &str = "assets/SimHei/web/font/SimHei.woff";
+pub const CAMPAIGN: &str = "levels/campaign.txt";
+
+// 编译时内嵌的资源；levels/ 下的文件要全部列出，内置战役的文件关卡从这里读取
+#[cfg(feature = "embed-assets")]
+const EMBEDDED: &[(&str, &[u8])] = &[
+    (FONT_LATIN, include_bytes!("../assets/FiraSans-Regular.ttf")),
+    (FONT_CJK, include_bytes!("../assets/SimHei/web/font/SimHei.woff")),
+    ("assets/themes/stone.txt", include_bytes!("../assets/themes/stone.txt")),
+    ("assets/themes/stone.png", include_bytes!("../assets/themes/stone.png")),
+    (CAMPAIGN, include_bytes!("../levels/campaign.txt")),
+    ("levels/spiral.txt", include_bytes!("../levels/spiral.txt")),
+];
+
+#[cfg(not(feature = "embed-assets"))]
+const EMBEDDED: &[(&str, &[u8])] = &[];
+
+pub fn embedded(name: &str) -> Option<&'static [u8]> {
+    EMBEDDED.iter().find(|(n, _)| *n == name).map(|&(_, bytes)| bytes)
+}
+
+// 已请求过的资源，用于热重载和缺失提示
+struct Tracked {
+    name: String,
+    path: Option<PathBuf>,
+    modified: Option<SystemTime>,
+    ok: bool, // 是否成功加载
+}
+
+pub struct Assets {
+    roots: Vec<PathBuf>,
+    tracked: Vec<Tracked>,
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+impl Assets {
+    // 按可执行文件位置和当前目录确定查找目录
+    pub fn new() -> Self {
+        let mut roots = Vec::new();
+        if let Some(exe_dir) = std::env::current_exe().ok().and_then(|p| p.parent().map(Path::to_path_buf)) {
+            roots.extend(exe_dir.ancestors().take(3).map(Path::to_path_buf));
+        }
+        if let Ok(cwd) = std::env::current_dir() {
+            roots.push(cwd);
+        }
+        Assets::with_roots(roots)
+    }
+
+    pub fn with_roots(roots: Vec<PathBuf>) -> Self {
+        Assets { roots, tracked: Vec::new() }
+    }
+
+    // 在磁盘上查找资源文件
+    pub fn locate(&self, name: &str) -> Option<PathBuf> {
+        self.roots.iter().map(|root| root.join(name)).find(|path| path.is_file())
+    }
+
+    fn track(&mut self, name: &str, path: Option<PathBuf>, ok: bool) {
+        let modified = path.as_deref().and_then(modified_time);
+        let entry = Tracked { name: name.to_string(), path, modified, ok };
+        match self.tracked.iter_mut().find(|t| t.name == name) {
+            Some(existing) => *existing = entry,
+            None => self.tracked.push(entry),
+        }
+    }
+
+    // 读取资源内容：优先磁盘文件，其次内嵌数据；都没有时记为缺失
+    pub fn load(&mut self, name: &str) -> Option<Vec<u8>> {
+        let path = self.locate(name);
+        let bytes = match &path {
+            Some(path) => std::fs::read(path)
+                .map_err(|e| eprintln!("无法读取 {}: {}", path.display(), e))
+                .ok(),
+            None => embedded(name).map(<[u8]>::to_vec),
+        };
+        if bytes.is_none() {
+            eprintln!("缺少资源 {}", name);
+        }
+        self.track(name, path, bytes.is_some());
+        bytes
+    }
+
+    // 列出目录下的资源名（磁盘上第一个存在的该目录，加上内嵌的资源），按名称排序
+    pub fn list(&self, dir: &str) -> Vec<String> {
+        let mut names: Vec<String> = EMBEDDED
+            .iter()
+            .map(|(name, _)| name.to_string())
+            .filter(|name| name.strip_prefix(dir).is_some_and(|rest| rest.starts_with('/')))
+            .collect();
+        let entries = self.roots.iter().find_map(|root| std::fs::read_dir(root.join(dir)).ok());
+        for entry in entries.into_iter().flatten().flatten() {
+            if entry.path().is_file() {
+                names.push(format!("{}/{}", dir, entry.file_name().to_string_lossy()));
+            }
+        }
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    // 只关注文件位置的资源（由调用者自己读取），同样参与热重载
+    pub fn watch(&mut self, name: &str) -> Option<PathBuf> {
+        let path = self.locate(name);
+        self.track(name, path.clone(), true);
+        path
+    }
+
+    // 资源存在但无法使用（如字体格式错误）
+    pub fn mark_broken(&mut self, name: &str) {
+        if let Some(entry) = self.tracked.iter_mut().find(|t| t.name == name) {
+            entry.ok = false;
+        }
+    }
+
+    // 加载失败的资源名
+    pub fn missing(&self) -> Vec<&str> {
+        self.tracked.iter().filter(|t| !t.ok).map(|t| t.name.as_str()).collect()
+    }
+
+    // 检查磁盘上的资源是否有变化（新出现、被删除或被修改），返回变化的资源名
+    pub fn changed(&mut self) -> Vec<String> {
+        let mut changed = Vec::new();
+        for i in 0..self.tracked.len() {
+            let path = self.locate(&self.tracked[i].name);
+            let modified = path.as_deref().and_then(modified_time);
+            let entry = &mut self.tracked[i];
+            if path != entry.path || modified != entry.modified {
+                entry.path = path;
+                entry.modified = modified;
+                changed.push(entry.name.clone());
+            }
+        }
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+    use std::time::Duration;
+
+    fn temp_root(tag: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("maze_game_assets_{}_{}", tag, std::process::id()));
+        std::fs::create_dir_all(dir.join("assets")).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_load_and_report_missing() {
+        let root = temp_root("load");
+        std::fs::write(root.join("assets/a.txt"), b"hello").unwrap();
+        let mut assets = Assets::with_roots(vec![PathBuf::from("/nonexistent"), root.clone()]);
+
+        assert_eq!(assets.load("assets/a.txt").as_deref(), Some(&b"hello"[..]));
+        assert!(assets.load("assets/b.txt").is_none());
+        assert_eq!(assets.missing(), vec!["assets/b.txt"]);
+
+        assets.mark_broken("assets/a.txt");
+        assert_eq!(assets.missing().len(), 2);
+
+        std::fs::write(root.join("assets/c.txt"), b"").unwrap();
+        // 开启 embed-assets 时列表中还有内嵌的资源
+        let listed: Vec<String> = assets.list("assets").into_iter().filter(|name| embedded(name).is_none()).collect();
+        assert_eq!(listed, vec!["assets/a.txt", "assets/c.txt"]);
+        assert!(assets.list("maps").is_empty());
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[cfg(feature = "embed-assets")]
+    #[test]
+    fn test_levels_are_embedded() {
+        use crate::level::{parse_manifest, LevelSource};
+
+        for entry in std::fs::read_dir("levels").unwrap() {
+            let name = format!("levels/{}", entry.unwrap().file_name().to_string_lossy());
+            assert!(embedded(&name).is_some(), "{} 没有内嵌", name);
+        }
+        // 内置清单中的文件关卡都能通过内嵌表找到
+        let manifest = std::str::from_utf8(embedded(CAMPAIGN).unwrap()).unwrap();
+        for level in parse_manifest(manifest, Path::new("levels")).unwrap() {
+            if let LevelSource::File(path) = level.source {
+                assert!(embedded(&path.to_string_lossy()).is_some(), "{} 没有内嵌", path.display());
+            }
+        }
+    }
+
+    #[test]
+    fn test_changed_detects_edits_and_new_files() {
+        let root = temp_root("changed");
+        let file = root.join("assets/a.txt");
+        std::fs::write(&file, b"one").unwrap();
+        let mut assets = Assets::with_roots(vec![root.clone()]);
+        assets.load("assets/a.txt");
+        assets.load("assets/b.txt");
+        assert!(assets.changed().is_empty());
+
+        // 修改时间变化视为修改
+        let later = SystemTime::now() + Duration::from_secs(10);
+        File::options().write(true).open(&file).unwrap().set_modified(later).unwrap();
+        std::fs::write(root.join("assets/b.txt"), b"two").unwrap();
+        let mut changed = assets.changed();
+        changed.sort();
+        assert_eq!(changed, vec!["assets/a.txt", "assets/b.txt"]);
+        assert!(assets.changed().is_empty());
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,221 @@
+// 音效和背景音乐
+//
+// 声音都在启动时合成为 WAV（16 位单声道），不需要额外的资源文件。
+// 音效音量和音乐音量分开设置，静音时两者都为 0，音乐仍在后台循环播放。
+
+use crate::settings::Settings;
+use macroquad::audio::{load_sound_from_bytes, play_sound, set_sound_volume, PlaySoundParams, Sound};
+use std::f32::consts::TAU;
+
+const SAMPLE_RATE: u32 = 22050;
+
+// 音效
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Effect {
+    Step,    // 成功移动一步
+    Bump,    // 撞墙或出口未解锁
+    Chime,   // 切换提示路线
+    Fanfare, // 通关
+}
+
+struct Sounds {
+    step: Sound,
+    bump: Sound,
+    chime: Sound,
+    fanfare: Sound,
+    music: Sound,
+}
+
+// 没有加载声音（如测试中或音频设备不可用）时不发声
+#[derive(Default)]
+pub struct Audio {
+    sounds: Option<Sounds>,
+}
+
+// 16 位单声道 PCM WAV 文件
+fn wav(samples: &[f32]) -> Vec<u8> {
+    let data_len = samples.len() as u32 * 2;
+    let mut bytes = Vec::with_capacity(44 + data_len as usize);
+    bytes.extend_from_slice(b"RIFF");
+    bytes.extend_from_slice(&(36 + data_len).to_le_bytes());
+    bytes.extend_from_slice(b"WAVEfmt ");
+    bytes.extend_from_slice(&16u32.to_le_bytes());
+    bytes.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    bytes.extend_from_slice(&1u16.to_le_bytes()); // 单声道
+    bytes.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    bytes.extend_from_slice(&(SAMPLE_RATE * 2).to_le_bytes());
+    bytes.extend_from_slice(&2u16.to_le_bytes());
+    bytes.extend_from_slice(&16u16.to_le_bytes());
+    bytes.extend_from_slice(b"data");
+    bytes.extend_from_slice(&data_len.to_le_bytes());
+    for &sample in samples {
+        let value = (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16;
+        bytes.extend_from_slice(&value.to_le_bytes());
+    }
+    bytes
+}
+
+// 音名（相对 A4 的半音数）对应的频率
+fn note(semitones: i32) -> f32 {
+    440.0 * 2f32.powf(semitones as f32 / 12.0)
+}
+
+// 一个音符：wave 为一个周期内的波形（参数为 0~1 的相位），音量按 decay 指数衰减，
+// 开头和结尾各有几毫秒淡入淡出，避免爆音
+fn tone(freq: f32, seconds: f32, decay: f32, wave: fn(f32) -> f32) -> Vec<f32> {
+    let count = (seconds * SAMPLE_RATE as f32) as usize;
+    let fade = (SAMPLE_RATE as f32 * 0.005) as usize;
+    (0..count)
+        .map(|i| {
+            let t = i as f32 / SAMPLE_RATE as f32;
+            let edge = (i.min(count - 1 - i) as f32 / fade as f32).min(1.0);
+            wave((t * freq).fract()) * (-t * decay).exp() * edge
+        })
+        .collect()
+}
+
+fn sine(phase: f32) -> f32 {
+    (phase * TAU).sin()
+}
+
+fn square(phase: f32) -> f32 {
+    if phase < 0.5 { 0.5 } else { -0.5 }
+}
+
+fn triangle(phase: f32) -> f32 {
+    1.0 - 4.0 * (phase - 0.5).abs()
+}
+
+// 脚步：很短的噪声，用简单的线性同余生成器保证每次合成结果相同
+fn footstep() -> Vec<f32> {
+    let mut seed: u32 = 12345;
+    let count = (SAMPLE_RATE as f32 * 0.06) as usize;
+    let mut last = 0.0;
+    (0..count)
+        .map(|i| {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            let noise = (seed >> 16) as f32 / 32768.0 - 1.0;
+            // 低通滤波让声音更闷
+            last += (noise - last) * 0.3;
+            last * (-(i as f32) / count as f32 * 5.0).exp() * 0.6
+        })
+        .collect()
+}
+
+fn bump() -> Vec<f32> {
+    tone(note(-36), 0.12, 25.0, square)
+}
+
+fn chime() -> Vec<f32> {
+    [note(12), note(19)].into_iter().flat_map(|f| tone(f, 0.09, 12.0, sine)).map(|s| s * 0.6).collect()
+}
+
+// C E G C 琶音，最后一个音拖长
+fn fanfare() -> Vec<f32> {
+    [(3, 0.12), (7, 0.12), (10, 0.12), (15, 0.45)]
+        .into_iter()
+        .flat_map(|(n, seconds)| tone(note(n), seconds, 3.0, triangle))
+        .map(|s| s * 0.6)
+        .collect()
+}
+
+// 背景音乐：五声音阶的八分音符旋律加低音，循环播放
+fn music() -> Vec<f32> {
+    const MELODY: [i32; 32] = [
+        3, 5, 7, 10, 7, 5, 3, -2, 0, 3, 5, 7, 5, 3, 0, -2, //
+        -5, -2, 0, 3, 5, 3, 0, -2, 0, 3, 5, 10, 7, 5, 3, 3,
+    ];
+    const BASS: [i32; 4] = [-21, -16, -26, -19];
+    let eighth = 0.25;
+    let mut samples: Vec<f32> = MELODY.iter().flat_map(|&n| tone(note(n), eighth, 4.0, sine)).collect();
+    let bass: Vec<f32> =
+        BASS.iter().flat_map(|&n| tone(note(n), eighth * 8.0, 0.8, triangle)).collect();
+    for (s, b) in samples.iter_mut().zip(bass) {
+        *s = *s * 0.25 + b * 0.2;
+    }
+    samples
+}
+
+impl Audio {
+    // 合成并加载所有声音；失败时返回不发声的 Audio
+    pub async fn load() -> Self {
+        async fn sound(samples: Vec<f32>) -> Result<Sound, String> {
+            load_sound_from_bytes(&wav(&samples)).await.map_err(|e| e.to_string())
+        }
+        let sounds = async {
+            Ok::<_, String>(Sounds {
+                step: sound(footstep()).await?,
+                bump: sound(bump()).await?,
+                chime: sound(chime()).await?,
+                fanfare: sound(fanfare()).await?,
+                music: sound(music()).await?,
+            })
+        };
+        match sounds.await {
+            Ok(sounds) => Audio { sounds: Some(sounds) },
+            Err(e) => {
+                eprintln!("无法加载声音，游戏将静音运行: {}", e);
+                Audio::default()
+            }
+        }
+    }
+
+    fn volumes(settings: &Settings) -> (f32, f32) {
+        if settings.muted {
+            (0.0, 0.0)
+        } else {
+            (settings.volume, settings.music_volume)
+        }
+    }
+
+    // 开始循环播放背景音乐，只在启动时调用一次
+    pub fn start_music(&self, settings: &Settings) {
+        if let Some(sounds) = &self.sounds {
+            let (_, volume) = Audio::volumes(settings);
+            play_sound(&sounds.music, PlaySoundParams { looped: true, volume });
+        }
+    }
+
+    // 设置变化后更新正在播放的音乐音量
+    pub fn apply(&self, settings: &Settings) {
+        if let Some(sounds) = &self.sounds {
+            set_sound_volume(&sounds.music, Audio::volumes(settings).1);
+        }
+    }
+
+    pub fn play(&self, effect: Effect, settings: &Settings) {
+        let Some(sounds) = &self.sounds else {
+            return;
+        };
+        let (volume, _) = Audio::volumes(settings);
+        if volume <= 0.0 {
+            return;
+        }
+        let sound = match effect {
+            Effect::Step => &sounds.step,
+            Effect::Bump => &sounds.bump,
+            Effect::Chime => &sounds.chime,
+            Effect::Fanfare => &sounds.fanfare,
+        };
+        play_sound(sound, PlaySoundParams { looped: false, volume });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_synthesized_wav() {
+        let samples = fanfare();
+        assert!(samples.iter().all(|s| s.abs() <= 1.0));
+        let bytes = wav(&samples);
+        assert_eq!(&bytes[..4], b"RIFF");
+        assert_eq!(&bytes[8..16], b"WAVEfmt ");
+        assert_eq!(bytes.len(), 44 + samples.len() * 2);
+        assert_eq!(u32::from_le_bytes(bytes[40..44].try_into().unwrap()) as usize, samples.len() * 2);
+
+        // 背景音乐恰好四小节，低音覆盖整段旋律
+        assert_eq!(music().len(), (SAMPLE_RATE as f32 * 0.25) as usize * 32);
+    }
+}
@@ -0,0 +1,605 @@
+// 命令行工具：不打开窗口，分析或生成迷宫，以 JSON 输出难度指标
+//
+//   maze_game analyze [--sort] <迷宫文件>...
+//   maze_game analyze [--sort] --campaign [关卡清单]
+//   maze_game analyze [--sort] --generate <backtracker|prim> [--size 31x21] [--seeds 10] [--seed 1]
+//
+// --sort 按难度从低到高排序。输出是一个数组，每项包含 name 和 metrics，
+// 无法分析的迷宫给出 error 字段。
+//
+//   maze_game generate [--algorithm backtracker] [--size 41x31] [--seed 1]
+//                      [--difficulty 4-6] [--min-solution N] [--max-solution N] [--budget 2]
+//
+// 按难度目标搜索迷宫（见 target.rs），输出找到的迷宫文本、种子、后处理和指标；
+// 时间预算（秒）用完或尝试的迷宫数达到上限仍未满足时输出最接近的迷宫，met 为 false，退出码为 1。
+//
+//   maze_game serve [--port 7878] [--algorithm backtracker] [--size 21x15] [--seed N]
+//   maze_game join <地址[:端口]> [--name 名字]
+//
+// serve 运行联网竞速服务器（协议见 net.rs），一直运行到进程结束；
+// join 打开游戏窗口并连接到服务器，由 main 在打开窗口前处理。
+//
+//   maze_game env [--algorithm backtracker] [--size 15x11] [--seed 1] [--coins 0] [--gems 0]
+//                 [--patch R] [--max-steps N]
+//
+// env 在标准输入输出上运行逐步环境（协议见 env.rs）；--patch 时观测为半径 R 的局部视野，
+// 否则为整张迷宫；--max-steps 为每回合的步数上限，默认不限。
+//
+//   maze_game bots [--agents random,wall_follower,bfs] [--algorithm backtracker] [--size 21x15]
+//                  [--seeds 50] [--seed 1] [--coins 0] [--max-moves N]
+//
+// bots 让各个智能体（见 agents.rs）走同一批生成的迷宫，输出每个智能体的成功率和平均步数；
+// 每张迷宫最多走 --max-moves 步（撞墙也算），默认为迷宫格子数的 20 倍。
+
+use crate::agents::{self, AgentKind};
+use crate::assets::{self, Assets};
+use crate::env::{self, EnvConfig, ObservationKind};
+use crate::generator::{self, Algorithm, GeneratorParams, PostProcess};
+use crate::level::Campaign;
+use crate::metrics::Metrics;
+use crate::net::{self, ServerState, DEFAULT_PORT};
+use crate::target::{self, Target};
+use crate::{grid_to_text, MazeGame};
+use serde_json::{json, Value};
+use std::net::TcpListener;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const USAGE: &str = "用法:
+  maze_game                      启动游戏
+  maze_game analyze [--sort] <迷宫文件>...
+  maze_game analyze [--sort] --campaign [关卡清单]
+  maze_game analyze [--sort] --generate <backtracker|prim> [--size 31x21] [--seeds 10] [--seed 1]
+  maze_game generate [--algorithm backtracker] [--size 41x31] [--seed 1]
+                     [--difficulty 4-6] [--min-solution N] [--max-solution N] [--budget 2]
+  maze_game serve [--port 7878] [--algorithm backtracker] [--size 21x15] [--seed N]
+  maze_game join <地址[:端口]> [--name 名字]
+  maze_game env [--algorithm backtracker] [--size 15x11] [--seed 1] [--coins 0] [--gems 0]
+                [--patch R] [--max-steps N]
+  maze_game bots [--agents random,wall_follower,bfs] [--algorithm backtracker] [--size 21x15]
+                 [--seeds 50] [--seed 1] [--coins 0] [--max-moves N]";
+
+// --size 允许的范围
+const MIN_SIZE: usize = 5;
+const MAX_SIZE: usize = 1000;
+
+// 要分析的迷宫来源
+#[derive(Debug, PartialEq)]
+enum Source {
+    Files(Vec<PathBuf>),
+    Campaign(Option<PathBuf>),
+    Generate { algorithm: Algorithm, width: usize, height: usize, seed: u64, count: u64 },
+}
+
+#[derive(Debug, PartialEq)]
+struct Analyze {
+    source: Source,
+    sort: bool,
+}
+
+#[derive(Debug, PartialEq)]
+struct Generate {
+    params: GeneratorParams,
+    target: Target,
+    budget: Duration,
+}
+
+#[derive(Debug, PartialEq)]
+struct Serve {
+    port: u16,
+    params: GeneratorParams,
+}
+
+#[derive(Debug, PartialEq)]
+struct Bots {
+    agents: Vec<AgentKind>,
+    params: GeneratorParams,
+    count: u64,
+    max_moves: usize,
+}
+
+// join 的参数，连接在打开游戏窗口后进行
+#[derive(Debug, PartialEq)]
+pub struct Join {
+    pub addr: String,
+    pub name: String,
+}
+
+// 处理命令行参数；没有参数时返回 None（正常启动游戏），否则返回进程退出码
+pub fn run(args: &[String]) -> Option<i32> {
+    let (command, rest) = args.split_first()?;
+    let code = match command.as_str() {
+        "analyze" => match parse_analyze(rest).and_then(|analyze| report(&analyze)) {
+            Ok(output) => {
+                println!("{}", serde_json::to_string_pretty(&output).unwrap_or_default());
+                0
+            }
+            Err(e) => {
+                eprintln!("{}\n\n{}", e, USAGE);
+                2
+            }
+        },
+        "generate" => match parse_generate(rest).and_then(|generate| search(&generate)) {
+            Ok((output, met)) => {
+                println!("{}", serde_json::to_string_pretty(&output).unwrap_or_default());
+                if met {
+                    0
+                } else {
+                    1
+                }
+            }
+            Err(e) => {
+                eprintln!("{}\n\n{}", e, USAGE);
+                2
+            }
+        },
+        "serve" => match parse_serve(rest) {
+            Ok(serve) => match TcpListener::bind(("0.0.0.0", serve.port)) {
+                Ok(listener) => {
+                    println!("竞速服务器已在端口 {} 上启动（种子 {}）", serve.port, serve.params.seed);
+                    net::serve(listener, ServerState::new(serve.params));
+                    0
+                }
+                Err(e) => {
+                    eprintln!("无法监听端口 {}: {}", serve.port, e);
+                    1
+                }
+            },
+            Err(e) => {
+                eprintln!("{}\n\n{}", e, USAGE);
+                2
+            }
+        },
+        "env" => match parse_env(rest) {
+            Ok(config) => {
+                let stdout = std::io::stdout();
+                match env::run_stdio(config, std::io::stdin().lock(), std::io::BufWriter::new(stdout.lock())) {
+                    Ok(()) => 0,
+                    Err(e) => {
+                        eprintln!("{}", e);
+                        1
+                    }
+                }
+            }
+            Err(e) => {
+                eprintln!("{}\n\n{}", e, USAGE);
+                2
+            }
+        },
+        "bots" => match parse_bots(rest) {
+            Ok(bots) => {
+                let summaries = agents::batch(&bots.agents, &bots.params, bots.count, bots.max_moves);
+                println!("{}", serde_json::to_string_pretty(&summaries).unwrap_or_default());
+                0
+            }
+            Err(e) => {
+                eprintln!("{}\n\n{}", e, USAGE);
+                2
+            }
+        },
+        "help" | "--help" | "-h" => {
+            println!("{}", USAGE);
+            0
+        }
+        other => {
+            eprintln!("未知命令 {}\n\n{}", other, USAGE);
+            2
+        }
+    };
+    Some(code)
+}
+
+fn parse_analyze(args: &[String]) -> Result<Analyze, String> {
+    let mut sort = false;
+    let mut files = Vec::new();
+    let mut campaign: Option<Option<PathBuf>> = None;
+    let mut generate: Option<Algorithm> = None;
+    let (mut width, mut height, mut seed, mut count) = (31, 21, 1, 10);
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let mut value = |name: &str| iter.next().ok_or_else(|| format!("{} 缺少参数", name));
+        match arg.as_str() {
+            "--sort" => sort = true,
+            "--campaign" => campaign = Some(None),
+            "--generate" => {
+                let name = value("--generate")?;
+                generate = Some(Algorithm::parse(name).ok_or_else(|| format!("未知的生成算法 {}", name))?);
+            }
+            "--size" => (width, height) = parse_size(value("--size")?)?,
+            "--seeds" => count = value("--seeds")?.parse().map_err(|_| "--seeds 应为整数".to_string())?,
+            "--seed" => seed = value("--seed")?.parse().map_err(|_| "--seed 应为整数".to_string())?,
+            flag if flag.starts_with("--") => return Err(format!("未知选项 {}", flag)),
+            path => match &mut campaign {
+                Some(manifest @ None) => *manifest = Some(PathBuf::from(path)),
+                _ => files.push(PathBuf::from(path)),
+            },
+        }
+    }
+
+    let source = match (campaign, generate) {
+        (Some(_), Some(_)) => return Err("--campaign 和 --generate 不能同时使用".to_string()),
+        (Some(manifest), None) if files.is_empty() => Source::Campaign(manifest),
+        (None, Some(algorithm)) if files.is_empty() => Source::Generate { algorithm, width, height, seed, count },
+        (None, None) if !files.is_empty() => Source::Files(files),
+        (None, None) => return Err("没有要分析的迷宫".to_string()),
+        _ => return Err("迷宫文件不能与 --campaign 或 --generate 同时使用".to_string()),
+    };
+    Ok(Analyze { source, sort })
+}
+
+// 解析 宽x高
+fn parse_size(size: &str) -> Result<(usize, usize), String> {
+    let parsed = size.split_once('x').and_then(|(w, h)| Some((w.parse().ok()?, h.parse().ok()?)));
+    let (width, height) = parsed.ok_or_else(|| format!("尺寸 {} 应为 宽x高", size))?;
+    if width < MIN_SIZE || height < MIN_SIZE {
+        return Err(format!("尺寸 {} 太小", size));
+    }
+    if width > MAX_SIZE || height > MAX_SIZE {
+        return Err(format!("尺寸 {} 太大，宽和高最多为 {}", size, MAX_SIZE));
+    }
+    Ok((width, height))
+}
+
+// 从 seed 开始连续 count 个种子，最后一个种子 seed + count - 1 不能超出范围
+fn check_seeds(seed: u64, count: u64) -> Result<(), String> {
+    seed.checked_add(count.saturating_sub(1)).map(|_| ()).ok_or_else(|| format!("--seed {} 加上 --seeds {} 超出范围", seed, count))
+}
+
+fn parse_generate(args: &[String]) -> Result<Generate, String> {
+    let mut params = GeneratorParams {
+        algorithm: Algorithm::Backtracker,
+        width: 41,
+        height: 31,
+        seed: 1,
+        coins: 0,
+        gems: 0,
+        post: PostProcess::default(),
+    };
+    let mut target = Target::default();
+    let mut budget = Duration::from_secs(2);
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let value = iter.next().ok_or_else(|| format!("{} 缺少参数", arg))?;
+        let number = |name: &str| value.parse::<f64>().map_err(|_| format!("{} 应为数字", name));
+        match arg.as_str() {
+            "--algorithm" => {
+                params.algorithm = Algorithm::parse(value).ok_or_else(|| format!("未知的生成算法 {}", value))?
+            }
+            "--size" => (params.width, params.height) = parse_size(value)?,
+            "--seed" => params.seed = value.parse().map_err(|_| "--seed 应为整数".to_string())?,
+            // 形如 4-6、4- 或 -6
+            "--difficulty" => {
+                let (min, max) = value.split_once('-').ok_or("--difficulty 应为 最小-最大")?;
+                let bound = |s: &str| -> Result<Option<f64>, String> {
+                    (!s.is_empty()).then(|| s.parse().map_err(|_| format!("难度 {} 应为数字", s))).transpose()
+                };
+                target.min_difficulty = bound(min)?;
+                target.max_difficulty = bound(max)?;
+            }
+            "--min-solution" | "--max-solution" => {
+                let steps = value.parse().map_err(|_| format!("{} 应为非负整数", arg))?;
+                match arg.as_str() {
+                    "--min-solution" => target.min_solution = Some(steps),
+                    _ => target.max_solution = Some(steps),
+                }
+            }
+            // 负数、无穷大和超出 Duration 范围的秒数都会出错
+            "--budget" => {
+                budget = Duration::try_from_secs_f64(number(arg)?).map_err(|_| format!("{} 超出范围", arg))?
+            }
+            other => return Err(format!("未知选项 {}", other)),
+        }
+    }
+    Ok(Generate { params, target, budget })
+}
+
+fn parse_serve(args: &[String]) -> Result<Serve, String> {
+    let seed = SystemTime::now().duration_since(UNIX_EPOCH).map_or(1, |d| d.as_secs());
+    let mut params = GeneratorParams {
+        algorithm: Algorithm::Backtracker,
+        width: 21,
+        height: 15,
+        seed,
+        coins: 0,
+        gems: 0,
+        post: PostProcess::default(),
+    };
+    let mut port = DEFAULT_PORT;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let value = iter.next().ok_or_else(|| format!("{} 缺少参数", arg))?;
+        match arg.as_str() {
+            "--port" => port = value.parse().map_err(|_| "--port 应为 0 到 65535 的整数".to_string())?,
+            "--algorithm" => {
+                params.algorithm = Algorithm::parse(value).ok_or_else(|| format!("未知的生成算法 {}", value))?
+            }
+            "--size" => (params.width, params.height) = parse_size(value)?,
+            "--seed" => params.seed = value.parse().map_err(|_| "--seed 应为整数".to_string())?,
+            other => return Err(format!("未知选项 {}", other)),
+        }
+    }
+    Ok(Serve { port, params })
+}
+
+fn parse_env(args: &[String]) -> Result<EnvConfig, String> {
+    let mut config = EnvConfig {
+        params: GeneratorParams {
+            algorithm: Algorithm::Backtracker,
+            width: 15,
+            height: 11,
+            seed: 1,
+            coins: 0,
+            gems: 0,
+            post: PostProcess::default(),
+        },
+        observation: ObservationKind::Grid,
+        max_steps: 0,
+    };
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let value = iter.next().ok_or_else(|| format!("{} 缺少参数", arg))?;
+        let count = || value.parse::<usize>().map_err(|_| format!("{} 应为非负整数", arg));
+        let params = &mut config.params;
+        match arg.as_str() {
+            "--algorithm" => {
+                params.algorithm = Algorithm::parse(value).ok_or_else(|| format!("未知的生成算法 {}", value))?
+            }
+            "--size" => (params.width, params.height) = parse_size(value)?,
+            "--seed" => params.seed = value.parse().map_err(|_| "--seed 应为整数".to_string())?,
+            "--coins" => params.coins = count()?,
+            "--gems" => params.gems = count()?,
+            "--patch" => config.observation = ObservationKind::Patch(count()?),
+            "--max-steps" => config.max_steps = count()?,
+            other => return Err(format!("未知选项 {}", other)),
+        }
+    }
+    Ok(config)
+}
+
+fn parse_bots(args: &[String]) -> Result<Bots, String> {
+    let mut params = GeneratorParams {
+        algorithm: Algorithm::Backtracker,
+        width: 21,
+        height: 15,
+        seed: 1,
+        coins: 0,
+        gems: 0,
+        post: PostProcess::default(),
+    };
+    let mut agents = AgentKind::ALL.to_vec();
+    let mut count = 50;
+    let mut max_moves = None;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let value = iter.next().ok_or_else(|| format!("{} 缺少参数", arg))?;
+        let number = || value.parse::<usize>().map_err(|_| format!("{} 应为非负整数", arg));
+        match arg.as_str() {
+            "--agents" => {
+                agents = value
+                    .split(',')
+                    .map(|name| AgentKind::parse(name).ok_or_else(|| format!("未知的智能体 {}", name)))
+                    .collect::<Result<_, _>>()?
+            }
+            "--algorithm" => {
+                params.algorithm = Algorithm::parse(value).ok_or_else(|| format!("未知的生成算法 {}", value))?
+            }
+            "--size" => (params.width, params.height) = parse_size(value)?,
+            "--seeds" => count = number()? as u64,
+            "--seed" => params.seed = value.parse().map_err(|_| "--seed 应为整数".to_string())?,
+            "--coins" => params.coins = number()?,
+            "--max-moves" => max_moves = Some(number()?),
+            other => return Err(format!("未知选项 {}", other)),
+        }
+    }
+    check_seeds(params.seed, count)?;
+    let max_moves = max_moves.unwrap_or(params.width * params.height * 20);
+    Ok(Bots { agents, params, count, max_moves })
+}
+
+// 第一个参数是 join 时解析其余参数，否则返回 None；地址不带端口时使用默认端口
+pub fn parse_join(args: &[String]) -> Option<Result<Join, String>> {
+    let (command, rest) = args.split_first()?;
+    if command != "join" {
+        return None;
+    }
+    let parse = || {
+        let mut addr = None;
+        let mut name = std::env::var("USER").unwrap_or_default();
+        let mut iter = rest.iter();
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "--name" => name = iter.next().ok_or("--name 缺少参数")?.clone(),
+                flag if flag.starts_with("--") => return Err(format!("未知选项 {}", flag)),
+                other if addr.is_none() => addr = Some(other.to_string()),
+                other => return Err(format!("多余的参数 {}", other)),
+            }
+        }
+        let addr = addr.ok_or("缺少服务器地址")?;
+        let addr = if addr.contains(':') { addr } else { format!("{}:{}", addr, DEFAULT_PORT) };
+        Ok(Join { addr, name })
+    };
+    Some(parse().map_err(|e: String| format!("{}\n\n{}", e, USAGE)))
+}
+
+fn search(generate: &Generate) -> Result<(Value, bool), String> {
+    let outcome = target::search(&generate.params, &generate.target, generate.budget)?;
+    let maze: Vec<String> = grid_to_text(&outcome.grid).lines().map(str::to_string).collect();
+    let output = json!({
+        "algorithm": generate.params.algorithm,
+        "seed": outcome.seed,
+        "post": outcome.post,
+        "met": outcome.met,
+        "attempts": outcome.attempts,
+        "metrics": outcome.metrics,
+        "maze": maze,
+    });
+    Ok((output, outcome.met))
+}
+
+fn entry(name: String, game: Result<MazeGame, String>) -> Value {
+    match game.and_then(|game| Metrics::analyze(&game)) {
+        Ok(metrics) => json!({ "name": name, "metrics": metrics }),
+        Err(e) => json!({ "name": name, "error": e }),
+    }
+}
+
+fn report(analyze: &Analyze) -> Result<Value, String> {
+    let mut entries: Vec<Value> = match &analyze.source {
+        Source::Files(paths) => paths
+            .iter()
+            .map(|path| {
+                let game = std::fs::read_to_string(path)
+                    .map_err(|e| format!("无法读取 {}: {}", path.display(), e))
+                    .and_then(|text| MazeGame::from_text(&text));
+                entry(path.display().to_string(), game)
+            })
+            .collect(),
+        Source::Campaign(manifest) => {
+            let path = manifest.clone().or_else(|| Assets::new().locate(assets::CAMPAIGN));
+            Campaign::load_or_builtin(path.as_deref())
+                .levels
+                .iter()
+                .map(|level| entry(level.name.clone(), level.build()))
+                .collect()
+        }
+        &Source::Generate { algorithm, width, height, seed, count } => {
+            check_seeds(seed, count)?;
+            (0..count)
+                .map(|i| seed + i)
+                .map(|seed| {
+                    let params = GeneratorParams {
+                        algorithm,
+                        width,
+                        height,
+                        seed,
+                        coins: 0,
+                        gems: 0,
+                        post: PostProcess::default(),
+                    };
+                    entry(format!("seed {}", seed), MazeGame::from_grid(generator::generate(&params)))
+                })
+                .collect()
+        }
+    };
+
+    // 无法分析的排在最后
+    if analyze.sort {
+        let key = |entry: &Value| entry["metrics"]["difficulty"].as_f64().unwrap_or(f64::INFINITY);
+        entries.sort_by(|a, b| key(a).total_cmp(&key(b)));
+    }
+    Ok(Value::Array(entries))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_analyze_arguments() {
+        let analyze = parse_analyze(&args(&["--campaign", "levels/campaign.txt", "--sort"])).unwrap();
+        assert_eq!(analyze.source, Source::Campaign(Some(PathBuf::from("levels/campaign.txt"))));
+        assert!(analyze.sort);
+
+        let analyze = parse_analyze(&args(&["--generate", "prim", "--size", "15x11", "--seeds", "3"])).unwrap();
+        assert_eq!(
+            analyze.source,
+            Source::Generate { algorithm: Algorithm::Prim, width: 15, height: 11, seed: 1, count: 3 }
+        );
+
+        assert!(parse_analyze(&[]).is_err());
+        assert!(parse_analyze(&args(&["--generate", "nope"])).is_err());
+        assert!(parse_analyze(&args(&["--size", "15"])).is_err());
+        assert!(parse_analyze(&args(&["--generate", "prim", "--size", "100000x5"])).is_err());
+        let overflow = parse_analyze(&args(&["--generate", "prim", "--seed", &u64::MAX.to_string()])).unwrap();
+        assert!(report(&overflow).is_err());
+        let last = parse_analyze(&args(&["--generate", "prim", "--size", "7x7", "--seed", &u64::MAX.to_string(), "--seeds", "1"]));
+        assert_eq!(report(&last.unwrap()).unwrap().as_array().unwrap().len(), 1);
+        assert!(check_seeds(u64::MAX - 1, 2).is_ok() && check_seeds(u64::MAX - 1, 3).is_err());
+        assert!(check_seeds(u64::MAX, 0).is_ok());
+        assert!(parse_analyze(&args(&["a.txt", "--campaign"])).is_err());
+        assert_eq!(run(&[]), None);
+    }
+
+    #[test]
+    fn test_generate_to_target() {
+        let generate = parse_generate(&args(&["--size", "15x11", "--difficulty", "1-", "--budget", "5"])).unwrap();
+        assert_eq!(generate.target.min_difficulty, Some(1.0));
+        assert_eq!(generate.target.max_difficulty, None);
+        let (output, met) = search(&generate).unwrap();
+        assert!(met);
+        assert!(output["metrics"]["difficulty"].as_f64().unwrap() >= 1.0);
+
+        // 输出的迷宫文本可以直接作为关卡文件读取
+        let text: Vec<&str> = output["maze"].as_array().unwrap().iter().map(|l| l.as_str().unwrap()).collect();
+        assert_eq!(text.len(), 11);
+        assert!(MazeGame::from_text(&text.join("\n")).is_ok());
+
+        assert!(parse_generate(&args(&["--difficulty", "4"])).is_err());
+        assert!(parse_generate(&args(&["--min-solution", "1.5"])).is_err());
+        let generate = parse_generate(&args(&["--min-solution", "40", "--max-solution", "60"])).unwrap();
+        assert_eq!((generate.target.min_solution, generate.target.max_solution), (Some(40), Some(60)));
+        assert!(parse_generate(&args(&["--budget"])).is_err());
+        for budget in ["1e20", "inf", "NaN", "-1"] {
+            assert!(parse_generate(&args(&["--budget", budget])).is_err(), "{}", budget);
+        }
+        assert_eq!(parse_generate(&args(&["--budget", "0.5"])).unwrap().budget, Duration::from_millis(500));
+    }
+
+    #[test]
+    fn test_parse_serve_join_and_env() {
+        let serve = parse_serve(&args(&["--port", "9000", "--size", "15x11", "--seed", "4"])).unwrap();
+        assert_eq!((serve.port, serve.params.width, serve.params.seed), (9000, 15, 4));
+        assert!(parse_serve(&args(&["--port", "70000"])).is_err());
+
+        let join = parse_join(&args(&["join", "localhost", "--name", "小明"])).unwrap().unwrap();
+        assert_eq!(join, Join { addr: format!("localhost:{}", DEFAULT_PORT), name: "小明".to_string() });
+        assert_eq!(parse_join(&args(&["join", "10.0.0.2:9000"])).unwrap().unwrap().addr, "10.0.0.2:9000");
+        assert!(parse_join(&args(&["join"])).unwrap().is_err());
+        assert!(parse_join(&args(&["analyze"])).is_none());
+
+        let config = parse_env(&args(&["--patch", "3", "--max-steps", "200", "--coins", "2"])).unwrap();
+        assert_eq!(config.observation, ObservationKind::Patch(3));
+        assert_eq!((config.max_steps, config.params.coins), (200, 2));
+        assert!(parse_env(&args(&["--patch", "-1"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_bots_arguments() {
+        let bots = parse_bots(&args(&["--agents", "bfs,random", "--size", "11x9", "--seeds", "3"])).unwrap();
+        assert_eq!(bots.agents, vec![AgentKind::Bfs, AgentKind::Random]);
+        assert_eq!((bots.count, bots.max_moves), (3, 11 * 9 * 20));
+        assert_eq!(parse_bots(&[]).unwrap().agents.len(), 3);
+        assert!(parse_bots(&args(&["--agents", "bfs,nope"])).is_err());
+        assert!(parse_bots(&args(&["--seed", &u64::MAX.to_string()])).is_err());
+        assert!(parse_bots(&args(&["--seed", &u64::MAX.to_string(), "--seeds", "1"])).is_ok());
+    }
+
+    #[test]
+    fn test_report_generated_mazes_sorted() {
+        let analyze = Analyze {
+            source: Source::Generate { algorithm: Algorithm::Backtracker, width: 15, height: 11, seed: 5, count: 4 },
+            sort: true,
+        };
+        let output = report(&analyze).unwrap();
+        let difficulties: Vec<f64> = output
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|entry| entry["metrics"]["difficulty"].as_f64().unwrap())
+            .collect();
+        assert_eq!(difficulties.len(), 4);
+        assert!(difficulties.windows(2).all(|pair| pair[0] <= pair[1]));
+
+        let missing = Analyze { source: Source::Files(vec![PathBuf::from("/nonexistent/maze.txt")]), sort: false };
+        assert!(report(&missing).unwrap()[0]["error"].is_string());
+    }
+}
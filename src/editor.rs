@@ -0,0 +1,369 @@
+// 迷宫编辑器：用鼠标绘制墙、空地和特殊格子，实时检查能否走通，
+// 支持撤销/重做、调整尺寸，以文本迷宫格式（见 MazeGame::from_text）保存和读取
+
+use crate::i18n::{fill, tr, Language, Text};
+use crate::theme::Style;
+use crate::{draw_label, grid_to_text, Cell, MazeGame, Position};
+use macroquad::prelude::*;
+use std::path::{Path, PathBuf};
+
+const MIN_SIZE: usize = 5;
+const MAX_WIDTH: usize = 61;
+const MAX_HEIGHT: usize = 41;
+const UNDO_LIMIT: usize = 100;
+const HUD_HEIGHT: f32 = 140.0;
+
+// 可选的画笔，数字键 1~6 依次选择
+const BRUSHES: [Cell; 6] = [Cell::Wall, Cell::Empty, Cell::Start, Cell::End, Cell::Coin, Cell::Gem];
+
+// 实时检查的结果
+#[derive(Debug, Clone, PartialEq)]
+enum Check {
+    Solvable(Vec<Position>), // 起点到终点的最短路线
+    Unsolvable,
+    Invalid(String), // 无法构成关卡，如缺少起点
+}
+
+pub struct Editor {
+    grid: Vec<Vec<Cell>>,
+    brush: Cell,
+    undo: Vec<Vec<Vec<Cell>>>,
+    redo: Vec<Vec<Vec<Cell>>>,
+    check: Check,
+    show_path: bool,
+    stroke: Option<Vec<Vec<Cell>>>, // 拖动开始前的网格，松开鼠标时有变化才记入撤销
+    path: Option<PathBuf>,   // 保存和读取的文件，为 None 时不能保存
+    message: Option<String>, // 最近一次保存或读取的结果
+}
+
+// 四周是墙、左上角为起点、右下角为终点的空迷宫
+fn blank(width: usize, height: usize) -> Vec<Vec<Cell>> {
+    let mut grid = vec![vec![Cell::Empty; width]; height];
+    for (y, row) in grid.iter_mut().enumerate() {
+        for (x, cell) in row.iter_mut().enumerate() {
+            if x == 0 || y == 0 || x == width - 1 || y == height - 1 {
+                *cell = Cell::Wall;
+            }
+        }
+    }
+    grid[1][1] = Cell::Start;
+    grid[height - 2][width - 2] = Cell::End;
+    grid
+}
+
+fn brush_name(cell: Cell) -> Text {
+    match cell {
+        Cell::Wall => Text::BrushWall,
+        Cell::Start => Text::BrushStart,
+        Cell::End => Text::BrushEnd,
+        Cell::Coin => Text::BrushCoin,
+        Cell::Gem => Text::BrushGem,
+        _ => Text::BrushFloor,
+    }
+}
+
+impl Editor {
+    pub fn new(path: Option<PathBuf>) -> Self {
+        let mut editor = Editor {
+            grid: blank(21, 15),
+            brush: Cell::Wall,
+            undo: Vec::new(),
+            redo: Vec::new(),
+            check: Check::Unsolvable,
+            show_path: true,
+            stroke: None,
+            path,
+            message: None,
+        };
+        editor.recheck();
+        editor
+    }
+
+    fn width(&self) -> usize {
+        self.grid[0].len()
+    }
+
+    fn height(&self) -> usize {
+        self.grid.len()
+    }
+
+    // 用 MazeGame 的规则检查关卡，并求最短路线
+    fn recheck(&mut self) {
+        self.check = match MazeGame::from_grid(self.grid.clone()) {
+            Ok(game) => match game.find_shortest_path() {
+                Some(path) => Check::Solvable(path),
+                None => Check::Unsolvable,
+            },
+            Err(e) => Check::Invalid(e),
+        };
+    }
+
+    // 修改之前保存当前网格
+    fn snapshot(&mut self) {
+        self.push_undo(self.grid.clone());
+    }
+
+    fn push_undo(&mut self, grid: Vec<Vec<Cell>>) {
+        self.undo.push(grid);
+        if self.undo.len() > UNDO_LIMIT {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+    }
+
+    // 在 pos 处画上 cell；起点和终点只有一个，画新的时清掉旧的
+    fn paint(&mut self, pos: Position, cell: Cell) {
+        if pos.x >= self.width() || pos.y >= self.height() || self.grid[pos.y][pos.x] == cell {
+            return;
+        }
+        if matches!(cell, Cell::Start | Cell::End) {
+            for c in self.grid.iter_mut().flatten().filter(|c| **c == cell) {
+                *c = Cell::Empty;
+            }
+        }
+        self.grid[pos.y][pos.x] = cell;
+        self.recheck();
+    }
+
+    // 调整尺寸，保留左上角的内容，新增的格子是墙
+    fn resize(&mut self, width: usize, height: usize) {
+        let width = width.clamp(MIN_SIZE, MAX_WIDTH);
+        let height = height.clamp(MIN_SIZE, MAX_HEIGHT);
+        if (width, height) == (self.width(), self.height()) {
+            return;
+        }
+        self.snapshot();
+        self.grid.resize(height, vec![Cell::Wall; width]);
+        for row in &mut self.grid {
+            row.resize(width, Cell::Wall);
+        }
+        self.recheck();
+    }
+
+    fn undo(&mut self) {
+        if let Some(grid) = self.undo.pop() {
+            self.redo.push(std::mem::replace(&mut self.grid, grid));
+            self.recheck();
+        }
+    }
+
+    fn redo(&mut self) {
+        if let Some(grid) = self.redo.pop() {
+            self.undo.push(std::mem::replace(&mut self.grid, grid));
+            self.recheck();
+        }
+    }
+
+    fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        std::fs::write(path, grid_to_text(&self.grid)).map_err(|e| format!("无法写入 {}: {}", path.display(), e))
+    }
+
+    // 读取文本迷宫，文件须能构成关卡（见 MazeGame::from_grid）
+    fn load(&mut self, path: &Path) -> Result<(), String> {
+        let text = std::fs::read_to_string(path).map_err(|e| format!("无法读取 {}: {}", path.display(), e))?;
+        let grid = MazeGame::from_text(&text)?.initial_grid;
+        if grid.len() > MAX_HEIGHT || grid[0].len() > MAX_WIDTH {
+            return Err(format!("迷宫超过 {}x{}", MAX_WIDTH, MAX_HEIGHT));
+        }
+        self.snapshot();
+        self.grid = grid;
+        self.recheck();
+        Ok(())
+    }
+
+    fn cell_size(&self) -> f32 {
+        let fit_w = screen_width() / self.width() as f32;
+        let fit_h = (screen_height() - HUD_HEIGHT) / self.height() as f32;
+        fit_w.min(fit_h).clamp(4.0, 40.0)
+    }
+
+    // 每帧处理输入；返回 false 表示离开编辑器
+    pub fn update(&mut self, language: Language) -> bool {
+        if is_key_pressed(KeyCode::Escape) {
+            return false;
+        }
+        let ctrl = is_key_down(KeyCode::LeftControl) || is_key_down(KeyCode::RightControl);
+        let shift = is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift);
+
+        const BRUSH_KEYS: [KeyCode; 6] =
+            [KeyCode::Key1, KeyCode::Key2, KeyCode::Key3, KeyCode::Key4, KeyCode::Key5, KeyCode::Key6];
+        for (key, brush) in BRUSH_KEYS.into_iter().zip(BRUSHES) {
+            if is_key_pressed(key) {
+                self.brush = brush;
+            }
+        }
+
+        // 左键用当前画笔，右键擦成空地
+        let cell_size = self.cell_size();
+        let (mx, my) = mouse_position();
+        let hovered = (mx >= 0.0 && my >= 0.0).then(|| Position {
+            x: (mx / cell_size) as usize,
+            y: (my / cell_size) as usize,
+        });
+        let (left, right) = (is_mouse_button_down(MouseButton::Left), is_mouse_button_down(MouseButton::Right));
+        if (left || right) && self.stroke.is_none() {
+            self.stroke = Some(self.grid.clone());
+        }
+        if let Some(pos) = hovered {
+            if left {
+                self.paint(pos, self.brush);
+            } else if right {
+                self.paint(pos, Cell::Empty);
+            }
+        }
+        if !left && !right {
+            if let Some(before) = self.stroke.take().filter(|before| *before != self.grid) {
+                self.push_undo(before);
+            }
+        }
+
+        let (w, h) = (self.width(), self.height());
+        if is_key_pressed(KeyCode::Right) {
+            self.resize(w + 1, h);
+        } else if is_key_pressed(KeyCode::Left) {
+            self.resize(w - 1, h);
+        } else if is_key_pressed(KeyCode::Down) {
+            self.resize(w, h + 1);
+        } else if is_key_pressed(KeyCode::Up) {
+            self.resize(w, h - 1);
+        }
+
+        if ctrl && (is_key_pressed(KeyCode::Y) || (shift && is_key_pressed(KeyCode::Z))) {
+            self.redo();
+        } else if ctrl && is_key_pressed(KeyCode::Z) {
+            self.undo();
+        }
+        if is_key_pressed(KeyCode::P) {
+            self.show_path = !self.show_path;
+        }
+        if is_key_pressed(KeyCode::N) {
+            self.snapshot();
+            self.grid = blank(w, h);
+            self.recheck();
+        }
+
+        let t = |text| tr(language, text);
+        if let Some(path) = self.path.clone() {
+            let path_text = path.display().to_string();
+            if ctrl && is_key_pressed(KeyCode::S) {
+                self.message = Some(match self.save(&path) {
+                    Ok(()) => fill(t(Text::EditorSaved), &[&path_text]),
+                    Err(e) => e,
+                });
+            }
+            if ctrl && is_key_pressed(KeyCode::L) {
+                self.message = Some(match self.load(&path) {
+                    Ok(()) => fill(t(Text::EditorLoaded), &[&path_text]),
+                    Err(e) => e,
+                });
+            }
+        }
+        true
+    }
+
+    pub fn render(&self, style: &Style) {
+        let palette = style.palette;
+        let cell_size = self.cell_size();
+        for (y, row) in self.grid.iter().enumerate() {
+            for (x, &cell) in row.iter().enumerate() {
+                let (px, py) = (x as f32 * cell_size, y as f32 * cell_size);
+                let color = match cell {
+                    Cell::Wall => palette.wall,
+                    Cell::Start => palette.start,
+                    Cell::End => palette.end,
+                    _ => palette.floor,
+                };
+                draw_rectangle(px, py, cell_size, cell_size, color);
+                let (cx, cy) = (px + cell_size / 2.0, py + cell_size / 2.0);
+                match cell {
+                    Cell::Coin => draw_circle(cx, cy, cell_size * 0.25, palette.coin),
+                    Cell::Gem => draw_poly(cx, cy, 4, cell_size * 0.3, 0.0, palette.gem),
+                    _ => {}
+                }
+                draw_rectangle_lines(px, py, cell_size, cell_size, 1.0, palette.grid_line);
+            }
+        }
+        if let (true, Check::Solvable(path)) = (self.show_path, &self.check) {
+            for pos in path.iter().filter(|&&pos| self.grid[pos.y][pos.x] != Cell::End) {
+                let c = vec2(pos.x as f32 + 0.5, pos.y as f32 + 0.5) * cell_size;
+                draw_circle(c.x, c.y, cell_size * 0.2, palette.path);
+            }
+        }
+
+        let t = |text| tr(style.language, text);
+        let mut check = match &self.check {
+            Check::Solvable(path) => fill(t(Text::EditorSolvable), &[&path.len()]),
+            Check::Unsolvable => t(Text::Unsolvable).to_string(),
+            Check::Invalid(e) => e.clone(),
+        };
+        if let Some(message) = &self.message {
+            check = format!("{}  {}", check, message);
+        }
+        let lines = [
+            fill(t(Text::EditorBrush), &[&t(brush_name(self.brush))]),
+            fill(t(Text::EditorHelp), &[&self.width(), &self.height()]),
+            t(Text::EditorKeys).to_string(),
+            check,
+        ];
+        let top = self.height() as f32 * cell_size;
+        for (i, line) in lines.iter().enumerate() {
+            draw_label(line, 10.0, top + 30.0 + i as f32 * 25.0, style);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paint_undo_redo_and_check() {
+        let mut editor = Editor::new(None);
+        assert!(matches!(editor.check, Check::Solvable(_)));
+
+        // 用墙把起点围住
+        editor.snapshot();
+        editor.paint(Position { x: 2, y: 1 }, Cell::Wall);
+        editor.paint(Position { x: 1, y: 2 }, Cell::Wall);
+        editor.paint(Position { x: 2, y: 2 }, Cell::Wall);
+        assert_eq!(editor.check, Check::Unsolvable);
+        editor.undo();
+        assert!(matches!(editor.check, Check::Solvable(_)));
+        editor.redo();
+        assert_eq!(editor.check, Check::Unsolvable);
+
+        // 起点只有一个
+        editor.paint(Position { x: 5, y: 5 }, Cell::Start);
+        assert_eq!(editor.grid[1][1], Cell::Empty);
+        assert!(matches!(editor.check, Check::Solvable(_)));
+        editor.paint(Position { x: 19, y: 13 }, Cell::Wall);
+        assert!(matches!(editor.check, Check::Invalid(_)));
+    }
+
+    #[test]
+    fn test_resize_and_save_load_round_trip() {
+        let path = std::env::temp_dir().join(format!("maze_game_editor_{}.txt", std::process::id()));
+        let mut editor = Editor::new(Some(path.clone()));
+        editor.resize(23, 4);
+        assert_eq!((editor.width(), editor.height()), (23, MIN_SIZE));
+        assert_eq!(editor.grid[0][22], Cell::Wall);
+        // 终点被裁掉了，重新放一个
+        assert!(matches!(editor.check, Check::Invalid(_)));
+        editor.paint(Position { x: 3, y: 3 }, Cell::End);
+        editor.paint(Position { x: 2, y: 3 }, Cell::Coin);
+        assert!(matches!(editor.check, Check::Solvable(_)));
+        editor.save(&path).unwrap();
+
+        let saved = editor.grid.clone();
+        let mut other = Editor::new(Some(path.clone()));
+        other.load(&path).unwrap();
+        assert_eq!(other.grid, saved);
+        other.undo();
+        assert_eq!((other.width(), other.height()), (21, 15));
+        std::fs::remove_file(&path).unwrap();
+        assert!(other.load(&path).is_err());
+    }
+}
@@ -0,0 +1,199 @@
+// 视觉效果层：撞墙时画面抖动、玩家身后逐渐消失的脚印、通关时的彩纸和终点脉冲。
+// 只按时间推进，不影响游戏逻辑；由 App 在移动被拒绝、走了一步和通关时通知。
+
+use crate::theme::Palette;
+use crate::Position;
+use macroquad::prelude::*;
+use macroquad::rand::RandGenerator;
+
+const SHAKE_TIME: f32 = 0.25;
+const SHAKE_AMPLITUDE: f32 = 6.0; // 像素
+const FOOTPRINT_LIFE: f32 = 2.5;
+const MAX_FOOTPRINTS: usize = 40;
+const CONFETTI_COUNT: usize = 120;
+const CONFETTI_LIFE: f32 = 2.5;
+const GRAVITY: f32 = 600.0; // 像素/秒²
+const PULSE_PERIOD: f32 = 0.8;
+
+struct Footprint {
+    pos: Position,
+    dir: Vec2,
+    age: f32,
+}
+
+// 彩纸，位置和速度为屏幕像素
+struct Confetti {
+    pos: Vec2,
+    vel: Vec2,
+    color: Color,
+    spin: f32,
+    age: f32,
+}
+
+pub struct Effects {
+    shake: f32, // 抖动剩余时间
+    shake_dir: Vec2,
+    footprints: Vec<Footprint>,
+    confetti: Vec<Confetti>,
+    pulse: Option<(Position, f32)>, // 通关后终点的位置和经过的时间
+    rng: RandGenerator,
+}
+
+impl Default for Effects {
+    fn default() -> Self {
+        let rng = RandGenerator::new();
+        rng.srand(1);
+        Effects {
+            shake: 0.0,
+            shake_dir: Vec2::ZERO,
+            footprints: Vec::new(),
+            confetti: Vec::new(),
+            pulse: None,
+            rng,
+        }
+    }
+}
+
+impl Effects {
+    // 关卡开始或重置时清除所有效果
+    pub fn clear(&mut self) {
+        self.shake = 0.0;
+        self.footprints.clear();
+        self.confetti.clear();
+        self.pulse = None;
+    }
+
+    // 向 (dx, dy) 方向移动被挡住：沿该方向抖动
+    pub fn bump(&mut self, (dx, dy): (i32, i32)) {
+        self.shake = SHAKE_TIME;
+        self.shake_dir = vec2(dx as f32, dy as f32).normalize_or_zero();
+    }
+
+    // 从 from 出发向 (dx, dy) 走了一步，在 from 留下脚印
+    pub fn step(&mut self, from: Position, (dx, dy): (i32, i32)) {
+        if self.footprints.len() >= MAX_FOOTPRINTS {
+            self.footprints.remove(0);
+        }
+        let dir = vec2(dx as f32, dy as f32).normalize_or_zero();
+        self.footprints.push(Footprint { pos: from, dir, age: 0.0 });
+    }
+
+    // 通关：从屏幕上的 origin 喷出彩纸；end 为俯视图中的终点，用于画脉冲
+    pub fn celebrate(&mut self, origin: Vec2, end: Option<Position>, palette: &Palette) {
+        let colors = [palette.coin, palette.gem, palette.end, palette.player, palette.path];
+        for i in 0..CONFETTI_COUNT {
+            let angle = self.rng.gen_range(-2.6f32, -0.5);
+            let speed = self.rng.gen_range(250.0f32, 650.0);
+            self.confetti.push(Confetti {
+                pos: origin,
+                vel: vec2(angle.cos(), angle.sin()) * speed,
+                color: colors[i % colors.len()],
+                spin: self.rng.gen_range(0.0f32, 6.0),
+                age: 0.0,
+            });
+        }
+        self.pulse = end.map(|end| (end, 0.0));
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        self.shake = (self.shake - dt).max(0.0);
+        for footprint in &mut self.footprints {
+            footprint.age += dt;
+        }
+        self.footprints.retain(|f| f.age < FOOTPRINT_LIFE);
+        for c in &mut self.confetti {
+            c.vel.y += GRAVITY * dt;
+            c.vel *= (-dt * 1.5).exp(); // 空气阻力
+            c.pos += c.vel * dt;
+            c.spin += dt * 8.0;
+            c.age += dt;
+        }
+        self.confetti.retain(|c| c.age < CONFETTI_LIFE);
+        if let Some((_, t)) = &mut self.pulse {
+            *t += dt;
+        }
+    }
+
+    // 当前抖动的偏移（像素），来回摆动并逐渐减弱
+    pub fn shake_offset(&self) -> Vec2 {
+        if self.shake <= 0.0 {
+            return Vec2::ZERO;
+        }
+        let progress = 1.0 - self.shake / SHAKE_TIME;
+        let swing = (progress * std::f32::consts::TAU * 3.0).sin();
+        self.shake_dir * swing * SHAKE_AMPLITUDE * (self.shake / SHAKE_TIME)
+    }
+
+    // 画在迷宫上的效果（脚印和终点脉冲），坐标与俯视图的格子一致
+    pub fn render_world(&self, palette: &Palette, cell_size: f32) {
+        let center = |pos: Position| vec2(pos.x as f32 + 0.5, pos.y as f32 + 0.5) * cell_size;
+        for footprint in &self.footprints {
+            let mut color = palette.player;
+            color.a = 0.5 * (1.0 - footprint.age / FOOTPRINT_LIFE);
+            let side = vec2(-footprint.dir.y, footprint.dir.x) * cell_size * 0.12;
+            let c = center(footprint.pos);
+            // 左右两只脚一前一后
+            for (s, ahead) in [(1.0, 0.1), (-1.0, -0.1)] {
+                let p = c + side * s + footprint.dir * cell_size * ahead;
+                draw_circle(p.x, p.y, cell_size * 0.08, color);
+            }
+        }
+        if let Some((end, t)) = self.pulse {
+            let c = center(end);
+            let phase = (t / PULSE_PERIOD).fract();
+            let mut color = palette.end;
+            color.a = 1.0 - phase;
+            draw_circle_lines(c.x, c.y, cell_size * (0.5 + phase), 3.0, color);
+        }
+    }
+
+    // 彩纸画在所有内容之上（屏幕坐标）
+    pub fn render_particles(&self) {
+        for c in &self.confetti {
+            let mut color = c.color;
+            color.a = 1.0 - (c.age / CONFETTI_LIFE).powi(2);
+            draw_rectangle_ex(
+                c.pos.x,
+                c.pos.y,
+                8.0,
+                4.0,
+                DrawRectangleParams { offset: vec2(0.5, 0.5), rotation: c.spin, color },
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_effects_fade_over_time() {
+        let palette = Palette::named("classic");
+        let mut effects = Effects::default();
+        effects.bump((1, 0));
+        assert_eq!(effects.shake_offset(), Vec2::ZERO); // 刚开始时处于摆动的起点
+        effects.update(SHAKE_TIME / 4.0);
+        assert!(effects.shake_offset().x.abs() > 0.0);
+        assert_eq!(effects.shake_offset().y, 0.0);
+
+        for x in 0..MAX_FOOTPRINTS + 5 {
+            effects.step(Position { x, y: 1 }, (1, 0));
+        }
+        assert_eq!(effects.footprints.len(), MAX_FOOTPRINTS);
+        assert_eq!(effects.footprints[0].pos.x, 5);
+
+        effects.celebrate(vec2(100.0, 100.0), Some(Position { x: 3, y: 3 }), &palette);
+        assert_eq!(effects.confetti.len(), CONFETTI_COUNT);
+        effects.update(1.0);
+        assert!(effects.confetti.iter().all(|c| c.pos != vec2(100.0, 100.0)));
+
+        // 时间过去后只剩终点脉冲，重新开始时清除
+        effects.update(FOOTPRINT_LIFE.max(CONFETTI_LIFE));
+        assert_eq!(effects.shake_offset(), Vec2::ZERO);
+        assert!(effects.footprints.is_empty() && effects.confetti.is_empty());
+        assert!(effects.pulse.is_some());
+        effects.clear();
+        assert!(effects.pulse.is_none());
+    }
+}
@@ -0,0 +1,269 @@
+// 无窗口的逐步环境，供脚本、机器人和强化学习使用：reset(seed) 生成一张迷宫并返回观测，
+// step(action) 移动一步并返回观测、奖励和是否结束。
+//
+// 观测是以格子为单位的整数矩阵（CELL_* 编码），可以是整张迷宫，也可以是以玩家为中心、
+// 边长 2r+1 的局部视野（迷宫外视为墙）。动作 0 上、1 右、2 下、3 左。
+//
+// stdio 协议（maze_game env，见 cli.rs）：标准输入每行一个请求，标准输出每行一个回复。
+//   {"cmd":"reset","seed":3}   → {"observation":[[...]],"x":1,"y":1,"width":15,"height":11}
+//                                 省略 seed 时使用上一次的种子加一
+//   {"cmd":"step","action":1}  → {"observation":[[...]],"x":2,"y":1,"reward":-0.01,"done":false,
+//                                  "won":false,"truncated":false,"moves":1}
+//   {"cmd":"close"}            → 退出（读到输入结束也会退出）
+// 出错时回复 {"error":"..."}，环境状态不变。
+
+use crate::generator::{self, GeneratorParams};
+use crate::{Cell, Facing, MazeGame};
+use serde::{Deserialize, Serialize};
+use std::io::{self, BufRead, Write};
+
+// 观测中格子的编码
+pub const CELL_FLOOR: u8 = 0;
+pub const CELL_WALL: u8 = 1;
+pub const CELL_START: u8 = 2;
+pub const CELL_END: u8 = 3;
+pub const CELL_COIN: u8 = 4;
+pub const CELL_GEM: u8 = 5;
+pub const CELL_PLAYER: u8 = 6;
+
+// 奖励：每一步有小的代价，撞墙代价更大，收集物品和到达终点得分
+const REWARD_STEP: f32 = -0.01;
+const REWARD_BUMP: f32 = -0.05;
+const REWARD_COIN: f32 = 0.1;
+const REWARD_GEM: f32 = 0.3;
+const REWARD_WIN: f32 = 1.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ObservationKind {
+    Grid,
+    Patch(usize), // 局部视野的半径
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnvConfig {
+    pub params: GeneratorParams, // 其中的种子由 reset 决定
+    pub observation: ObservationKind,
+    pub max_steps: usize, // 超过后回合截断，0 表示不限
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Observation {
+    pub observation: Vec<Vec<u8>>,
+    pub x: usize, // 玩家在整张迷宫中的位置
+    pub y: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Step {
+    #[serde(flatten)]
+    pub observation: Observation,
+    pub reward: f32,
+    pub done: bool,
+    pub won: bool,
+    pub truncated: bool, // 因步数上限结束
+    pub moves: usize,
+}
+
+pub struct Env {
+    config: EnvConfig,
+    game: Option<MazeGame>,
+    seed: u64,
+    steps: usize,
+    done: bool,
+}
+
+impl Env {
+    pub fn new(config: EnvConfig) -> Self {
+        let seed = config.params.seed;
+        Env { config, game: None, seed, steps: 0, done: false }
+    }
+
+    pub fn reset(&mut self, seed: u64) -> Observation {
+        self.seed = seed;
+        let params = GeneratorParams { seed, ..self.config.params.clone() };
+        let game = MazeGame::from_grid(generator::generate(&params)).expect("生成的迷宫应当有效");
+        let observation = self.observe(&game);
+        self.game = Some(game);
+        self.steps = 0;
+        self.done = false;
+        observation
+    }
+
+    pub fn step(&mut self, action: usize) -> Result<Step, String> {
+        let &facing = Facing::ALL.get(action).ok_or_else(|| format!("动作 {} 应为 0 到 3", action))?;
+        if self.done {
+            return Err("回合已结束，请先 reset".to_string());
+        }
+        let game = self.game.as_mut().ok_or("请先 reset")?;
+        let items = (game.coins_collected, game.gems_collected);
+        let (dx, dy) = facing.delta();
+        let mut reward = if game.move_player(dx, dy) { REWARD_STEP } else { REWARD_BUMP };
+        reward += (game.coins_collected - items.0) as f32 * REWARD_COIN;
+        reward += (game.gems_collected - items.1) as f32 * REWARD_GEM;
+        let won = game.has_won();
+        if won {
+            reward += REWARD_WIN;
+        }
+
+        self.steps += 1;
+        let truncated = !won && self.config.max_steps > 0 && self.steps >= self.config.max_steps;
+        self.done = won || truncated;
+        let game = self.game.as_ref().expect("上面已经检查过");
+        Ok(Step { observation: self.observe(game), reward, done: self.done, won, truncated, moves: game.moves })
+    }
+
+    fn observe(&self, game: &MazeGame) -> Observation {
+        let code = |x: i64, y: i64| {
+            if x < 0 || y < 0 || x as usize >= game.width || y as usize >= game.height {
+                return CELL_WALL;
+            }
+            let (x, y) = (x as usize, y as usize);
+            if (x, y) == (game.player_pos.x, game.player_pos.y) {
+                return CELL_PLAYER;
+            }
+            match game.grid[y][x] {
+                Cell::Wall => CELL_WALL,
+                Cell::Start => CELL_START,
+                Cell::End => CELL_END,
+                Cell::Coin => CELL_COIN,
+                Cell::Gem => CELL_GEM,
+                Cell::Empty | Cell::Path => CELL_FLOOR,
+            }
+        };
+        let (px, py) = (game.player_pos.x as i64, game.player_pos.y as i64);
+        let (left, top, width, height) = match self.config.observation {
+            ObservationKind::Grid => (0, 0, game.width as i64, game.height as i64),
+            ObservationKind::Patch(r) => {
+                let r = r as i64;
+                (px - r, py - r, 2 * r + 1, 2 * r + 1)
+            }
+        };
+        let observation = (top..top + height)
+            .map(|y| (left..left + width).map(|x| code(x, y)).collect())
+            .collect();
+        Observation { observation, x: game.player_pos.x, y: game.player_pos.y }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "cmd", rename_all = "snake_case")]
+enum Request {
+    Reset { seed: Option<u64> },
+    Step { action: usize },
+    Close,
+}
+
+#[derive(Serialize)]
+struct ResetReply {
+    #[serde(flatten)]
+    observation: Observation,
+    width: usize,
+    height: usize,
+}
+
+// 逐行处理请求直到 close 或输入结束；每个回复立即刷新，便于交互式驱动
+pub fn run_stdio(config: EnvConfig, input: impl BufRead, mut output: impl Write) -> io::Result<()> {
+    let mut env = Env::new(config);
+    let mut next_seed = env.seed;
+    for line in input.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let reply = match serde_json::from_str(&line) {
+            Ok(Request::Reset { seed }) => {
+                let seed = seed.unwrap_or(next_seed);
+                next_seed = seed.wrapping_add(1);
+                let observation = env.reset(seed);
+                let game = env.game.as_ref().expect("reset 之后应有迷宫");
+                serde_json::to_value(ResetReply { observation, width: game.width, height: game.height })
+            }
+            Ok(Request::Step { action }) => match env.step(action) {
+                Ok(step) => serde_json::to_value(step),
+                Err(e) => Ok(serde_json::json!({ "error": e })),
+            },
+            Ok(Request::Close) => break,
+            Err(e) => Ok(serde_json::json!({ "error": format!("无法解析请求: {}", e) })),
+        }
+        .map_err(io::Error::other)?;
+        writeln!(output, "{}", reply)?;
+        output.flush()?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::{Algorithm, PostProcess};
+
+    fn config(observation: ObservationKind, max_steps: usize) -> EnvConfig {
+        EnvConfig {
+            params: GeneratorParams {
+                algorithm: Algorithm::Backtracker,
+                width: 11,
+                height: 9,
+                seed: 1,
+                coins: 0,
+                gems: 0,
+                post: PostProcess::default(),
+            },
+            observation,
+            max_steps,
+        }
+    }
+
+    #[test]
+    fn test_episode_rewards_and_patches() {
+        let mut env = Env::new(config(ObservationKind::Grid, 0));
+        assert!(env.step(0).is_err());
+        let first = env.reset(5);
+        assert_eq!(env.reset(5), first);
+        assert_eq!((first.observation.len(), first.observation[0].len()), (9, 11));
+        assert_eq!(first.observation[first.y][first.x], CELL_PLAYER);
+        assert!(env.step(4).is_err());
+
+        // 沿最短路线走到终点
+        let game = env.game.as_ref().unwrap();
+        let mut pos = game.start_pos;
+        let path = game.find_shortest_path().unwrap();
+        let mut total = 0.0;
+        for next in path {
+            let dir = (next.x as i32 - pos.x as i32, next.y as i32 - pos.y as i32);
+            let action = Facing::ALL.iter().position(|f| f.delta() == dir).unwrap();
+            let step = env.step(action).unwrap();
+            total += step.reward;
+            pos = next;
+            if step.done {
+                assert!(step.won && !step.truncated);
+            }
+        }
+        assert!(total > 0.5);
+        assert!(env.step(0).is_err());
+
+        // 局部视野以玩家为中心，迷宫外是墙；撞墙扣分，达到步数上限时截断
+        let mut env = Env::new(config(ObservationKind::Patch(2), 2));
+        let observation = env.reset(5);
+        assert_eq!(observation.observation.len(), 5);
+        assert_eq!(observation.observation[2][2], CELL_PLAYER);
+        assert_eq!(observation.observation[0], vec![CELL_WALL; 5]);
+        let step = env.step(0).unwrap(); // 起点在左上角，上方是外墙
+        assert_eq!((step.reward, step.moves, step.done), (REWARD_BUMP, 0, false));
+        assert!(env.step(0).unwrap().truncated);
+    }
+
+    #[test]
+    fn test_stdio_protocol() {
+        let input = "{\"cmd\":\"reset\",\"seed\":2}\n{\"cmd\":\"step\",\"action\":0}\nnonsense\n\
+                     {\"cmd\":\"reset\"}\n{\"cmd\":\"close\"}\n{\"cmd\":\"reset\"}\n";
+        let mut output = Vec::new();
+        run_stdio(config(ObservationKind::Grid, 0), input.as_bytes(), &mut output).unwrap();
+        let replies: Vec<serde_json::Value> =
+            String::from_utf8(output).unwrap().lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+        assert_eq!(replies.len(), 4);
+        assert_eq!(replies[0]["width"], 11);
+        assert_eq!(replies[1]["reward"].as_f64().unwrap() as f32, REWARD_BUMP);
+        assert!(replies[2]["error"].is_string());
+        assert_ne!(replies[3]["observation"], replies[0]["observation"]);
+    }
+}
@@ -0,0 +1,266 @@
+// 随机迷宫生成器
+//
+// 在细墙迷宫上生成，再转换为"整格墙"网格：奇数坐标为房间，偶数坐标为墙或打通的通道，
+// 因此宽高都会被调整为奇数。起点固定在左上角，终点固定在右下角。
+
+use crate::i18n::{tr, Language, Text};
+use crate::thin::ThinMaze;
+use crate::{Cell, Facing, Position};
+use macroquad::rand::RandGenerator;
+use serde::{Deserialize, Serialize};
+
+// 生成算法
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Algorithm {
+    Backtracker, // 递归回溯（长走廊、分支少）
+    Prim,        // 随机 Prim（短分支多、死路多）
+}
+
+impl Algorithm {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "backtracker" => Some(Algorithm::Backtracker),
+            "prim" => Some(Algorithm::Prim),
+            _ => None,
+        }
+    }
+
+    pub fn display_name(self, language: Language) -> &'static str {
+        match self {
+            Algorithm::Backtracker => tr(language, Text::Backtracker),
+            Algorithm::Prim => tr(language, Text::Prim),
+        }
+    }
+}
+
+// 生成后的处理：打通死路、增加环路，二者都会让迷宫出现多条路线
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct PostProcess {
+    pub braid: f64,   // 打通的死路比例，0.0 ~ 1.0
+    pub loops: usize, // 额外拆掉的墙数
+}
+
+// 生成参数
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeneratorParams {
+    pub algorithm: Algorithm,
+    pub width: usize,
+    pub height: usize,
+    pub seed: u64,
+    pub coins: usize,
+    pub gems: usize,
+    pub post: PostProcess,
+}
+
+// 把尺寸调整为不小于5的奇数
+fn odd_size(n: usize) -> usize {
+    n.max(5) | 1
+}
+
+// 按参数生成网格；同样的参数总是得到同样的迷宫
+pub fn generate(params: &GeneratorParams) -> Vec<Vec<Cell>> {
+    let width = odd_size(params.width);
+    let height = odd_size(params.height);
+    let rng = RandGenerator::new();
+    rng.srand(params.seed);
+
+    // 在细墙迷宫上拆墙，再转换为方块网格
+    let mut maze = ThinMaze::new(width / 2, height / 2);
+    match params.algorithm {
+        Algorithm::Backtracker => carve_backtracker(&mut maze, &rng),
+        Algorithm::Prim => carve_prim(&mut maze, &rng),
+    }
+    braid(&mut maze, &rng, params.post.braid);
+    add_loops(&mut maze, &rng, params.post.loops);
+    let mut grid = maze.to_grid();
+
+    grid[1][1] = Cell::Start;
+    grid[height - 2][width - 2] = Cell::End;
+
+    scatter_items(&mut grid, &rng, Cell::Coin, params.coins);
+    scatter_items(&mut grid, &rng, Cell::Gem, params.gems);
+    grid
+}
+
+// 相邻的格子及其方向，顺序为上、下、左、右
+fn neighbors(maze: &ThinMaze, pos: Position) -> Vec<(Position, Facing)> {
+    [Facing::Up, Facing::Down, Facing::Left, Facing::Right]
+        .into_iter()
+        .filter_map(|dir| maze.step(pos, dir).map(|next| (next, dir)))
+        .collect()
+}
+
+// 递归回溯（用显式栈实现，避免大迷宫栈溢出）
+fn carve_backtracker(maze: &mut ThinMaze, rng: &RandGenerator) {
+    let mut visited = vec![vec![false; maze.width]; maze.height];
+    let origin = Position { x: 0, y: 0 };
+    let mut stack = vec![origin];
+    visited[0][0] = true;
+
+    while let Some(&pos) = stack.last() {
+        let unvisited: Vec<_> = neighbors(maze, pos)
+            .into_iter()
+            .filter(|(next, _)| !visited[next.y][next.x])
+            .collect();
+        if unvisited.is_empty() {
+            stack.pop();
+            continue;
+        }
+        let (next, dir) = unvisited[rng.gen_range(0, unvisited.len())];
+        maze.set_wall(pos, dir, false);
+        visited[next.y][next.x] = true;
+        stack.push(next);
+    }
+}
+
+// 随机 Prim：从已打通区域的边界上随机挑一个格子接入
+fn carve_prim(maze: &mut ThinMaze, rng: &RandGenerator) {
+    let mut carved = vec![vec![false; maze.width]; maze.height];
+    let origin = Position { x: 0, y: 0 };
+    carved[0][0] = true;
+    let mut frontier: Vec<Position> = neighbors(maze, origin).into_iter().map(|(p, _)| p).collect();
+
+    while !frontier.is_empty() {
+        let pos = frontier.swap_remove(rng.gen_range(0, frontier.len()));
+        if carved[pos.y][pos.x] {
+            continue;
+        }
+        let joins: Vec<_> = neighbors(maze, pos)
+            .into_iter()
+            .filter(|(next, _)| carved[next.y][next.x])
+            .collect();
+        let (_, dir) = joins[rng.gen_range(0, joins.len())];
+        maze.set_wall(pos, dir, false);
+        carved[pos.y][pos.x] = true;
+        frontier.extend(
+            neighbors(maze, pos)
+                .into_iter()
+                .map(|(p, _)| p)
+                .filter(|p| !carved[p.y][p.x]),
+        );
+    }
+}
+
+// 朝向上打开的方向数
+fn open_sides(maze: &ThinMaze, pos: Position) -> usize {
+    Facing::ALL.into_iter().filter(|&dir| maze.can_move(pos, dir).is_some()).count()
+}
+
+// 打通一部分死路：优先连到相邻的另一个死路，否则随机连到相邻格子。
+// 起点和终点所在的格子保持原样
+fn braid(maze: &mut ThinMaze, rng: &RandGenerator, fraction: f64) {
+    let last = Position { x: maze.width - 1, y: maze.height - 1 };
+    let mut dead_ends: Vec<Position> = (0..maze.height)
+        .flat_map(|y| (0..maze.width).map(move |x| Position { x, y }))
+        .filter(|&pos| pos != Position { x: 0, y: 0 } && pos != last && open_sides(maze, pos) == 1)
+        .collect();
+    let count = (dead_ends.len() as f64 * fraction.clamp(0.0, 1.0)).round() as usize;
+    for _ in 0..count {
+        let pos = dead_ends.swap_remove(rng.gen_range(0, dead_ends.len()));
+        // 之前打通别的死路时可能已经连上了
+        if open_sides(maze, pos) != 1 {
+            continue;
+        }
+        let closed: Vec<_> = neighbors(maze, pos)
+            .into_iter()
+            .filter(|&(_, dir)| maze.has_wall(pos, dir))
+            .collect();
+        let &(_, dir) = closed
+            .iter()
+            .find(|(next, _)| open_sides(maze, *next) == 1)
+            .unwrap_or_else(|| &closed[rng.gen_range(0, closed.len())]);
+        maze.set_wall(pos, dir, false);
+    }
+}
+
+// 随机拆掉若干面内墙，形成环路
+fn add_loops(maze: &mut ThinMaze, rng: &RandGenerator, count: usize) {
+    let mut walls: Vec<(Position, Facing)> = (0..maze.height)
+        .flat_map(|y| (0..maze.width).map(move |x| Position { x, y }))
+        .flat_map(|pos| [(pos, Facing::Right), (pos, Facing::Down)])
+        .filter(|&(pos, dir)| maze.step(pos, dir).is_some() && maze.has_wall(pos, dir))
+        .collect();
+    for _ in 0..count.min(walls.len()) {
+        let (pos, dir) = walls.swap_remove(rng.gen_range(0, walls.len()));
+        maze.set_wall(pos, dir, false);
+    }
+}
+
+// 在随机的空房间上放置物品（不放在房间之间的通道上，保证能转换为细墙迷宫）
+fn scatter_items(grid: &mut [Vec<Cell>], rng: &RandGenerator, item: Cell, count: usize) {
+    let mut free: Vec<(usize, usize)> = Vec::new();
+    for (y, row) in grid.iter().enumerate() {
+        for (x, &cell) in row.iter().enumerate() {
+            if cell == Cell::Empty && x % 2 == 1 && y % 2 == 1 {
+                free.push((x, y));
+            }
+        }
+    }
+    for _ in 0..count.min(free.len()) {
+        let (x, y) = free.swap_remove(rng.gen_range(0, free.len()));
+        grid[y][x] = item;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MazeGame;
+
+    fn params(algorithm: Algorithm) -> GeneratorParams {
+        GeneratorParams {
+            algorithm,
+            width: 20,
+            height: 14,
+            seed: 7,
+            coins: 3,
+            gems: 1,
+            post: PostProcess::default(),
+        }
+    }
+
+    #[test]
+    fn test_generated_mazes_are_solvable() {
+        for algorithm in [Algorithm::Backtracker, Algorithm::Prim] {
+            let grid = generate(&params(algorithm));
+            assert_eq!(grid.len(), 15);
+            assert_eq!(grid[0].len(), 21);
+            let game = MazeGame::from_grid(grid).unwrap();
+            assert!(game.find_shortest_path().is_some());
+            assert!(game.find_collect_route().is_some());
+            assert_eq!(game.coins_total, 3);
+            assert_eq!(game.gems_total, 1);
+        }
+    }
+
+    #[test]
+    fn test_post_processing_adds_routes() {
+        let params = params(Algorithm::Prim);
+        let count_dead_ends = |grid: &Vec<Vec<Cell>>| {
+            let maze = ThinMaze::from_grid(grid).unwrap();
+            (0..maze.height)
+                .flat_map(|y| (0..maze.width).map(move |x| Position { x, y }))
+                .filter(|&pos| open_sides(&maze, pos) == 1)
+                .count()
+        };
+        let plain = generate(&params);
+        let post = |braid, loops| GeneratorParams { post: PostProcess { braid, loops }, ..params.clone() };
+        let braided = generate(&post(1.0, 0));
+        assert!(count_dead_ends(&plain) > 2);
+        // 只剩起点和终点可能是死路
+        assert!(count_dead_ends(&braided) <= 2);
+
+        let looped = generate(&post(0.0, 5));
+        let open = |grid: &Vec<Vec<Cell>>| grid.iter().flatten().filter(|&&c| c != Cell::Wall).count();
+        assert_eq!(open(&looped), open(&plain) + 5);
+        assert!(MazeGame::from_grid(looped).unwrap().find_shortest_path().is_some());
+    }
+
+    #[test]
+    fn test_same_seed_same_maze() {
+        let a = generate(&params(Algorithm::Backtracker));
+        let b = generate(&params(Algorithm::Backtracker));
+        assert_eq!(a, b);
+    }
+}
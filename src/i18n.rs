@@ -0,0 +1,481 @@
+// 界面文字的多语言字符串表
+//
+// 每种语言一张表（一个 match），新增文字时编译器会提示补全所有语言。
+// 带参数的文字用 {} 占位，由 fill 依次填入。
+
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+// 界面语言
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    English,
+    Chinese,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::English, Language::Chinese];
+
+    // 语言自身的名称，设置界面中显示
+    pub fn native_name(self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::Chinese => "简体中文",
+        }
+    }
+}
+
+// 文字条目
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Text {
+    GameTitle,
+    PlayCampaign,
+    QuickPlay,
+    Resume,
+    RestartLevel,
+    NextLevel,
+    SelectLevel,
+    Settings,
+    BackToTitle,
+    Quit,
+    Back,
+    SettingsTitle,
+    SelectLevelTitle,
+    Paused,
+    LevelComplete,
+    CampaignComplete,
+    MazeSolved,
+    Locked,
+    Cleared,
+    NextLevelIn,
+    LevelTitle,
+    QuickPlayTitle,
+    HelpMove,
+    HelpReset,
+    ItemsStatus,
+    ExitLocked,
+    TimerStatus,
+    ResultStats,
+    HintRoute,
+    KeyRepeat,
+    QuickPlayWidth,
+    QuickPlayHeight,
+    Generator,
+    Theme,
+    CellSize,
+    SoundVolume,
+    LanguageLabel,
+    HintAuto,
+    HintShortest,
+    RepeatOff,
+    RepeatSlow,
+    RepeatFast,
+    Backtracker,
+    Prim,
+    MissingAssets,
+    WallStyle,
+    WallBlocks,
+    WallThin,
+    Shape,
+    ShapeSquare,
+    ShapeHex,
+    ShapeTriangle,
+    ShapePolar,
+    QuickPlayShapeTitle,
+    ShapeTorus,
+    ShapeFloors,
+    FloorStatus,
+    DiagonalHint,
+    FloorAbove,
+    FloorBelow,
+    WrapHint,
+    FirstPersonHelp,
+    Minimap,
+    MinimapSize,
+    Shown,
+    Hidden,
+    MetricsTitle,
+    MetricSolution,
+    MetricDecisions,
+    MetricDeadEnds,
+    MetricBranching,
+    MetricCorridor,
+    MetricRiver,
+    MetricDifficulty,
+    Unsolvable,
+    Braid,
+    Loops,
+    HintRoutes,
+    Editor,
+    EditorBrush,
+    EditorHelp,
+    EditorKeys,
+    EditorSolvable,
+    EditorSaved,
+    EditorLoaded,
+    BrushWall,
+    BrushFloor,
+    BrushStart,
+    BrushEnd,
+    BrushCoin,
+    BrushGem,
+    MusicVolume,
+    Mute,
+    On,
+    Off,
+    Trail,
+    Breadcrumbs,
+    Heatmap,
+    VisitStats,
+    HelpMarkers,
+    SolverTitle,
+    SolverDeadEndFill,
+    SolverTremaux,
+    SolverLeftHand,
+    SolverRightHand,
+    SolverPledge,
+    SolverFound,
+    SolverFailed,
+    SolverNoPath,
+    SolverLoopsRemain,
+    SolverWallLoop,
+    SolverPledgeLoop,
+    Race,
+    RaceHelp,
+    RacePlayerStatus,
+    RaceWinner,
+    RaceTitle,
+    OnlineHelp,
+    OnlineStatus,
+    OnlinePlayer,
+    OnlineWaiting,
+    OnlineWinner,
+    OnlineDisconnected,
+    AgentRandom,
+    AgentWallFollower,
+    AgentBfs,
+    AutoPlayAgent,
+    AutoPlaySpeed,
+    RequireAllItems,
+    AutoPlayStatus,
+}
+
+fn english(text: Text) -> &'static str {
+    match text {
+        Text::GameTitle => "Maze Game",
+        Text::PlayCampaign => "Play campaign",
+        Text::QuickPlay => "Quick play (random maze)",
+        Text::Resume => "Resume",
+        Text::RestartLevel => "Restart level",
+        Text::NextLevel => "Next level",
+        Text::SelectLevel => "Select level",
+        Text::Settings => "Settings",
+        Text::BackToTitle => "Back to title",
+        Text::Quit => "Quit",
+        Text::Back => "Back",
+        Text::SettingsTitle => "Settings (A/D to change, Esc to go back)",
+        Text::SelectLevelTitle => "Select level (Enter to play, Esc to go back)",
+        Text::Paused => "Paused",
+        Text::LevelComplete => "Level complete!",
+        Text::CampaignComplete => "Campaign complete!",
+        Text::MazeSolved => "Maze solved!",
+        Text::Locked => "[locked]",
+        Text::Cleared => "[cleared]",
+        Text::NextLevelIn => "Next level in {}s",
+        Text::LevelTitle => "Level {}/{}: {}",
+        Text::QuickPlayTitle => "Quick play: {}x{} {} (seed {})",
+        Text::HelpMove => "Use WASD to move, P to show/hide path, M for minimap (- and = to resize), B for trail, G to auto-play",
+        Text::HelpReset => "Press R to reset game, T to change theme, V for first-person view, I for maze stats, N to mute, Esc to pause",
+        Text::ItemsStatus => "Coins: {}/{}  Gems: {}/{}",
+        Text::ExitLocked => "(collect all to open the exit)",
+        Text::TimerStatus => "Time: {}s  Moves: {}",
+        Text::ResultStats => "Time: {}s   Moves: {}   Coins: {}/{}   Gems: {}/{}",
+        Text::HintRoute => "Hint route",
+        Text::KeyRepeat => "Key repeat",
+        Text::QuickPlayWidth => "Quick play width",
+        Text::QuickPlayHeight => "Quick play height",
+        Text::Generator => "Generator",
+        Text::Theme => "Theme",
+        Text::CellSize => "Cell size",
+        Text::SoundVolume => "Sound effects volume",
+        Text::LanguageLabel => "Language",
+        Text::HintAuto => "Auto",
+        Text::HintShortest => "Shortest",
+        Text::RepeatOff => "Off",
+        Text::RepeatSlow => "Slow",
+        Text::RepeatFast => "Fast",
+        Text::Backtracker => "Backtracker",
+        Text::Prim => "Prim",
+        Text::MissingAssets => "Missing assets: {}",
+        Text::WallStyle => "Walls",
+        Text::WallBlocks => "Blocks",
+        Text::WallThin => "Thin lines",
+        Text::Shape => "Quick play shape",
+        Text::ShapeSquare => "Square",
+        Text::ShapeHex => "Hexagons",
+        Text::ShapeTriangle => "Triangles",
+        Text::ShapePolar => "Circular",
+        Text::QuickPlayShapeTitle => "Quick play: {} maze (seed {})",
+        Text::ShapeTorus => "Wrapping",
+        Text::ShapeFloors => "Multi-floor",
+        Text::FloorStatus => "Floor {}/{}  (on stairs: E up, Q down)",
+        Text::DiagonalHint => "Q, E, Z and C move diagonally",
+        Text::FloorAbove => "Floor above",
+        Text::FloorBelow => "Floor below",
+        Text::WrapHint => "Edges wrap around to the opposite side",
+        Text::FirstPersonHelp => "W/S: forward/back, A/D: turn, P: hint, M: minimap, V: top-down view",
+        Text::Minimap => "Minimap",
+        Text::MinimapSize => "Minimap size",
+        Text::Shown => "Shown",
+        Text::Hidden => "Hidden",
+        Text::MetricsTitle => "Maze stats (I to hide)",
+        Text::MetricSolution => "Solution length: {}",
+        Text::MetricDecisions => "Decisions on the solution: {}",
+        Text::MetricDeadEnds => "Dead ends: {}",
+        Text::MetricBranching => "Branching factor: {}",
+        Text::MetricCorridor => "Longest corridor: {}",
+        Text::MetricRiver => "River factor: {}",
+        Text::MetricDifficulty => "Difficulty: {} / 10",
+        Text::Unsolvable => "This maze has no solution",
+        Text::Braid => "Quick play dead ends removed",
+        Text::Loops => "Quick play extra loops",
+        Text::HintRoutes => "Hint routes shown",
+        Text::Editor => "Maze Editor",
+        Text::EditorBrush => "Brush: {}  (1 wall, 2 floor, 3 start, 4 end, 5 coin, 6 gem)",
+        Text::EditorHelp => "Left drag to paint, right drag to erase, arrows to resize ({} x {})",
+        Text::EditorKeys => "Ctrl+Z/Y undo/redo, Ctrl+S save, Ctrl+L load, N new, P path, Esc back",
+        Text::EditorSolvable => "Solvable in {} steps",
+        Text::EditorSaved => "Saved to {}",
+        Text::EditorLoaded => "Loaded {}",
+        Text::BrushWall => "Wall",
+        Text::BrushFloor => "Floor",
+        Text::BrushStart => "Start",
+        Text::BrushEnd => "End",
+        Text::BrushCoin => "Coin",
+        Text::BrushGem => "Gem",
+        Text::MusicVolume => "Music volume",
+        Text::Mute => "Mute",
+        Text::On => "On",
+        Text::Off => "Off",
+        Text::Trail => "Visited cells",
+        Text::Breadcrumbs => "Breadcrumbs",
+        Text::Heatmap => "Heatmap",
+        Text::HelpMarkers => "F or left click to mark a cell, right click to clear, X to mark explored dead ends, L for solver demos",
+        Text::SolverTitle => "Solver",
+        Text::SolverDeadEndFill => "Dead-end filling",
+        Text::SolverTremaux => "Trémaux's algorithm",
+        Text::SolverLeftHand => "Left-hand wall follower",
+        Text::SolverRightHand => "Right-hand wall follower",
+        Text::SolverPledge => "Pledge algorithm",
+        Text::SolverFound => "Reached the exit",
+        Text::SolverFailed => "Failed to reach the exit",
+        Text::SolverNoPath => "The exit cannot be reached from here",
+        Text::SolverLoopsRemain => "Loops remain: dead-end filling cannot rule out routes that form a cycle",
+        Text::SolverWallLoop => "Walked in a circle: the exit is on an island not connected to the followed wall",
+        Text::SolverPledgeLoop => "Walked in a circle: Pledge finds ways out to the outer wall, not goals inside the maze",
+        Text::VisitStats => "Cells visited: {}  Revisited: {}  Most visits: {}",
+        Text::Race => "Two-player race",
+        Text::RaceHelp => "Player 1: WASD  Player 2: arrow keys  V: shared/split view  R: restart  Esc: pause",
+        Text::RacePlayerStatus => "Player {}: {} s, {} moves",
+        Text::RaceWinner => "Player {} wins!",
+        Text::RaceTitle => "Two-player race: {}x{} (seed {})",
+        Text::OnlineHelp => "Online race  WASD: move  Esc: leave",
+        Text::OnlineStatus => "Round {}  Players: {}",
+        Text::OnlinePlayer => "{}: {} moves",
+        Text::OnlineWaiting => "Waiting for the server...",
+        Text::OnlineWinner => "{} reached the exit in {} moves ({} s). The next round starts soon.",
+        Text::OnlineDisconnected => "Disconnected from the server",
+        Text::AgentRandom => "Random walk",
+        Text::AgentWallFollower => "Wall follower",
+        Text::AgentBfs => "BFS explorer",
+        Text::AutoPlayAgent => "Auto-play agent",
+        Text::AutoPlaySpeed => "Auto-play speed (moves/s)",
+        Text::RequireAllItems => "Exit needs all items",
+        Text::AutoPlayStatus => "Auto-play: {} (G or a move key to stop)",
+    }
+}
+
+fn chinese(text: Text) -> &'static str {
+    match text {
+        Text::GameTitle => "迷宫游戏",
+        Text::PlayCampaign => "战役模式",
+        Text::QuickPlay => "快速游戏（随机迷宫）",
+        Text::Resume => "继续游戏",
+        Text::RestartLevel => "重新开始本关",
+        Text::NextLevel => "下一关",
+        Text::SelectLevel => "选择关卡",
+        Text::Settings => "设置",
+        Text::BackToTitle => "返回标题",
+        Text::Quit => "退出",
+        Text::Back => "返回",
+        Text::SettingsTitle => "设置（A/D 修改，Esc 返回）",
+        Text::SelectLevelTitle => "选择关卡（回车开始，Esc 返回）",
+        Text::Paused => "已暂停",
+        Text::LevelComplete => "过关！",
+        Text::CampaignComplete => "战役全部通关！",
+        Text::MazeSolved => "成功走出迷宫！",
+        Text::Locked => "[未解锁]",
+        Text::Cleared => "[已通关]",
+        Text::NextLevelIn => "{} 秒后进入下一关",
+        Text::LevelTitle => "第 {}/{} 关：{}",
+        Text::QuickPlayTitle => "快速游戏：{}x{} {}（种子 {}）",
+        Text::HelpMove => "WASD 移动，P 显示/隐藏路径，M 小地图（- 和 = 调整大小），B 走过的路线，G 自动游戏",
+        Text::HelpReset => "R 重置本关，T 切换主题，V 第一人称视图，I 迷宫数据，N 静音，Esc 暂停",
+        Text::ItemsStatus => "金币：{}/{}  宝石：{}/{}",
+        Text::ExitLocked => "（收集全部物品后终点开启）",
+        Text::TimerStatus => "用时：{} 秒  步数：{}",
+        Text::ResultStats => "用时：{} 秒   步数：{}   金币：{}/{}   宝石：{}/{}",
+        Text::HintRoute => "提示路线",
+        Text::KeyRepeat => "按键连发",
+        Text::QuickPlayWidth => "快速游戏宽度",
+        Text::QuickPlayHeight => "快速游戏高度",
+        Text::Generator => "生成算法",
+        Text::Theme => "主题",
+        Text::CellSize => "格子大小",
+        Text::SoundVolume => "音效音量",
+        Text::LanguageLabel => "语言",
+        Text::HintAuto => "自动",
+        Text::HintShortest => "最短路线",
+        Text::RepeatOff => "关闭",
+        Text::RepeatSlow => "慢",
+        Text::RepeatFast => "快",
+        Text::Backtracker => "递归回溯",
+        Text::Prim => "Prim 算法",
+        Text::MissingAssets => "缺少资源：{}",
+        Text::WallStyle => "墙壁样式",
+        Text::WallBlocks => "方块",
+        Text::WallThin => "细线",
+        Text::Shape => "快速游戏形状",
+        Text::ShapeSquare => "方格",
+        Text::ShapeHex => "六边形",
+        Text::ShapeTriangle => "三角形",
+        Text::ShapePolar => "圆形",
+        Text::QuickPlayShapeTitle => "快速游戏：{}迷宫（种子 {}）",
+        Text::ShapeTorus => "环绕",
+        Text::ShapeFloors => "多层",
+        Text::FloorStatus => "第 {}/{} 层（站在楼梯上：E 上楼，Q 下楼）",
+        Text::DiagonalHint => "Q、E、Z、C 斜向移动",
+        Text::FloorAbove => "楼上",
+        Text::FloorBelow => "楼下",
+        Text::WrapHint => "从边缘走出会从对面进入",
+        Text::FirstPersonHelp => "W/S 前进/后退，A/D 转向，P 提示路径，M 小地图，V 俯视图",
+        Text::Minimap => "小地图",
+        Text::MinimapSize => "小地图大小",
+        Text::Shown => "显示",
+        Text::Hidden => "隐藏",
+        Text::MetricsTitle => "迷宫数据（按 I 隐藏）",
+        Text::MetricSolution => "最短路线长度：{}",
+        Text::MetricDecisions => "路线上的路口：{}",
+        Text::MetricDeadEnds => "死路：{}",
+        Text::MetricBranching => "分支系数：{}",
+        Text::MetricCorridor => "最长走廊：{}",
+        Text::MetricRiver => "河流系数：{}",
+        Text::MetricDifficulty => "难度：{} / 10",
+        Text::Unsolvable => "这个迷宫无解",
+        Text::Braid => "快速游戏打通死路",
+        Text::Loops => "快速游戏额外环路",
+        Text::HintRoutes => "提示路线条数",
+        Text::Editor => "迷宫编辑器",
+        Text::EditorBrush => "画笔：{}（1 墙，2 空地，3 起点，4 终点，5 金币，6 宝石）",
+        Text::EditorHelp => "左键拖动绘制，右键拖动擦除，方向键调整尺寸（{} x {}）",
+        Text::EditorKeys => "Ctrl+Z/Y 撤销/重做，Ctrl+S 保存，Ctrl+L 读取，N 新建，P 路线，Esc 返回",
+        Text::EditorSolvable => "可以走通，需要 {} 步",
+        Text::EditorSaved => "已保存到 {}",
+        Text::EditorLoaded => "已读取 {}",
+        Text::BrushWall => "墙",
+        Text::BrushFloor => "空地",
+        Text::BrushStart => "起点",
+        Text::BrushEnd => "终点",
+        Text::BrushCoin => "金币",
+        Text::BrushGem => "宝石",
+        Text::MusicVolume => "音乐音量",
+        Text::Mute => "静音",
+        Text::On => "开",
+        Text::Off => "关",
+        Text::Trail => "走过的格子",
+        Text::Breadcrumbs => "面包屑",
+        Text::Heatmap => "热力图",
+        Text::HelpMarkers => "F 或左键在格子上做记号，右键清除，X 标出已探明的死路，L 演示解法",
+        Text::SolverTitle => "解法",
+        Text::SolverDeadEndFill => "死路填充",
+        Text::SolverTremaux => "Trémaux 算法",
+        Text::SolverLeftHand => "左手扶墙",
+        Text::SolverRightHand => "右手扶墙",
+        Text::SolverPledge => "Pledge 算法",
+        Text::SolverFound => "到达了终点",
+        Text::SolverFailed => "没能到达终点",
+        Text::SolverNoPath => "从这里无法到达终点",
+        Text::SolverLoopsRemain => "仍有环路：死路填充无法排除构成环的路线",
+        Text::SolverWallLoop => "在绕圈：终点在一座与所扶的墙不相连的岛上",
+        Text::SolverPledgeLoop => "在绕圈：Pledge 算法用于走到外墙出口，找不到迷宫内部的终点",
+        Text::VisitStats => "到过 {} 格  重复 {} 格  最多 {} 次",
+        Text::Race => "双人竞速",
+        Text::RaceHelp => "玩家一：WASD  玩家二：方向键  V：同屏/分屏  R：重新开始  Esc：暂停",
+        Text::RacePlayerStatus => "玩家{}：{} 秒，{} 步",
+        Text::RaceWinner => "玩家{}获胜！",
+        Text::RaceTitle => "双人竞速：{}x{}（种子 {}）",
+        Text::OnlineHelp => "联网竞速  WASD：移动  Esc：离开",
+        Text::OnlineStatus => "第 {} 轮  玩家 {} 人",
+        Text::OnlinePlayer => "{}：{} 步",
+        Text::OnlineWaiting => "正在等待服务器……",
+        Text::OnlineWinner => "{} 用 {} 步（{} 秒）到达终点，下一轮即将开始",
+        Text::OnlineDisconnected => "与服务器的连接已断开",
+        Text::AgentRandom => "随机游走",
+        Text::AgentWallFollower => "扶墙走",
+        Text::AgentBfs => "BFS 探索",
+        Text::AutoPlayAgent => "自动游戏的智能体",
+        Text::AutoPlaySpeed => "自动游戏速度（步/秒）",
+        Text::RequireAllItems => "收集全部物品才开启终点",
+        Text::AutoPlayStatus => "自动游戏：{}（按 G 或移动键停止）",
+    }
+}
+
+// 取某种语言下的文字
+pub fn tr(language: Language, text: Text) -> &'static str {
+    match language {
+        Language::English => english(text),
+        Language::Chinese => chinese(text),
+    }
+}
+
+// 依次用参数替换模板中的 {}
+pub fn fill(template: &str, args: &[&dyn Display]) -> String {
+    let mut result = String::with_capacity(template.len());
+    let mut args = args.iter();
+    let mut rest = template;
+    while let Some(index) = rest.find("{}") {
+        result.push_str(&rest[..index]);
+        match args.next() {
+            Some(arg) => result.push_str(&arg.to_string()),
+            None => result.push_str("{}"),
+        }
+        rest = &rest[index + 2..];
+    }
+    result.push_str(rest);
+    result
+}
+
+// 是否包含需要中文字体才能显示的字符
+pub fn needs_cjk_font(text: &str) -> bool {
+    text.chars().any(|c| c >= '\u{2E80}')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fill_placeholders() {
+        assert_eq!(fill("{}/{}", &[&1, &"b"]), "1/b");
+        assert_eq!(fill("第 {} 关", &[&3]), "第 3 关");
+        // 参数不足时保留占位符
+        assert_eq!(fill("{} {}", &[&1]), "1 {}");
+    }
+
+    #[test]
+    fn test_needs_cjk_font() {
+        assert!(!needs_cjk_font("Maze Game"));
+        assert!(needs_cjk_font("迷宫游戏"));
+    }
+}
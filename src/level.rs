@@ -0,0 +1,426 @@
+// 关卡与战役进度
+//
+// 关卡清单是一个简单的分段文本文件，每个 [level] 段描述一个关卡，# 开头的行是注释：
+//
+//   [level]
+//   name = Warm-up
+//   # 可选的中文名称
+//   name.zh = 热身
+//   # 内置的经典布局
+//   maze = classic
+//
+//   [level]
+//   name = 手绘迷宫
+//   # 文本迷宫文件（相对清单所在目录）
+//   file = spiral.txt
+//
+//   [level]
+//   name = 森林
+//   # 随机生成：backtracker / prim
+//   generator = backtracker
+//   size = 31x21
+//   seed = 3
+//   coins = 4
+//   gems = 1
+//   # 可选：打通的死路百分比和额外拆掉的墙数（形成环路）
+//   braid = 30
+//   loops = 5
+//   # 收集全部物品后终点才开启（默认 true）
+//   require_all = true
+//
+// 值中的 # 是普通字符，例如 name = Level #3。
+
+use crate::assets;
+use crate::generator::{self, Algorithm, GeneratorParams, PostProcess};
+use crate::i18n::{tr, Language, Text};
+use crate::MazeGame;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+// 内置战役清单，找不到外部清单时使用
+const BUILTIN_MANIFEST: &str = include_str!("../levels/campaign.txt");
+
+// 关卡来源
+#[derive(Debug, Clone, PartialEq)]
+pub enum LevelSource {
+    Classic,
+    File(PathBuf),
+    Generated(GeneratorParams),
+}
+
+// 关卡定义
+#[derive(Debug, Clone, PartialEq)]
+pub struct LevelDef {
+    pub name: String,
+    pub name_zh: Option<String>,
+    pub source: LevelSource,
+    pub require_all_items: bool,
+}
+
+impl LevelDef {
+    // 按语言取关卡名称，没有对应翻译时用默认名称
+    pub fn display_name(&self, language: Language) -> &str {
+        match (language, &self.name_zh) {
+            (Language::Chinese, Some(name)) => name,
+            _ => &self.name,
+        }
+    }
+
+    // 根据定义构建游戏
+    pub fn build(&self) -> Result<MazeGame, String> {
+        let mut game = match &self.source {
+            LevelSource::Classic => MazeGame::new(20, 15),
+            LevelSource::File(path) => MazeGame::from_text(&read_maze_file(path)?)?,
+            LevelSource::Generated(params) => MazeGame::from_grid(generator::generate(params))?,
+        };
+        game.require_all_items = self.require_all_items;
+        Ok(game)
+    }
+}
+
+// 读取迷宫文件；磁盘上没有时使用同名的内嵌资源（内置战役的关卡）
+fn read_maze_file(path: &Path) -> Result<String, String> {
+    std::fs::read_to_string(path).or_else(|e| {
+        assets::embedded(&path.to_string_lossy().replace('\\', "/"))
+            .map(|bytes| String::from_utf8_lossy(bytes).into_owned())
+            .ok_or_else(|| format!("无法读取迷宫文件 {}: {}", path.display(), e))
+    })
+}
+
+// 解析关卡清单，文件路径相对 base_dir
+pub fn parse_manifest(text: &str, base_dir: &Path) -> Result<Vec<LevelDef>, String> {
+    // 先按 [level] 分段收集键值
+    let mut sections: Vec<Vec<(usize, String, String)>> = Vec::new();
+    for (index, raw) in text.lines().enumerate() {
+        let line_no = index + 1;
+        let line = raw.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if line == "[level]" {
+            sections.push(Vec::new());
+            continue;
+        }
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| format!("第 {} 行: 应为 key = value", line_no))?;
+        let section = sections
+            .last_mut()
+            .ok_or_else(|| format!("第 {} 行: 缺少 [level] 段头", line_no))?;
+        section.push((line_no, key.trim().to_string(), value.trim().to_string()));
+    }
+
+    sections
+        .into_iter()
+        .enumerate()
+        .map(|(i, entries)| parse_level(i, &entries, base_dir))
+        .collect()
+}
+
+fn parse_level(index: usize, entries: &[(usize, String, String)], base_dir: &Path) -> Result<LevelDef, String> {
+    let mut name = format!("Level {}", index + 1);
+    let mut name_zh = None;
+    let mut source = None;
+    let mut algorithm = None;
+    let mut size = (21, 15);
+    let mut seed = index as u64 + 1;
+    let mut coins = 0;
+    let mut gems = 0;
+    let mut post = PostProcess::default();
+    let mut require_all_items = true;
+
+    for (line_no, key, value) in entries {
+        let bad = || format!("第 {} 行: {} 的值无效: {}", line_no, key, value);
+        match key.as_str() {
+            "name" => name = value.clone(),
+            "name.zh" => name_zh = Some(value.clone()),
+            "maze" if value == "classic" => source = Some(LevelSource::Classic),
+            "maze" => return Err(format!("第 {} 行: 未知的 maze 值 {}", line_no, value)),
+            "file" => source = Some(LevelSource::File(base_dir.join(value))),
+            "generator" => algorithm = Some(Algorithm::parse(value).ok_or_else(bad)?),
+            "size" => {
+                let (w, h) = value.split_once('x').ok_or_else(bad)?;
+                size = (w.trim().parse().map_err(|_| bad())?, h.trim().parse().map_err(|_| bad())?);
+            }
+            "seed" => seed = value.parse().map_err(|_| bad())?,
+            "coins" => coins = value.parse().map_err(|_| bad())?,
+            "gems" => gems = value.parse().map_err(|_| bad())?,
+            "braid" => {
+                let percent: u32 = value.trim_end_matches('%').parse().map_err(|_| bad())?;
+                post.braid = percent.min(100) as f64 / 100.0;
+            }
+            "loops" => post.loops = value.parse().map_err(|_| bad())?,
+            "require_all" => require_all_items = value.parse().map_err(|_| bad())?,
+            _ => return Err(format!("第 {} 行: 未知的键 {}", line_no, key)),
+        }
+    }
+
+    if let Some(algorithm) = algorithm {
+        source = Some(LevelSource::Generated(GeneratorParams {
+            algorithm,
+            width: size.0,
+            height: size.1,
+            seed,
+            coins,
+            gems,
+            post,
+        }));
+    }
+
+    Ok(LevelDef {
+        name,
+        name_zh,
+        source: source.ok_or_else(|| format!("关卡 {} 缺少 maze/file/generator", index + 1))?,
+        require_all_items,
+    })
+}
+
+// 保存到磁盘的战役进度（与 settings.json 同一目录的 progress.json），关卡按序号记录
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+struct Progress {
+    current: usize,
+    unlocked: usize,
+    completed: BTreeSet<usize>,
+}
+
+// 战役：关卡序列与解锁进度
+pub struct Campaign {
+    pub levels: Vec<LevelDef>,
+    pub current: usize,
+    pub unlocked: usize,            // 已解锁的关卡数（至少为1）
+    pub completed: BTreeSet<usize>, // 通关过的关卡
+}
+
+impl Campaign {
+    pub fn new(levels: Vec<LevelDef>) -> Self {
+        Campaign {
+            levels,
+            current: 0,
+            unlocked: 1,
+            completed: BTreeSet::new(),
+        }
+    }
+
+    // 读取外部清单，失败时退回内置战役
+    pub fn load_or_builtin(path: Option<&Path>) -> Self {
+        let external = path.and_then(|path| {
+            let text = std::fs::read_to_string(path).ok()?;
+            let base_dir = path.parent().unwrap_or(Path::new("."));
+            parse_manifest(&text, base_dir)
+                .map_err(|e| eprintln!("关卡清单 {} 有误: {}", path.display(), e))
+                .ok()
+        });
+        match external {
+            Some(levels) if !levels.is_empty() => Campaign::new(levels),
+            _ => Campaign::builtin(),
+        }
+    }
+
+    // 内置战役；读不到的文件关卡保留在列表中（进度按序号记录），但在这里报告出来，
+    // 游玩时 build_current 会退回经典布局
+    pub fn builtin() -> Self {
+        let levels = parse_manifest(BUILTIN_MANIFEST, Path::new("levels")).expect("内置关卡清单应当有效");
+        for level in &levels {
+            if let LevelSource::File(path) = &level.source {
+                if let Err(e) = read_maze_file(path) {
+                    eprintln!("内置关卡 \"{}\" 不可用: {}", level.name, e);
+                }
+            }
+        }
+        Campaign::new(levels)
+    }
+
+    // 套用进度，超出关卡数的部分舍去
+    fn restore(&mut self, progress: Progress) {
+        let len = self.levels.len();
+        self.unlocked = progress.unlocked.clamp(1, len);
+        self.current = progress.current.min(self.unlocked - 1);
+        self.completed = progress.completed.into_iter().filter(|&i| i < len).collect();
+    }
+
+    // 重新读取关卡清单（资源热重载），保留已解锁的进度
+    pub fn reload(&mut self, path: Option<&Path>) {
+        let mut campaign = Campaign::load_or_builtin(path);
+        let completed = std::mem::take(&mut self.completed);
+        campaign.restore(Progress { current: self.current, unlocked: self.unlocked, completed });
+        *self = campaign;
+    }
+
+    // 读取保存的进度；文件不存在时保持初始进度，无法解析时提示并忽略
+    pub fn load_progress(&mut self, path: &Path) {
+        let Ok(text) = std::fs::read_to_string(path) else {
+            return;
+        };
+        match serde_json::from_str(&text) {
+            Ok(progress) => self.restore(progress),
+            Err(e) => eprintln!("进度文件 {} 无效，从头开始: {}", path.display(), e),
+        }
+    }
+
+    pub fn save_progress(&self, path: &Path) -> Result<(), String> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let progress = Progress { current: self.current, unlocked: self.unlocked, completed: self.completed.clone() };
+        let text = serde_json::to_string_pretty(&progress).map_err(|e| e.to_string())?;
+        std::fs::write(path, text).map_err(|e| format!("无法写入 {}: {}", path.display(), e))
+    }
+
+    pub fn current_level(&self) -> &LevelDef {
+        &self.levels[self.current]
+    }
+
+    // 构建当前关卡，失败时退回经典布局
+    pub fn build_current(&self) -> MazeGame {
+        self.current_level().build().unwrap_or_else(|e| {
+            eprintln!("关卡 \"{}\" 加载失败: {}", self.current_level().name, e);
+            MazeGame::new(20, 15)
+        })
+    }
+
+    pub fn is_last(&self) -> bool {
+        self.current + 1 >= self.levels.len()
+    }
+
+    // 通关当前关卡，解锁下一关
+    pub fn complete_current(&mut self) {
+        self.completed.insert(self.current);
+        self.unlocked = self.unlocked.max((self.current + 2).min(self.levels.len()));
+    }
+
+    // 前进到下一关；已是最后一关时返回 false
+    pub fn advance(&mut self) -> bool {
+        if self.is_last() {
+            return false;
+        }
+        self.complete_current();
+        self.current += 1;
+        true
+    }
+
+    // 选择关卡（只能选择已解锁的）
+    pub fn select(&mut self, index: usize) -> bool {
+        if index < self.unlocked {
+            self.current = index;
+            true
+        } else {
+            false
+        }
+    }
+
+    // 关卡选择界面中显示的条目
+    pub fn level_labels(&self, language: Language) -> Vec<String> {
+        self.levels
+            .iter()
+            .enumerate()
+            .map(|(i, level)| {
+                let name = level.display_name(language);
+                if self.completed.contains(&i) {
+                    format!("{}. {}  {}", i + 1, name, tr(language, Text::Cleared))
+                } else if i < self.unlocked {
+                    format!("{}. {}", i + 1, name)
+                } else {
+                    format!("{}. {}  {}", i + 1, name, tr(language, Text::Locked))
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_manifest() {
+        let text = "
+            # 注释
+            [level]
+            name = Warm-up #1
+            name.zh = 热身
+            maze = classic
+
+            [level]
+            generator = prim
+            size = 15x11
+            seed = 4
+            coins = 2
+            braid = 40%
+            loops = 3
+            require_all = false
+
+            [level]
+            name = Custom
+            file = custom.txt
+        ";
+        let levels = parse_manifest(text, Path::new("levels")).unwrap();
+        assert_eq!(levels.len(), 3);
+        assert_eq!(levels[0].source, LevelSource::Classic);
+        assert_eq!(levels[0].display_name(Language::Chinese), "热身");
+        assert_eq!(levels[0].name, "Warm-up #1");
+        assert_eq!(levels[1].display_name(Language::Chinese), "Level 2");
+        assert_eq!(levels[1].name, "Level 2");
+        assert!(!levels[1].require_all_items);
+        match &levels[1].source {
+            LevelSource::Generated(params) => {
+                assert_eq!(params.algorithm, Algorithm::Prim);
+                assert_eq!((params.width, params.height, params.seed, params.coins), (15, 11, 4, 2));
+                assert_eq!(params.post, PostProcess { braid: 0.4, loops: 3 });
+            }
+            other => panic!("unexpected source {:?}", other),
+        }
+        assert_eq!(levels[2].source, LevelSource::File(PathBuf::from("levels/custom.txt")));
+    }
+
+    #[test]
+    fn test_manifest_errors() {
+        assert!(parse_manifest("name = x", Path::new(".")).is_err());
+        assert!(parse_manifest("[level]\nsize = 10", Path::new(".")).is_err());
+        assert!(parse_manifest("[level]\nname = empty", Path::new(".")).is_err());
+        let unknown = parse_manifest("[level]\nmaze = fancy", Path::new(".")).unwrap_err();
+        assert!(unknown.contains("未知的 maze 值"), "{}", unknown);
+    }
+
+    #[test]
+    fn test_progression_unlocks_levels() {
+        let mut campaign = Campaign::builtin();
+        assert!(campaign.levels.len() > 1);
+        // 文件关卡也在内置战役中
+        assert!(campaign.levels.iter().any(|level| matches!(level.source, LevelSource::File(_))));
+        assert!(!campaign.select(1));
+        assert!(campaign.advance());
+        assert_eq!(campaign.current, 1);
+        assert!(campaign.select(0));
+        assert!(campaign.select(1));
+        assert!(campaign.level_labels(Language::English)[2].ends_with("[locked]"));
+        for level in &campaign.levels {
+            assert!(level.build().is_ok(), "内置关卡 {} 应能构建", level.name);
+        }
+
+        // 重新加载后进度保留
+        campaign.reload(None);
+        assert_eq!((campaign.current, campaign.unlocked), (1, 2));
+        assert!(campaign.level_labels(Language::English)[0].ends_with("[cleared]"));
+    }
+
+    #[test]
+    fn test_progress_saved_and_restored() {
+        let path = std::env::temp_dir().join(format!("maze_game_progress_{}/progress.json", std::process::id()));
+        let mut campaign = Campaign::builtin();
+        campaign.advance();
+        campaign.complete_current();
+        campaign.save_progress(&path).unwrap();
+
+        let mut restored = Campaign::builtin();
+        restored.load_progress(&path);
+        assert_eq!((restored.current, restored.unlocked), (1, 3));
+        assert_eq!(restored.completed, BTreeSet::from([0, 1]));
+
+        // 关卡变少时进度截断到现有关卡
+        let mut short = Campaign::new(restored.levels[..1].to_vec());
+        short.load_progress(&path);
+        assert_eq!((short.current, short.unlocked, short.completed.len()), (0, 1, 1));
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
@@ -1,6 +1,46 @@
 use macroquad::prelude::*;
 use std::collections::{VecDeque, HashSet};
 
+mod agents;
+mod assets;
+mod audio;
+mod cli;
+mod editor;
+mod effects;
+mod env;
+mod generator;
+mod i18n;
+mod level;
+mod markers;
+mod menu;
+mod metrics;
+mod minimap;
+mod net;
+mod online;
+mod race;
+mod raycast;
+mod route;
+mod scene;
+mod settings;
+mod solvers;
+mod target;
+mod theme;
+mod thin;
+mod topology;
+mod trail;
+mod woff;
+
+use assets::Assets;
+use audio::Audio;
+use level::Campaign;
+use markers::Marker;
+use scene::App;
+use settings::{HintMode, Settings};
+use i18n::{fill, tr, Language, Text};
+use theme::{Atlas, Fonts, Palette, Sprite, Style, Themes};
+use thin::ThinMaze;
+use trail::TrailOverlay;
+
 // 迷宫单元格类型
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum Cell {
@@ -8,8 +48,9 @@ enum Cell {
     Wall,     // 墙
     Start,    // 起点
     End,      // 终点
-    Path,     // 路径标记
-    Player,   // 玩家
+    Path,     // 走过的空地
+    Coin,     // 金币
+    Gem,      // 宝石
 }
 
 // 位置结构体
@@ -19,6 +60,26 @@ struct Position {
     y: usize,
 }
 
+// 玩家朝向，贴图主题中决定玩家贴图
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Facing {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Facing {
+    fn from_delta(dx: i32, dy: i32) -> Self {
+        match (dx, dy) {
+            (0, d) if d < 0 => Facing::Up,
+            (d, _) if d > 0 => Facing::Right,
+            (d, _) if d < 0 => Facing::Left,
+            _ => Facing::Down,
+        }
+    }
+}
+
 // 迷宫游戏结构体
 struct MazeGame {
     grid: Vec<Vec<Cell>>,
@@ -30,9 +91,67 @@ struct MazeGame {
     show_path: bool,
     path_positions: Vec<Position>,
     game_won: bool,
+    coins_total: usize,
+    gems_total: usize,
+    coins_collected: usize,
+    gems_collected: usize,
+    require_all_items: bool, // 为真时必须收集全部物品终点才会开启
+    initial_grid: Vec<Vec<Cell>>, // 关卡初始网格，用于重置
+    elapsed: f32, // 本关已用时间（秒），暂停时不计时
+    moves: usize,
+    hint_mode: HintMode,
+    facing: Facing,
+    thin: Option<ThinMaze>, // 细墙模式下由方块网格转换得到，无法转换的关卡为 None
+    explored: Vec<Vec<bool>>, // 玩家看到过的格子，小地图只显示这些
+    hint_routes: usize, // 提示最短路线时显示的路线条数
+    alt_paths: Vec<Vec<Position>>, // 最短路线以外的其他路线，用不同颜色显示
+    visits: Vec<Vec<u32>>, // 每个格子的到访次数
+    trail: Vec<Position>,  // 按顺序走过的格子，包括起点
+    trail_overlay: TrailOverlay,
+    markers: Vec<Vec<Option<Marker>>>, // 玩家做的记号
+}
+
+// 其他路线的颜色，按路线长度依次使用
+const ALT_PATH_COLORS: [Color; 3] = [
+    Color { a: 0.75, ..ORANGE },
+    Color { a: 0.75, ..VIOLET },
+    Color { a: 0.75, ..SKYBLUE },
+];
+
+fn alt_path_color(index: usize) -> Color {
+    ALT_PATH_COLORS[index % ALT_PATH_COLORS.len()]
+}
+
+// 将迷宫文本字符转换为单元格
+fn cell_from_char(ch: char) -> Option<Cell> {
+    match ch {
+        '#' => Some(Cell::Wall),
+        ' ' | '.' => Some(Cell::Empty),
+        'S' => Some(Cell::Start),
+        'E' => Some(Cell::End),
+        '$' => Some(Cell::Coin),
+        '*' => Some(Cell::Gem),
+        _ => None,
+    }
+}
+
+// 网格转换为迷宫文本（cell_from_char 的逆过程），走过的格子写成空地
+fn grid_to_text(grid: &[Vec<Cell>]) -> String {
+    let char_of = |cell| match cell {
+        Cell::Wall => '#',
+        Cell::Start => 'S',
+        Cell::End => 'E',
+        Cell::Coin => '$',
+        Cell::Gem => '*',
+        Cell::Empty | Cell::Path => ' ',
+    };
+    grid.iter()
+        .map(|row| row.iter().map(|&cell| char_of(cell)).collect::<String>() + "\n")
+        .collect()
 }
 
 impl MazeGame {
+    #[allow(clippy::needless_range_loop)]
     fn new(width: usize, height: usize) -> Self {
         let mut grid = vec![vec![Cell::Empty; width]; height];
         
@@ -56,13 +175,13 @@ impl MazeGame {
             "# # ### # ### # ### #",
             "# #   # #   # #   # #",
             "# ##### ##### ##### #",
-            "# #   #     #     # #",
+            "# #$  #  *  #     # #",
             "# # # ##### # ##### #",
-            "# # #     # #     # #",
+            "# # #     # #   $ # #",
             "# # ##### # ##### # #",
-            "# #     # #     # # #",
+            "# #     # #  *  # # #",
             "# ##### # ##### # # #",
-            "#       #       #   E#",
+            "#   $   #       #   E#",
             "####################",
         ];
         
@@ -70,12 +189,7 @@ impl MazeGame {
         for (y, line) in maze_layout.iter().enumerate() {
             for (x, ch) in line.chars().enumerate() {
                 if y < height && x < width {
-                    grid[y][x] = match ch {
-                        '#' => Cell::Wall,
-                        'S' => Cell::Start,
-                        'E' => Cell::End,
-                        _ => Cell::Empty,
-                    };
+                    grid[y][x] = cell_from_char(ch).unwrap_or(Cell::Empty);
                 }
             }
         }
@@ -88,16 +202,95 @@ impl MazeGame {
         grid[start_pos.y][start_pos.x] = Cell::Start;
         grid[end_pos.y][end_pos.x] = Cell::End;
         
+        Self::build(grid, start_pos, end_pos)
+    }
+    
+    // 从网格创建游戏，网格中必须恰好有一个起点和一个终点
+    fn from_grid(grid: Vec<Vec<Cell>>) -> Result<Self, String> {
+        let height = grid.len();
+        let width = grid.first().map_or(0, |row| row.len());
+        if width < 3 || height < 3 || grid.iter().any(|row| row.len() != width) {
+            return Err("迷宫必须是至少 3x3 的矩形".to_string());
+        }
+        
+        let find = |target: Cell| -> Result<Position, String> {
+            let found: Vec<Position> = grid
+                .iter()
+                .enumerate()
+                .flat_map(|(y, row)| {
+                    row.iter()
+                        .enumerate()
+                        .filter(move |&(_, &c)| c == target)
+                        .map(move |(x, _)| Position { x, y })
+                })
+                .collect();
+            match found.as_slice() {
+                [pos] => Ok(*pos),
+                _ => Err(format!("迷宫中应恰好有一个 {:?}，实际有 {} 个", target, found.len())),
+            }
+        };
+        let start_pos = find(Cell::Start)?;
+        let end_pos = find(Cell::End)?;
+        
+        Ok(Self::build(grid, start_pos, end_pos))
+    }
+    
+    // 从文本迷宫创建游戏：# 墙，空格或 . 空地，S 起点，E 终点，$ 金币，* 宝石
+    // 较短的行用墙补齐
+    fn from_text(text: &str) -> Result<Self, String> {
+        let lines: Vec<&str> = text.lines().filter(|line| !line.trim().is_empty()).collect();
+        let width = lines.iter().map(|line| line.chars().count()).max().unwrap_or(0);
+        
+        let mut grid = Vec::with_capacity(lines.len());
+        for (y, line) in lines.iter().enumerate() {
+            let mut row = Vec::with_capacity(width);
+            for (x, ch) in line.chars().enumerate() {
+                let cell = cell_from_char(ch)
+                    .ok_or_else(|| format!("第 {} 行第 {} 列: 无法识别的字符 {:?}", y + 1, x + 1, ch))?;
+                row.push(cell);
+            }
+            row.resize(width, Cell::Wall);
+            grid.push(row);
+        }
+        
+        Self::from_grid(grid)
+    }
+    
+    fn build(grid: Vec<Vec<Cell>>, start_pos: Position, end_pos: Position) -> Self {
+        // 统计可收集物品
+        let count = |target: Cell| grid.iter().flatten().filter(|&&c| c == target).count();
+        let coins_total = count(Cell::Coin);
+        let gems_total = count(Cell::Gem);
+        
+        let (width, height) = (grid[0].len(), grid.len());
         let mut game = MazeGame {
+            initial_grid: grid.clone(),
+            width,
+            height,
             grid,
             player_pos: start_pos,
             start_pos,
             end_pos,
-            width,
-            height,
             show_path: false,
             path_positions: Vec::new(),
             game_won: false,
+            coins_total,
+            gems_total,
+            coins_collected: 0,
+            gems_collected: 0,
+            require_all_items: true,
+            elapsed: 0.0,
+            moves: 0,
+            hint_mode: HintMode::Auto,
+            facing: Facing::Down,
+            thin: None,
+            explored: vec![vec![false; width]; height],
+            hint_routes: 1,
+            alt_paths: Vec::new(),
+            visits: vec![vec![0; width]; height],
+            trail: Vec::new(),
+            trail_overlay: TrailOverlay::Off,
+            markers: vec![vec![None; width]; height],
         };
         
         game.update_player_position(start_pos);
@@ -106,16 +299,23 @@ impl MazeGame {
     
     // 更新玩家位置
     fn update_player_position(&mut self, new_pos: Position) {
-        // 清除旧位置（如果是起点则恢复为起点，否则恢复为空地）
-        if self.player_pos == self.start_pos {
-            self.grid[self.player_pos.y][self.player_pos.x] = Cell::Start;
-        } else {
-            self.grid[self.player_pos.y][self.player_pos.x] = Cell::Empty;
+        // 收集物品；空地和物品格子标记为走过。玩家只记录在 player_pos 中，
+        // 不写入网格，这样同一张网格上可以有多个玩家
+        let cell = &mut self.grid[new_pos.y][new_pos.x];
+        match *cell {
+            Cell::Coin => self.coins_collected += 1,
+            Cell::Gem => self.gems_collected += 1,
+            _ => {}
+        }
+        if matches!(*cell, Cell::Empty | Cell::Coin | Cell::Gem) {
+            *cell = Cell::Path;
         }
         
         // 设置新位置
         self.player_pos = new_pos;
-        self.grid[new_pos.y][new_pos.x] = Cell::Player;
+        self.visits[new_pos.y][new_pos.x] += 1;
+        self.trail.push(new_pos);
+        self.reveal();
         
         // 检查是否获胜
         if self.player_pos == self.end_pos {
@@ -123,16 +323,53 @@ impl MazeGame {
         }
     }
     
-    // 碰撞检测
-    fn can_move(&self, pos: Position) -> bool {
+    // 记录玩家能看到的格子：周围一圈，以及四个方向上直到墙为止的走廊
+    fn reveal(&mut self) {
+        let Position { x, y } = self.player_pos;
+        for ny in y.saturating_sub(1)..(y + 2).min(self.height) {
+            for nx in x.saturating_sub(1)..(x + 2).min(self.width) {
+                self.explored[ny][nx] = true;
+            }
+        }
+        for facing in Facing::ALL {
+            let (dx, dy) = facing.delta();
+            let (mut cx, mut cy) = (x as i32 + dx, y as i32 + dy);
+            while cx >= 0 && cy >= 0 && (cx as usize) < self.width && (cy as usize) < self.height {
+                self.explored[cy as usize][cx as usize] = true;
+                if self.grid[cy as usize][cx as usize] == Cell::Wall {
+                    break;
+                }
+                cx += dx;
+                cy += dy;
+            }
+        }
+    }
+    
+    // 剩余未收集的物品数量
+    fn items_remaining(&self) -> usize {
+        (self.coins_total - self.coins_collected) + (self.gems_total - self.gems_collected)
+    }
+    
+    // 终点是否仍被锁住
+    fn exit_locked(&self) -> bool {
+        self.require_all_items && self.items_remaining() > 0
+    }
+    
+    // 是否为可通行的格子（不考虑终点锁）
+    fn is_open(&self, pos: Position) -> bool {
         if pos.x >= self.width || pos.y >= self.height {
             return false;
         }
         
-        match self.grid[pos.y][pos.x] {
-            Cell::Wall => false,
-            _ => true,
+        self.grid[pos.y][pos.x] != Cell::Wall
+    }
+    
+    // 碰撞检测
+    fn can_move(&self, pos: Position) -> bool {
+        if pos == self.end_pos && self.exit_locked() {
+            return false;
         }
+        self.is_open(pos)
     }
     
     // 移动玩家
@@ -140,6 +377,21 @@ impl MazeGame {
         if self.game_won {
             return false;
         }
+        self.facing = Facing::from_delta(dx, dy);
+        
+        // 细墙模式：一步从一个格子走到相邻格子（方块网格中的两格）
+        if let (Some(thin), Some(room)) = (&self.thin, thin::from_block(self.player_pos)) {
+            let Some(next) = thin.can_move(room, self.facing) else {
+                return false;
+            };
+            let new_pos = thin::to_block(next);
+            if !self.can_move(new_pos) {
+                return false;
+            }
+            self.update_player_position(new_pos);
+            self.moves += 1;
+            return true;
+        }
         
         let new_x = self.player_pos.x as i32 + dx;
         let new_y = self.player_pos.y as i32 + dy;
@@ -152,6 +404,7 @@ impl MazeGame {
             
             if new_pos.x < self.width && new_pos.y < self.height && self.can_move(new_pos) {
                 self.update_player_position(new_pos);
+                self.moves += 1;
                 return true;
             }
         }
@@ -163,27 +416,42 @@ impl MazeGame {
         self.game_won
     }
     
+    // 推进计时，获胜后停止
+    fn tick(&mut self, dt: f32) {
+        if !self.game_won {
+            self.elapsed += dt;
+        }
+    }
+    
     // 使用BFS寻找最短路径
     fn find_shortest_path(&self) -> Option<Vec<Position>> {
+        self.path_from(self.start_pos)
+    }
+    
+    // 从任意位置到终点的最短路径（不含出发点）
+    fn path_from(&self, from: Position) -> Option<Vec<Position>> {
+        if let Some(thin) = &self.thin {
+            let start = thin::from_block(from)?;
+            let to = thin::from_block(self.end_pos)?;
+            return thin.shortest_path(start, to).map(|rooms| thin::path_to_blocks(&rooms));
+        }
+        let parent = self.bfs_parents(from);
+        Self::trace_path(&parent, from, self.end_pos)
+    }
+    
+    // 从 from 出发做BFS，返回每个格子的前驱
+    // 终点只能作为目的地进入，不能被穿过（踏上终点即获胜）
+    fn bfs_parents(&self, from: Position) -> Vec<Vec<Option<Position>>> {
         let mut queue = VecDeque::new();
         let mut visited = HashSet::new();
         let mut parent = vec![vec![None; self.width]; self.height];
         
-        queue.push_back(self.start_pos);
-        visited.insert(self.start_pos);
+        queue.push_back(from);
+        visited.insert(from);
         
         while let Some(current) = queue.pop_front() {
-            if current == self.end_pos {
-                // 重建路径
-                let mut path = Vec::new();
-                let mut step = current;
-                
-                while step != self.start_pos {
-                    path.push(step);
-                    step = parent[step.y][step.x].unwrap();
-                }
-                path.reverse();
-                return Some(path);
+            if current == self.end_pos && current != from {
+                continue;
             }
             
             // 检查四个方向
@@ -204,10 +472,7 @@ impl MazeGame {
                         y: y as usize 
                     };
                     
-                    if new_pos.x < self.width && new_pos.y < self.height 
-                        && self.can_move(new_pos) 
-                        && !visited.contains(&new_pos) {
-                        
+                    if self.is_open(new_pos) && !visited.contains(&new_pos) {
                         visited.insert(new_pos);
                         parent[new_pos.y][new_pos.x] = Some(current);
                         queue.push_back(new_pos);
@@ -216,21 +481,117 @@ impl MazeGame {
             }
         }
         
-        None
+        parent
+    }
+    
+    // 根据前驱表重建 from 到 to 的路径（不含 from）
+    fn trace_path(parent: &[Vec<Option<Position>>], from: Position, to: Position) -> Option<Vec<Position>> {
+        let mut path = Vec::new();
+        let mut step = to;
+        
+        while step != from {
+            path.push(step);
+            step = parent[step.y][step.x]?;
+        }
+        path.reverse();
+        Some(path)
+    }
+    
+    // 所有尚未收集的物品位置
+    fn remaining_item_positions(&self) -> Vec<Position> {
+        let mut items = Vec::new();
+        for (y, row) in self.grid.iter().enumerate() {
+            for (x, &cell) in row.iter().enumerate() {
+                if cell == Cell::Coin || cell == Cell::Gem {
+                    items.push(Position { x, y });
+                }
+            }
+        }
+        items
+    }
+    
+    // 从玩家当前位置出发，先收集全部剩余物品再前往终点的最短路线
+    fn find_collect_route(&self) -> Option<Vec<Position>> {
+        let mut points = vec![self.player_pos];
+        points.extend(self.remaining_item_positions());
+        points.push(self.end_pos);
+        
+        // 每个兴趣点做一次BFS，得到两两之间的路径
+        let parents: Vec<_> = points.iter().map(|&p| self.bfs_parents(p)).collect();
+        let legs: Vec<Vec<Option<Vec<Position>>>> = points
+            .iter()
+            .enumerate()
+            .map(|(i, &from)| {
+                points
+                    .iter()
+                    .map(|&to| Self::trace_path(&parents[i], from, to))
+                    .collect()
+            })
+            .collect();
+        let dist: Vec<Vec<Option<usize>>> = legs
+            .iter()
+            .map(|row| row.iter().map(|leg| leg.as_ref().map(|l| l.len())).collect())
+            .collect();
+        
+        let order = route::plan_visit_order(&dist)?;
+        
+        // 按访问顺序拼接各段路径
+        let mut route = Vec::new();
+        let mut current = 0;
+        for next in order.into_iter().chain(std::iter::once(points.len() - 1)) {
+            route.extend(legs[current][next].iter().flatten());
+            current = next;
+        }
+        Some(route)
     }
     
     // 显示路径
     fn display_path(&mut self) {
-        if let Some(path) = self.find_shortest_path() {
+        // 终点因物品未收集而锁住时，提示"先收集再出门"的路线
+        let collect = self.hint_mode == HintMode::Auto && self.exit_locked();
+        let path = if collect {
+            self.find_collect_route()
+        } else {
+            self.find_shortest_path()
+        };
+        if let Some(path) = path {
             self.path_positions = path;
             self.show_path = true;
         }
+        self.alt_paths = if collect { Vec::new() } else { self.alternative_paths() };
+    }
+    
+    // 最短路线以外的其他路线（不含起点），按长度排列，共 hint_routes - 1 条
+    fn alternative_paths(&self) -> Vec<Vec<Position>> {
+        if self.hint_routes <= 1 {
+            return Vec::new();
+        }
+        // 与 bfs_parents 一致：终点不能被穿过
+        let neighbors = |pos: Position| {
+            if pos == self.end_pos {
+                return Vec::new();
+            }
+            [(0, -1), (0, 1), (-1, 0), (1, 0)]
+                .into_iter()
+                .filter_map(|(dx, dy)| {
+                    let next = Position { x: pos.x.checked_add_signed(dx)?, y: pos.y.checked_add_signed(dy)? };
+                    self.is_open(next).then_some(next)
+                })
+                .collect()
+        };
+        route::k_shortest_paths(self.start_pos, self.end_pos, self.hint_routes, neighbors)
+            .into_iter()
+            .map(|path| path[1..].to_vec())
+            .filter(|path| *path != self.path_positions)
+            .take(self.hint_routes - 1)
+            .collect()
     }
     
     // 清除路径显示
     fn clear_path(&mut self) {
         self.show_path = false;
         self.path_positions.clear();
+        self.alt_paths.clear();
     }
     
     // 切换路径显示
@@ -242,156 +603,341 @@ impl MazeGame {
         }
     }
     
-    // 重置游戏
+    // 重置游戏（回到本关初始状态）
     fn reset_game(&mut self) {
-        *self = MazeGame::new(self.width, self.height);
+        let require_all_items = self.require_all_items;
+        let hint_mode = self.hint_mode;
+        let hint_routes = self.hint_routes;
+        let trail_overlay = self.trail_overlay;
+        let thin = self.thin.take();
+        *self = Self::build(self.initial_grid.clone(), self.start_pos, self.end_pos);
+        self.require_all_items = require_all_items;
+        self.hint_mode = hint_mode;
+        self.hint_routes = hint_routes;
+        self.trail_overlay = trail_overlay;
+        self.thin = thin;
+    }
+    
+    // 根据窗口大小计算格子边长，给底部文字留出空间
+    fn cell_size(&self, max_cell_size: f32) -> f32 {
+        const HUD_HEIGHT: f32 = 165.0;
+        let fit_w = screen_width() / self.width as f32;
+        let fit_h = (screen_height() - HUD_HEIGHT) / self.height as f32;
+        max_cell_size.min(fit_w).min(fit_h).max(4.0)
+    }
+    
+    // 切换细墙模式；当前关卡无法转换为细墙迷宫时保持方块显示
+    fn set_thin_walls(&mut self, enabled: bool) {
+        self.thin = if enabled {
+            ThinMaze::from_grid(&self.initial_grid).ok()
+        } else {
+            None
+        };
+        if self.show_path {
+            self.display_path();
+        }
+    }
+    
+    // 四周相邻的墙（上=1、右=2、下=4、左=8），迷宫边界外视为墙
+    fn wall_mask(&self, x: usize, y: usize) -> usize {
+        let is_wall = |dx: i32, dy: i32| {
+            let nx = x as i32 + dx;
+            let ny = y as i32 + dy;
+            nx < 0
+                || ny < 0
+                || nx as usize >= self.width
+                || ny as usize >= self.height
+                || self.grid[ny as usize][nx as usize] == Cell::Wall
+        };
+        [(0, -1), (1, 0), (0, 1), (-1, 0)]
+            .iter()
+            .enumerate()
+            .filter(|(_, &(dx, dy))| is_wall(dx, dy))
+            .map(|(bit, _)| 1 << bit)
+            .sum()
+    }
+    
+    // 用主题图集绘制迷宫
+    fn render_tiles(&self, atlas: &Atlas, palette: &Palette, cell_size: f32) {
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let pos_x = x as f32 * cell_size;
+                let pos_y = y as f32 * cell_size;
+                if self.grid[y][x] == Cell::Wall {
+                    atlas.draw_wall(self.wall_mask(x, y), pos_x, pos_y, cell_size);
+                    continue;
+                }
+                atlas.draw_floor(x, y, pos_x, pos_y, cell_size);
+                let sprite = match self.grid[y][x] {
+                    Cell::Start => Sprite::Start,
+                    Cell::End if self.exit_locked() => Sprite::EndLocked,
+                    Cell::End => Sprite::End,
+                    Cell::Coin => Sprite::Coin,
+                    Cell::Gem => Sprite::Gem,
+                    _ => continue,
+                };
+                atlas.draw_sprite(sprite, pos_x, pos_y, cell_size);
+            }
+        }
+        
+        trail::render(self, palette, cell_size);
+        markers::render(self, cell_size);
+        for (i, path) in self.alt_paths.iter().enumerate() {
+            for &pos in path {
+                draw_rectangle(pos.x as f32 * cell_size, pos.y as f32 * cell_size, cell_size, cell_size, alt_path_color(i));
+            }
+        }
+        if self.show_path {
+            for &pos in &self.path_positions {
+                if pos != self.player_pos {
+                    atlas.draw_sprite(Sprite::Path, pos.x as f32 * cell_size, pos.y as f32 * cell_size, cell_size);
+                }
+            }
+        }
+        
+        let player_x = self.player_pos.x as f32 * cell_size;
+        let player_y = self.player_pos.y as f32 * cell_size;
+        atlas.draw_player(self.facing as usize, player_x, player_y, cell_size);
     }
     
     // 渲染游戏
-    fn render(&self, font: Option<&Font>) {
-        const CELL_SIZE: f32 = 30.0;
+    fn render(&self, style: &Style) {
+        let cell_size = self.cell_size(style.max_cell_size);
+        self.render_board(style, cell_size);
+        self.render_hud(style, cell_size);
+    }
+    
+    // 只画迷宫（包括玩家和各种叠加显示），左上角为原点
+    fn render_board(&self, style: &Style, cell_size: f32) {
+        let palette = style.palette;
+        if let Some(thin) = &self.thin {
+            self.render_thin(thin, palette, cell_size);
+        } else if let Some(atlas) = style.atlas {
+            self.render_tiles(atlas, palette, cell_size);
+        } else {
+            self.render_flat(palette, cell_size);
+        }
+    }
+    
+    // 细墙模式：格子占方块网格的两格，墙画成线段
+    fn render_thin(&self, thin: &ThinMaze, palette: &Palette, cell_size: f32) {
+        let center = |pos: Position| vec2(pos.x as f32 + 0.5, pos.y as f32 + 0.5) * cell_size;
+        draw_rectangle(0.0, 0.0, self.width as f32 * cell_size, self.height as f32 * cell_size, palette.floor);
         
-        // 绘制网格
         for y in 0..self.height {
             for x in 0..self.width {
-                let pos_x = x as f32 * CELL_SIZE;
-                let pos_y = y as f32 * CELL_SIZE;
-                
-                // 跳过玩家位置，稍后单独绘制
-                if self.grid[y][x] == Cell::Player {
-                    continue;
+                let c = center(Position { x, y });
+                // 起点和终点占满整个格子（方块网格的两格）
+                let room = |color| {
+                    let half = cell_size * 0.8;
+                    draw_rectangle(c.x - half, c.y - half, half * 2.0, half * 2.0, color)
+                };
+                match self.grid[y][x] {
+                    Cell::Start => room(palette.start),
+                    Cell::End if self.exit_locked() => room(palette.end_locked),
+                    Cell::End => room(palette.end),
+                    Cell::Coin => draw_circle(c.x, c.y, cell_size * 0.45, palette.coin),
+                    Cell::Gem => draw_poly(c.x, c.y, 4, cell_size * 0.55, 0.0, palette.gem),
+                    _ => {}
                 }
+            }
+        }
+        
+        trail::render(self, palette, cell_size);
+        markers::render(self, cell_size);
+        
+        // 路径画成连接格子中心的折线，其他路线画得细一些
+        for (i, path) in self.alt_paths.iter().enumerate() {
+            let mut prev = center(self.start_pos);
+            for &pos in path {
+                let next = center(pos);
+                draw_line(prev.x, prev.y, next.x, next.y, cell_size * 0.25, alt_path_color(i));
+                prev = next;
+            }
+        }
+        if self.show_path {
+            let mut prev = center(self.player_pos);
+            for &pos in &self.path_positions {
+                let next = center(pos);
+                draw_line(prev.x, prev.y, next.x, next.y, cell_size * 0.4, palette.path);
+                prev = next;
+            }
+        }
+        
+        let player = center(self.player_pos);
+        draw_circle(player.x, player.y, cell_size * 0.6, palette.player);
+        
+        let origin = vec2(cell_size * 0.5, cell_size * 0.5);
+        thin.render(origin, cell_size * 2.0, (cell_size * 0.3).max(2.0), palette.wall);
+    }
+    
+    // 无图集时用纯色方块绘制迷宫
+    fn render_flat(&self, palette: &Palette, cell_size: f32) {
+        // 绘制网格
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let pos_x = x as f32 * cell_size;
+                let pos_y = y as f32 * cell_size;
                 
                 let color = match self.grid[y][x] {
-                    Cell::Wall => DARKGRAY,
-                    Cell::Empty => LIGHTGRAY,
-                    Cell::Start => GREEN,
-                    Cell::End => RED,
-                    Cell::Path => LIGHTGRAY,
-                    Cell::Player => BLUE,
+                    Cell::Wall => palette.wall,
+                    Cell::Empty => palette.floor,
+                    Cell::Start => palette.start,
+                    Cell::End if self.exit_locked() => palette.end_locked,
+                    Cell::End => palette.end,
+                    Cell::Path => palette.floor,
+                    Cell::Coin | Cell::Gem => palette.floor,
                 };
                 
-                draw_rectangle(pos_x, pos_y, CELL_SIZE, CELL_SIZE, color);
+                draw_rectangle(pos_x, pos_y, cell_size, cell_size, color);
+                
+                // 绘制可收集物品
+                let center_x = pos_x + cell_size / 2.0;
+                let center_y = pos_y + cell_size / 2.0;
+                match self.grid[y][x] {
+                    Cell::Coin => draw_circle(center_x, center_y, cell_size * 0.25, palette.coin),
+                    Cell::Gem => draw_poly(center_x, center_y, 4, cell_size * 0.3, 0.0, palette.gem),
+                    _ => {}
+                }
                 
                 // 绘制网格线
-                draw_rectangle_lines(pos_x, pos_y, CELL_SIZE, CELL_SIZE, 1.0, BLACK);
+                draw_rectangle_lines(pos_x, pos_y, cell_size, cell_size, 1.0, palette.grid_line);
             }
         }
         
-        // 绘制路径
+        trail::render(self, palette, cell_size);
+        markers::render(self, cell_size);
+        
+        // 绘制路径：先画其他路线，与最短路线重合的部分被覆盖
+        for (i, path) in self.alt_paths.iter().enumerate() {
+            for &pos in path.iter().filter(|&&pos| pos != self.player_pos) {
+                draw_rectangle(pos.x as f32 * cell_size, pos.y as f32 * cell_size, cell_size, cell_size, alt_path_color(i));
+            }
+        }
         if self.show_path {
             for &pos in &self.path_positions {
                 // 跳过玩家所在的位置，避免覆盖玩家
                 if pos == self.player_pos {
                     continue;
                 }
-                let pos_x = pos.x as f32 * CELL_SIZE;
-                let pos_y = pos.y as f32 * CELL_SIZE;
-                draw_rectangle(pos_x, pos_y, CELL_SIZE, CELL_SIZE, YELLOW);
+                let pos_x = pos.x as f32 * cell_size;
+                let pos_y = pos.y as f32 * cell_size;
+                draw_rectangle(pos_x, pos_y, cell_size, cell_size, palette.path);
             }
         }
         
         // 最后绘制玩家，确保它在最上层
-        let player_pos_x = self.player_pos.x as f32 * CELL_SIZE;
-        let player_pos_y = self.player_pos.y as f32 * CELL_SIZE;
-        draw_rectangle(player_pos_x, player_pos_y, CELL_SIZE, CELL_SIZE, BLUE);
-        
-        // 绘制文本说明
+        let player_pos_x = self.player_pos.x as f32 * cell_size;
+        let player_pos_y = self.player_pos.y as f32 * cell_size;
+        draw_rectangle(player_pos_x, player_pos_y, cell_size, cell_size, palette.player);
+    }
+    
+    // 物品收集情况、用时和步数，两种视图共用
+    fn status_lines(&self, language: Language) -> [String; 2] {
+        let t = |text| tr(language, text);
+        let mut items_status = fill(
+            t(Text::ItemsStatus),
+            &[&self.coins_collected, &self.coins_total, &self.gems_collected, &self.gems_total],
+        );
+        if self.exit_locked() {
+            items_status = format!("{}  {}", items_status, t(Text::ExitLocked));
+        }
+        let timer_status = fill(t(Text::TimerStatus), &[&format!("{:.1}", self.elapsed), &self.moves]);
+        [items_status, timer_status]
+    }
+    
+    // 绘制迷宫下方的文本说明
+    fn render_hud(&self, style: &Style, cell_size: f32) {
+        let t = |text| tr(style.language, text);
+        let [items_status, timer_status] = self.status_lines(style.language);
         let instructions = [
-            "Use WASD to move",
-            "Press P to show/hide path",
-            "Press R to reset game",
+            t(Text::HelpMove),
+            t(Text::HelpReset),
+            t(Text::HelpMarkers),
+            items_status.as_str(),
+            timer_status.as_str(),
         ];
         
         for (i, instruction) in instructions.iter().enumerate() {
-            if let Some(font) = font {
-                draw_text_ex(
-                    instruction,
-                    10.0,
-                    (self.height as f32 * CELL_SIZE) + 30.0 + (i as f32 * 25.0),
-                    TextParams {
-                        font: Some(font),
-                        font_size: 20,
-                        color: BLACK,
-                        ..Default::default()
-                    },
-                );
-            } else {
-                draw_text(
-                    instruction,
-                    10.0,
-                    (self.height as f32 * CELL_SIZE) + 30.0 + (i as f32 * 25.0),
-                    20.0,
-                    BLACK,
-                );
-            }
+            draw_label(
+                instruction,
+                10.0,
+                (self.height as f32 * cell_size) + 30.0 + (i as f32 * 25.0),
+                style,
+            );
         }
-        
-        if self.game_won {
-            let win_message = "Congratulations! You won! Press R to restart";
-            if let Some(font) = font {
-                draw_text_ex(
-                    win_message,
-                    10.0,
-                    (self.height as f32 * CELL_SIZE) + 30.0 + (3 as f32 * 25.0),
-                    TextParams {
-                        font: Some(font),
-                        font_size: 20,
-                        color: BLACK,
-                        ..Default::default()
-                    },
-                );
-            } else {
-                draw_text(
-                    win_message,
-                    10.0,
-                    (self.height as f32 * CELL_SIZE) + 30.0 + (3 as f32 * 25.0),
-                    20.0,
-                    BLACK,
-                );
-            }
+    }
+}
+
+// 绘制一行说明文字，有字体时使用字体
+fn draw_label(text: &str, x: f32, y: f32, style: &Style) {
+    let color = style.palette.text;
+    if let Some(font) = style.font_for(text) {
+        draw_text_ex(
+            text,
+            x,
+            y,
+            TextParams {
+                font: Some(font),
+                font_size: 20,
+                color,
+                ..Default::default()
+            },
+        );
+    } else {
+        draw_text(text, x, y, 20.0, color);
+    }
+}
+
+// 带参数运行时是命令行工具（见 cli.rs），否则打开游戏窗口；join 在窗口中加入联网竞速
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let join = match cli::parse_join(&args) {
+        Some(Ok(join)) => Some(join),
+        Some(Err(e)) => {
+            eprintln!("{}", e);
+            std::process::exit(2);
+        }
+        None => None,
+    };
+    if join.is_none() {
+        if let Some(code) = cli::run(&args) {
+            std::process::exit(code);
         }
     }
+    macroquad::Window::new("Maze Game", run_game(join));
 }
 
-#[macroquad::main("Maze Game")]
-async fn main() {
-    let width = 20;
-    let height = 15;
-    
-    let mut game = MazeGame::new(width, height);
+async fn run_game(join: Option<cli::Join>) {
+    // 先读取设置，再创建游戏
+    let settings_path = settings::config_path();
+    let settings = settings_path.as_deref().map(Settings::load).unwrap_or_default();
+
+    // 资源按可执行文件位置查找，不依赖当前工作目录
+    let mut assets = Assets::new();
+    let campaign = Campaign::load_or_builtin(assets.watch(assets::CAMPAIGN).as_deref());
+    let fonts = Fonts::load(&mut assets);
+    let themes = Themes::load(&mut assets);
+    let audio = Audio::load().await;
     
-    // 尝试加载字体
-    let font = load_ttf_font("assets/FiraSans-Regular.ttf").await.ok();
+    let mut app = App::new(campaign, settings, settings_path, assets, fonts, themes, audio);
+    if let Some(join) = join {
+        match net::Client::connect(&join.addr, &join.name) {
+            Ok(client) => app.join_online(client),
+            Err(e) => {
+                eprintln!("{}", e);
+                return;
+            }
+        }
+    }
     
     loop {
-        clear_background(WHITE);
-        
-        // 处理输入
-        if is_key_pressed(KeyCode::P) {
-            game.toggle_path();
-        }
-        
-        if is_key_pressed(KeyCode::R) {
-            game.reset_game();
-        }
+        clear_background(app.background());
         
-        // 按键移动处理
-        if is_key_pressed(KeyCode::W) {
-            game.move_player(0, -1);
-        }
-        if is_key_pressed(KeyCode::S) {
-            game.move_player(0, 1);
-        }
-        if is_key_pressed(KeyCode::A) {
-            game.move_player(-1, 0);
-        }
-        if is_key_pressed(KeyCode::D) {
-            game.move_player(1, 0);
+        if !app.update() {
+            break;
         }
-        
-        // 渲染游戏
-        game.render(font.as_ref());
+        app.render();
         
         next_frame().await
     }
@@ -404,7 +950,8 @@ mod tests {
     #[test]
     fn test_maze_creation() {
         let game = MazeGame::new(20, 15);
-        assert_eq!(game.grid[1][1], Cell::Player);
+        assert_eq!(game.grid[1][1], Cell::Start);
+        assert_eq!(game.player_pos, game.start_pos);
         assert_eq!(game.grid[13][18], Cell::End);
     }
 
@@ -415,10 +962,162 @@ mod tests {
         assert!(game.can_move(Position { x: 1, y: 2 }));
     }
 
+    #[test]
+    fn test_facing_and_wall_mask() {
+        let mut game = MazeGame::from_text("#####\n#S  #\n# #E#\n#####\n").unwrap();
+        assert_eq!(game.facing, Facing::Down);
+        // 撞墙也会转向
+        assert!(!game.move_player(0, -1));
+        assert_eq!(game.facing, Facing::Up);
+        assert!(game.move_player(1, 0));
+        assert_eq!(game.facing, Facing::Right);
+
+        // 角落：右侧和下方是墙，边界外也算墙
+        assert_eq!(game.wall_mask(0, 0), 1 | 2 | 4 | 8);
+        assert_eq!(game.wall_mask(2, 2), 4);
+        assert_eq!(game.wall_mask(1, 0), 1 | 2 | 8);
+    }
+
+    #[test]
+    fn test_thin_walls_move_between_rooms() {
+        let params = generator::GeneratorParams {
+            algorithm: generator::Algorithm::Backtracker,
+            width: 15,
+            height: 11,
+            seed: 4,
+            coins: 0,
+            gems: 0,
+            post: generator::PostProcess::default(),
+        };
+        let mut game = MazeGame::from_grid(generator::generate(&params)).unwrap();
+        let block_path = game.find_shortest_path().unwrap();
+        game.set_thin_walls(true);
+        assert!(game.thin.is_some());
+        // 细墙上的最短路径与方块网格上的一致
+        assert_eq!(game.find_shortest_path().unwrap(), block_path);
+
+        // 每步走过两个方块
+        let rooms: Vec<Position> = block_path.iter().copied().filter(|&p| thin::from_block(p).is_some()).collect();
+        for room in rooms {
+            let dx = room.x as i32 - game.player_pos.x as i32;
+            let dy = room.y as i32 - game.player_pos.y as i32;
+            assert!(game.move_player(dx.signum(), dy.signum()));
+            assert_eq!(game.player_pos, room);
+        }
+        assert!(game.has_won());
+
+        // 经典布局无法转换，仍按方块显示
+        let mut classic = MazeGame::new(20, 15);
+        classic.set_thin_walls(true);
+        assert!(classic.thin.is_none());
+    }
+
     #[test]
     fn test_path_finding() {
         let game = MazeGame::new(20, 15);
         let path = game.find_shortest_path();
         assert!(path.is_some(), "应该能找到路径");
     }
+
+    #[test]
+    fn test_alternative_routes() {
+        // 上下两条路线到终点，下面的更长
+        let mut game = MazeGame::from_text("#######\n#S   E#\n#.###.#\n#.....#\n#######").unwrap();
+        game.hint_routes = 3;
+        game.hint_mode = HintMode::Shortest;
+        game.toggle_path();
+        assert_eq!(game.path_positions.len(), 4);
+        assert_eq!(game.alt_paths.len(), 1);
+        assert_eq!(game.alt_paths[0].len(), 8);
+        assert_eq!(game.alt_paths[0].last(), Some(&game.end_pos));
+        game.toggle_path();
+        assert!(game.alt_paths.is_empty());
+
+        // 自动模式下终点锁住时提示收集路线，不显示其他路线；关闭锁后与最短路线模式相同
+        let mut game = MazeGame::from_text("#######\n#S   E#\n#.###.#\n#..$..#\n#######").unwrap();
+        game.hint_routes = 3;
+        game.toggle_path();
+        assert_eq!(game.path_positions.len(), 8);
+        assert!(game.alt_paths.is_empty());
+        game.toggle_path();
+        game.require_all_items = false;
+        game.toggle_path();
+        assert_eq!(game.path_positions.len(), 4);
+        assert_eq!(game.alt_paths.len(), 1);
+    }
+
+    #[test]
+    fn test_from_text() {
+        let text = "#####\n#S$ #\n# #E#\n#####\n";
+        let game = MazeGame::from_text(text).unwrap();
+        assert_eq!((game.width, game.height), (5, 4));
+        assert_eq!(game.start_pos, Position { x: 1, y: 1 });
+        assert_eq!(game.end_pos, Position { x: 3, y: 2 });
+        assert_eq!(game.coins_total, 1);
+        
+        assert!(MazeGame::from_text("#####\n#S  #\n#####").is_err());
+        assert!(MazeGame::from_text("####\n#SxE#\n####").is_err());
+    }
+
+    #[test]
+    fn test_reset_restores_level() {
+        let mut game = MazeGame::from_text("######\n#S $E#\n######").unwrap();
+        game.move_player(1, 0);
+        game.move_player(1, 0);
+        assert_eq!(game.coins_collected, 1);
+        game.reset_game();
+        assert_eq!(game.player_pos, game.start_pos);
+        assert_eq!(game.coins_collected, 0);
+        assert_eq!(game.grid[1][3], Cell::Coin);
+    }
+
+    #[test]
+    fn test_timer_and_moves() {
+        let mut game = MazeGame::from_text("#####\n#S E#\n#####").unwrap();
+        game.tick(1.5);
+        assert!(!game.move_player(0, 1));
+        assert!(game.move_player(1, 0));
+        assert_eq!(game.moves, 1);
+        game.move_player(1, 0);
+        assert!(game.has_won());
+        // 获胜后计时停止
+        game.tick(1.0);
+        assert_eq!(game.elapsed, 1.5);
+    }
+
+    #[test]
+    fn test_collect_items() {
+        let mut game = MazeGame::new(20, 15);
+        assert_eq!(game.grid[7][3], Cell::Coin);
+        game.update_player_position(Position { x: 3, y: 7 });
+        assert_eq!(game.coins_collected, 1);
+        // 物品被收集后不会重新出现
+        assert_eq!(game.grid[7][3], Cell::Path);
+        game.update_player_position(Position { x: 3, y: 8 });
+        assert_eq!(game.grid[7][3], Cell::Path);
+    }
+
+    #[test]
+    fn test_exit_locked_until_collected() {
+        let mut game = MazeGame::new(20, 15);
+        assert!(game.exit_locked());
+        assert!(!game.can_move(game.end_pos));
+        for pos in game.remaining_item_positions() {
+            game.update_player_position(pos);
+        }
+        assert!(!game.exit_locked());
+        assert!(game.can_move(game.end_pos));
+    }
+
+    #[test]
+    fn test_collect_route_visits_all_items() {
+        let game = MazeGame::new(20, 15);
+        let route = game.find_collect_route().expect("应该能找到收集路线");
+        for item in game.remaining_item_positions() {
+            assert!(route.contains(&item));
+        }
+        assert_eq!(route.last(), Some(&game.end_pos));
+        // 终点只出现在路线末尾
+        assert_eq!(route.iter().filter(|&&p| p == game.end_pos).count(), 1);
+    }
 }
\ No newline at end of file
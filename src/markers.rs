@@ -0,0 +1,155 @@
+// 玩家在格子上做的记号：旗子和彩色粉笔，以及自动标出的死路。
+// 记号保存在 MazeGame::markers 中，和其他游戏状态一起在重置时清除。
+
+use crate::{Cell, MazeGame, Position};
+use macroquad::prelude::*;
+
+const CHALK_COLORS: [Color; 3] = [RED, GREEN, BLUE];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Marker {
+    Flag,
+    Chalk(usize), // CHALK_COLORS 中的颜色
+    DeadEnd,      // 由 fill_dead_ends 标出
+}
+
+// 依次切换格子上的记号：无 → 旗子 → 各色粉笔 → 无；死路记号直接清除
+pub fn cycle(game: &mut MazeGame, pos: Position) {
+    if pos.x >= game.width || pos.y >= game.height || !game.is_open(pos) {
+        return;
+    }
+    let marker = &mut game.markers[pos.y][pos.x];
+    *marker = match *marker {
+        None => Some(Marker::Flag),
+        Some(Marker::Flag) => Some(Marker::Chalk(0)),
+        Some(Marker::Chalk(i)) if i + 1 < CHALK_COLORS.len() => Some(Marker::Chalk(i + 1)),
+        Some(_) => None,
+    };
+}
+
+pub fn remove(game: &mut MazeGame, pos: Position) {
+    if pos.x < game.width && pos.y < game.height {
+        game.markers[pos.y][pos.x] = None;
+    }
+}
+
+fn neighbors(game: &MazeGame, pos: Position) -> impl Iterator<Item = Position> + '_ {
+    [(0, -1), (1, 0), (0, 1), (-1, 0)].into_iter().filter_map(move |(dx, dy)| {
+        let x = pos.x.checked_add_signed(dx)?;
+        let y = pos.y.checked_add_signed(dy)?;
+        (x < game.width && y < game.height).then_some(Position { x, y })
+    })
+}
+
+// 玩家已经看清的死路：反复找出只剩一个出口的通道格子（不含起点、终点、
+// 玩家所在格子和未收集的物品），要求它和四周都已探索过，避免泄露没看到的地形
+fn dead_ends(game: &MazeGame) -> Vec<Position> {
+    let mut filled = vec![vec![false; game.width]; game.height];
+    let fillable = |pos: Position, filled: &[Vec<bool>]| {
+        let keep = pos == game.start_pos
+            || pos == game.end_pos
+            || pos == game.player_pos
+            || matches!(game.grid[pos.y][pos.x], Cell::Coin | Cell::Gem);
+        let known = game.explored[pos.y][pos.x] && neighbors(game, pos).all(|n| game.explored[n.y][n.x]);
+        let exits = neighbors(game, pos).filter(|&n| game.is_open(n) && !filled[n.y][n.x]).count();
+        !keep && known && game.is_open(pos) && exits <= 1
+    };
+
+    let mut stack: Vec<Position> = (0..game.height)
+        .flat_map(|y| (0..game.width).map(move |x| Position { x, y }))
+        .collect();
+    let mut found = Vec::new();
+    while let Some(pos) = stack.pop() {
+        if filled[pos.y][pos.x] || !fillable(pos, &filled) {
+            continue;
+        }
+        filled[pos.y][pos.x] = true;
+        found.push(pos);
+        // 相邻格子可能因此变成死路
+        stack.extend(neighbors(game, pos));
+    }
+    found
+}
+
+// 标出已探明的死路，已有记号的格子不会被覆盖；返回新标出的格子数
+pub fn fill_dead_ends(game: &mut MazeGame) -> usize {
+    let mut count = 0;
+    for pos in dead_ends(game) {
+        if game.markers[pos.y][pos.x].is_none() {
+            game.markers[pos.y][pos.x] = Some(Marker::DeadEnd);
+            count += 1;
+        }
+    }
+    count
+}
+
+pub fn render(game: &MazeGame, cell_size: f32) {
+    for (y, row) in game.markers.iter().enumerate() {
+        for (x, marker) in row.iter().enumerate() {
+            let Some(marker) = marker else {
+                continue;
+            };
+            let (px, py) = (x as f32 * cell_size, y as f32 * cell_size);
+            match *marker {
+                Marker::Flag => {
+                    let pole = px + cell_size * 0.3;
+                    let thickness = (cell_size * 0.06).max(1.0);
+                    draw_line(pole, py + cell_size * 0.85, pole, py + cell_size * 0.15, thickness, BLACK);
+                    draw_triangle(
+                        vec2(pole, py + cell_size * 0.15),
+                        vec2(pole + cell_size * 0.45, py + cell_size * 0.28),
+                        vec2(pole, py + cell_size * 0.42),
+                        RED,
+                    );
+                }
+                Marker::Chalk(i) => {
+                    // 粉笔画的叉
+                    let color = Color { a: 0.8, ..CHALK_COLORS[i] };
+                    let (a, b) = (cell_size * 0.25, cell_size * 0.75);
+                    let thickness = (cell_size * 0.1).max(1.0);
+                    draw_line(px + a, py + a, px + b, py + b, thickness, color);
+                    draw_line(px + b, py + a, px + a, py + b, thickness, color);
+                }
+                Marker::DeadEnd => {
+                    draw_rectangle(px, py, cell_size, cell_size, Color::new(0.0, 0.0, 0.0, 0.35));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cycle_and_fill_dead_ends() {
+        // 起点右侧是一条死路，下方通往终点
+        let mut game = MazeGame::from_text("#######\n#S    #\n#.#####\n#.....#\n#####E#\n#######").unwrap();
+        let pos = Position { x: 3, y: 1 };
+        cycle(&mut game, pos);
+        assert_eq!(game.markers[1][3], Some(Marker::Flag));
+        cycle(&mut game, pos);
+        assert_eq!(game.markers[1][3], Some(Marker::Chalk(0)));
+        remove(&mut game, pos);
+        assert_eq!(game.markers[1][3], None);
+        cycle(&mut game, Position { x: 0, y: 0 }); // 墙上不能做记号
+        assert_eq!(game.markers[0][0], None);
+
+        // 还没看清死路的尽头时不标记
+        assert_eq!(fill_dead_ends(&mut game), 0);
+        game.move_player(1, 0);
+        game.move_player(1, 0);
+        game.move_player(1, 0);
+        game.move_player(-1, 0);
+        game.move_player(-1, 0);
+        game.move_player(-1, 0);
+        assert_eq!(fill_dead_ends(&mut game), 4);
+        assert_eq!(game.markers[1][5], Some(Marker::DeadEnd));
+        assert_eq!(game.markers[1][2], Some(Marker::DeadEnd));
+        assert_eq!(game.markers[2][1], None);
+
+        game.reset_game();
+        assert!(game.markers.iter().flatten().all(Option::is_none));
+    }
+}
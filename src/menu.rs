@@ -0,0 +1,96 @@
+// 通用菜单：光标移动与选择，不关心条目内容
+
+use crate::draw_label;
+use crate::theme::Style;
+use macroquad::prelude::*;
+
+// 菜单输入
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MenuInput {
+    Up,
+    Down,
+    Left,
+    Right,
+    Select,
+    Back,
+}
+
+// 读取本帧的菜单按键
+pub fn read_menu_input() -> Option<MenuInput> {
+    if is_key_pressed(KeyCode::Up) || is_key_pressed(KeyCode::W) {
+        Some(MenuInput::Up)
+    } else if is_key_pressed(KeyCode::Down) || is_key_pressed(KeyCode::S) {
+        Some(MenuInput::Down)
+    } else if is_key_pressed(KeyCode::Left) || is_key_pressed(KeyCode::A) {
+        Some(MenuInput::Left)
+    } else if is_key_pressed(KeyCode::Right) || is_key_pressed(KeyCode::D) {
+        Some(MenuInput::Right)
+    } else if is_key_pressed(KeyCode::Enter) || is_key_pressed(KeyCode::Space) {
+        Some(MenuInput::Select)
+    } else if is_key_pressed(KeyCode::Escape) || is_key_pressed(KeyCode::Backspace) {
+        Some(MenuInput::Back)
+    } else {
+        None
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Menu {
+    pub cursor: usize,
+}
+
+impl Menu {
+    pub fn new() -> Self {
+        Menu { cursor: 0 }
+    }
+
+    // 处理上下移动；按下选择键时返回当前条目下标
+    pub fn handle(&mut self, input: MenuInput, len: usize) -> Option<usize> {
+        match input {
+            MenuInput::Up => self.cursor = self.cursor.saturating_sub(1),
+            MenuInput::Down => self.cursor = (self.cursor + 1).min(len.saturating_sub(1)),
+            MenuInput::Select if self.cursor < len => return Some(self.cursor),
+            _ => {}
+        }
+        None
+    }
+
+    pub fn render(&self, title: &str, items: &[String], style: &Style) {
+        draw_label(title, 40.0, 60.0, style);
+        // 条目放不下时滚动，保证光标所在条目可见
+        let visible = (((screen_height() - 130.0) / 32.0) as usize).max(1);
+        let first = (self.cursor + 1).saturating_sub(visible);
+        for (i, item) in items.iter().enumerate().skip(first).take(visible) {
+            let y = 110.0 + (i - first) as f32 * 32.0;
+            if i == self.cursor {
+                draw_rectangle(30.0, y - 22.0, screen_width() - 60.0, 30.0, style.palette.highlight);
+            }
+            draw_label(item, 50.0, y, style);
+        }
+    }
+}
+
+// 在游戏画面上盖一层半透明底色，用于暂停和结算界面
+pub fn draw_overlay(style: &Style) {
+    let mut color = style.palette.background;
+    color.a = 0.85;
+    draw_rectangle(0.0, 0.0, screen_width(), screen_height(), color);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_menu_cursor_is_clamped() {
+        let mut menu = Menu::new();
+        assert_eq!(menu.handle(MenuInput::Up, 3), None);
+        assert_eq!(menu.cursor, 0);
+        for _ in 0..5 {
+            menu.handle(MenuInput::Down, 3);
+        }
+        assert_eq!(menu.cursor, 2);
+        assert_eq!(menu.handle(MenuInput::Select, 3), Some(2));
+        assert_eq!(menu.handle(MenuInput::Select, 0), None);
+    }
+}
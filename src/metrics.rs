@@ -0,0 +1,169 @@
+// 迷宫难度指标
+//
+// 在方块网格上统计（非墙格子都算通道，格子的度数为上下左右相邻的通道数）：
+//   solution_length  起点到终点最短路线的步数
+//   dead_ends        死路数（度数为 1 的格子，不含起点和终点）
+//   branching_factor 非死路格子平均可以继续走的方向数（度数 - 1 的平均值）
+//   longest_corridor 最长的无岔路走廊（相连的度数为 2 的格子数）
+//   river_factor     死路分支的平均长度：越大表示死路越少越长，像河流一样蜿蜒
+//   decision_points  最短路线上需要做选择的路口数
+//   difficulty       综合难度，0 ~ 10，只用于相对比较和排序
+
+use crate::i18n::{fill, tr, Language, Text};
+use crate::{MazeGame, Position};
+use serde::Serialize;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Metrics {
+    pub solution_length: usize,
+    pub dead_ends: usize,
+    pub branching_factor: f64,
+    pub longest_corridor: usize,
+    pub river_factor: f64,
+    pub decision_points: usize,
+    pub difficulty: f64,
+}
+
+// 保留两位小数，输出 JSON 时更易读
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+fn open_neighbors(game: &MazeGame, pos: Position) -> Vec<Position> {
+    [(0, -1), (1, 0), (0, 1), (-1, 0)]
+        .iter()
+        .filter_map(|&(dx, dy)| {
+            let x = pos.x.checked_add_signed(dx)?;
+            let y = pos.y.checked_add_signed(dy)?;
+            let next = Position { x, y };
+            game.is_open(next).then_some(next)
+        })
+        .collect()
+}
+
+impl Metrics {
+    pub fn analyze(game: &MazeGame) -> Result<Metrics, String> {
+        let path = game.find_shortest_path().ok_or("迷宫无解")?;
+        let open: Vec<Position> = (0..game.height)
+            .flat_map(|y| (0..game.width).map(move |x| Position { x, y }))
+            .filter(|&pos| game.is_open(pos))
+            .collect();
+        let degree = |pos: Position| open_neighbors(game, pos).len();
+        let endpoint = |pos: Position| pos == game.start_pos || pos == game.end_pos;
+
+        let dead_ends: Vec<Position> = open.iter().copied().filter(|&p| degree(p) == 1 && !endpoint(p)).collect();
+
+        let choices: Vec<usize> = open.iter().map(|&p| degree(p)).filter(|&d| d >= 2).map(|d| d - 1).collect();
+        let branching_factor = if choices.is_empty() {
+            0.0
+        } else {
+            choices.iter().sum::<usize>() as f64 / choices.len() as f64
+        };
+
+        // 走廊：度数为 2 的格子组成的连通块
+        let mut in_corridor = vec![vec![false; game.width]; game.height];
+        let mut longest_corridor = 0;
+        for &cell in &open {
+            if degree(cell) != 2 || in_corridor[cell.y][cell.x] {
+                continue;
+            }
+            let mut stack = vec![cell];
+            in_corridor[cell.y][cell.x] = true;
+            let mut size = 0;
+            while let Some(pos) = stack.pop() {
+                size += 1;
+                for next in open_neighbors(game, pos) {
+                    if degree(next) == 2 && !in_corridor[next.y][next.x] {
+                        in_corridor[next.y][next.x] = true;
+                        stack.push(next);
+                    }
+                }
+            }
+            longest_corridor = longest_corridor.max(size);
+        }
+
+        // 从每个死路往回走，直到遇到路口、起点或终点
+        let branch_total: usize = dead_ends
+            .iter()
+            .map(|&dead_end| {
+                let (mut prev, mut pos, mut length) = (dead_end, dead_end, 1);
+                loop {
+                    let next = open_neighbors(game, pos).into_iter().find(|&n| n != prev);
+                    match next {
+                        Some(next) if degree(next) == 2 && !endpoint(next) => {
+                            (prev, pos) = (pos, next);
+                            length += 1;
+                        }
+                        _ => break length,
+                    }
+                }
+            })
+            .sum();
+        let river_factor = if dead_ends.is_empty() { 0.0 } else { branch_total as f64 / dead_ends.len() as f64 };
+
+        // 路线上的路口：起点有两个以上方向、途中有三个以上方向（去掉来的方向）
+        let decision_points = std::iter::once(game.start_pos)
+            .chain(path.iter().copied().filter(|&p| p != game.end_pos))
+            .filter(|&p| degree(p) > if p == game.start_pos { 1 } else { 2 })
+            .count();
+
+        let solution_length = path.len();
+        let raw = solution_length as f64 * 0.05
+            + decision_points as f64 * 0.5
+            + dead_ends.len() as f64 * 0.05
+            + river_factor * 0.3;
+        let difficulty = 10.0 * (1.0 - (-raw / 15.0).exp());
+
+        Ok(Metrics {
+            solution_length,
+            dead_ends: dead_ends.len(),
+            branching_factor: round2(branching_factor),
+            longest_corridor,
+            river_factor: round2(river_factor),
+            decision_points,
+            difficulty: round2(difficulty),
+        })
+    }
+
+    // 游戏中叠加显示的各行文字
+    pub fn lines(&self, language: Language) -> Vec<String> {
+        let t = |text| tr(language, text);
+        vec![
+            t(Text::MetricsTitle).to_string(),
+            fill(t(Text::MetricSolution), &[&self.solution_length]),
+            fill(t(Text::MetricDecisions), &[&self.decision_points]),
+            fill(t(Text::MetricDeadEnds), &[&self.dead_ends]),
+            fill(t(Text::MetricBranching), &[&format!("{:.2}", self.branching_factor)]),
+            fill(t(Text::MetricCorridor), &[&self.longest_corridor]),
+            fill(t(Text::MetricRiver), &[&format!("{:.2}", self.river_factor)]),
+            fill(t(Text::MetricDifficulty), &[&format!("{:.1}", self.difficulty)]),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_metrics_of_small_maze() {
+        // 两条路线都能到达终点，没有死路
+        let game = MazeGame::from_text("#######\n#S    #\n#.###.#\n#.#E#.#\n#.#.#.#\n#.....#\n#######").unwrap();
+        let metrics = Metrics::analyze(&game).unwrap();
+        assert_eq!(metrics.solution_length, 8);
+        assert_eq!(metrics.dead_ends, 0);
+        assert_eq!(metrics.decision_points, 2);
+
+        let game = MazeGame::from_text("#####\n#S..#\n##.##\n#E..#\n#####").unwrap();
+        let metrics = Metrics::analyze(&game).unwrap();
+        assert_eq!(metrics.solution_length, 4);
+        assert_eq!(metrics.dead_ends, 2);
+        assert_eq!(metrics.river_factor, 1.0);
+        assert_eq!(metrics.decision_points, 2);
+        assert_eq!(metrics.longest_corridor, 1);
+        assert!(metrics.difficulty > 0.0 && metrics.difficulty < 10.0);
+
+        let walled = MazeGame::from_text("#####\n#S#E#\n#####").unwrap();
+        assert!(Metrics::analyze(&walled).is_err());
+    }
+}
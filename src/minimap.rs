@@ -0,0 +1,87 @@
+// 小地图：画在右上角，只显示走过时看到的格子（MazeGame::explored）、
+// 终点和玩家，下方的指南针指向从玩家出发的最短路线的第一步。
+// 俯视图和第一人称视图共用，大小由设置中的 minimap_size 决定。
+
+use crate::theme::Palette;
+use crate::{Facing, MazeGame};
+use macroquad::prelude::*;
+
+const MARGIN: f32 = 10.0;
+const COMPASS_RADIUS: f32 = 16.0;
+
+// player 和 dir 为玩家在网格中的位置（格子中心为 x+0.5）和朝向，
+// 第一人称视图传入相机的连续坐标，俯视图传入玩家所在格子
+pub fn render(game: &MazeGame, palette: &Palette, size: f32, player: Vec2, dir: Vec2) {
+    let cell = size / game.width.max(game.height) as f32;
+    let map = vec2(game.width as f32, game.height as f32) * cell;
+    let origin = vec2(screen_width() - map.x - MARGIN, MARGIN);
+
+    let mut panel = palette.background;
+    panel.a = 0.85;
+    draw_rectangle(origin.x - 4.0, origin.y - 4.0, map.x + 8.0, map.y + 8.0, panel);
+    for (y, row) in game.grid.iter().enumerate() {
+        for (x, &c) in row.iter().enumerate() {
+            if !game.explored[y][x] {
+                continue;
+            }
+            let color = if c == crate::Cell::Wall { palette.wall } else { palette.floor };
+            draw_rectangle(origin.x + x as f32 * cell, origin.y + y as f32 * cell, cell, cell, color);
+        }
+    }
+    draw_rectangle_lines(origin.x - 4.0, origin.y - 4.0, map.x + 8.0, map.y + 8.0, 2.0, palette.grid_line);
+
+    // 终点始终显示
+    let end = origin + vec2(game.end_pos.x as f32 + 0.5, game.end_pos.y as f32 + 0.5) * cell;
+    let end_color = if game.exit_locked() { palette.end_locked } else { palette.end };
+    draw_circle(end.x, end.y, (cell * 0.8).max(3.0), end_color);
+
+    let p = origin + player * cell;
+    let side = vec2(-dir.y, dir.x) * cell * 0.7;
+    let (tip, back) = (dir * cell * 1.1, dir * cell * 0.7);
+    draw_triangle(p + tip, p - back + side, p - back - side, palette.player);
+
+    if let Some(dir) = hint_direction(game) {
+        let center = vec2(origin.x + COMPASS_RADIUS, origin.y + map.y + 8.0 + COMPASS_RADIUS);
+        draw_circle(center.x, center.y, COMPASS_RADIUS, panel);
+        draw_circle_lines(center.x, center.y, COMPASS_RADIUS, 2.0, palette.grid_line);
+        let (dx, dy) = dir.delta();
+        let d = vec2(dx as f32, dy as f32);
+        let side = vec2(-d.y, d.x) * COMPASS_RADIUS * 0.45;
+        draw_triangle(
+            center + d * COMPASS_RADIUS * 0.8,
+            center - d * COMPASS_RADIUS * 0.5 + side,
+            center - d * COMPASS_RADIUS * 0.5 - side,
+            palette.path,
+        );
+    }
+}
+
+// 从玩家出发的最短路线第一步的方向；已到终点或无路可走时为 None
+pub fn hint_direction(game: &MazeGame) -> Option<Facing> {
+    let next = *game.path_from(game.player_pos)?.first()?;
+    let dx = next.x as i32 - game.player_pos.x as i32;
+    let dy = next.y as i32 - game.player_pos.y as i32;
+    Some(Facing::from_delta(dx.signum(), dy.signum()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Position;
+
+    #[test]
+    fn test_hint_direction_follows_shortest_path() {
+        let mut game = MazeGame::from_text("#####\n#S#E#\n# # #\n#   #\n#####").unwrap();
+        assert_eq!(hint_direction(&game), Some(Facing::Down));
+        game.move_player(0, 1);
+        game.move_player(0, 1);
+        assert_eq!(hint_direction(&game), Some(Facing::Right));
+
+        // 走过的地方周围和直线可见的走廊被记为已探索
+        assert!(game.explored[3][3]);
+        assert!(game.explored[2][2]);
+        assert!(!game.explored[1][3]);
+        game.player_pos = Position { x: 3, y: 1 };
+        assert_eq!(hint_direction(&game), None);
+    }
+}
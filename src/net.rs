@@ -0,0 +1,507 @@
+// 联网竞速：服务器生成迷宫并广播给客户端，由服务器判定每一步是否合法并宣布胜者。
+//
+// 协议：TCP 上每行一个 JSON 对象（UTF-8，以 \n 结尾），type 字段区分消息类型。
+//
+// 客户端 → 服务器
+//   {"type":"join","name":"小明"}                  加入比赛，连接后必须先发送
+//   {"type":"move","dx":1,"dy":0}                 向相邻格子移动一步
+//
+// 服务器 → 客户端
+//   {"type":"welcome","id":3}                     加入成功，告知自己的编号
+//   {"type":"round","round":1,"maze":["#####",...],"players":[...],"winner":null}
+//                                                 完整状态：加入时和每轮开始时发送
+//   {"type":"joined","id":4,"name":"小红","x":1,"y":1,"moves":0}
+//   {"type":"left","id":4}                        有玩家断开连接
+//   {"type":"moved","id":3,"x":2,"y":1,"moves":1} 服务器确认的移动，发给所有人
+//   {"type":"winner","id":3,"name":"小明","moves":40,"time":12.5}
+//   {"type":"rejected","reason":"..."}            只发给发送者：移动被墙挡住、本轮已结束、消息无法解析等
+//
+// players 中每项的字段与 joined 相同。迷宫文本的格式与关卡文件一致，不含物品。
+// 中途加入的玩家从起点出发；本轮已有胜者时只能观看，等待下一轮。
+// 有人获胜 ROUND_DELAY 秒后，服务器换用下一个种子生成新迷宫，向所有人发送 round。
+// 一行超过 MAX_LINE_LENGTH 字节，或者待发送的消息积压超过 MAX_QUEUED 条时，服务器断开该连接。
+
+use crate::generator::{self, GeneratorParams};
+use crate::race::race_grid;
+use crate::{grid_to_text, Cell, MazeGame};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender, SyncSender, TryRecvError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_PORT: u16 = 7878;
+const ROUND_DELAY: f32 = 5.0;
+const MAX_NAME_LENGTH: usize = 16;
+const TICK: Duration = Duration::from_millis(50);
+const MAX_LINE_LENGTH: usize = 4096;
+const MAX_QUEUED: usize = 256; // 每个连接等待写出的消息数上限
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    Join { name: String },
+    Move { dx: i32, dy: i32 },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlayerState {
+    pub id: usize,
+    pub name: String,
+    pub x: usize,
+    pub y: usize,
+    pub moves: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Finish {
+    pub id: usize,
+    pub name: String,
+    pub moves: usize,
+    pub time: f32, // 秒
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage {
+    Welcome { id: usize },
+    Round { round: u32, maze: Vec<String>, players: Vec<PlayerState>, winner: Option<Finish> },
+    Joined(PlayerState),
+    Left { id: usize },
+    Moved { id: usize, x: usize, y: usize, moves: usize },
+    Winner(Finish),
+    Rejected { reason: String },
+}
+
+// 消息的接收者
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Recipient {
+    Player(usize),
+    Others(usize), // 除该玩家以外的所有已加入玩家
+    Everyone,      // 所有已加入玩家
+}
+
+pub type Outbox = Vec<(Recipient, ServerMessage)>;
+
+struct Player {
+    name: String,
+    game: MazeGame, // 只用于这名玩家的位置、步数和计时
+}
+
+// 服务器的比赛状态，不涉及网络，连接编号即玩家编号
+pub struct ServerState {
+    params: GeneratorParams,
+    grid: Vec<Vec<Cell>>,
+    round: u32,
+    players: BTreeMap<usize, Player>,
+    winner: Option<Finish>,
+    next_round: f32, // 有人获胜后距离下一轮的时间
+}
+
+impl ServerState {
+    pub fn new(params: GeneratorParams) -> Self {
+        let grid = race_grid(&generator::generate(&params));
+        ServerState { params, grid, round: 1, players: BTreeMap::new(), winner: None, next_round: 0.0 }
+    }
+
+    fn new_game(&self) -> MazeGame {
+        MazeGame::from_grid(self.grid.clone()).expect("生成的迷宫应当有效")
+    }
+
+    fn player_state(id: usize, player: &Player) -> PlayerState {
+        let pos = player.game.player_pos;
+        PlayerState { id, name: player.name.clone(), x: pos.x, y: pos.y, moves: player.game.moves }
+    }
+
+    fn round_message(&self) -> ServerMessage {
+        ServerMessage::Round {
+            round: self.round,
+            maze: grid_to_text(&self.grid).lines().map(str::to_string).collect(),
+            players: self.players.iter().map(|(&id, p)| Self::player_state(id, p)).collect(),
+            winner: self.winner.clone(),
+        }
+    }
+
+    // 某条消息实际要发给的连接
+    pub fn recipients(&self, to: Recipient) -> Vec<usize> {
+        match to {
+            Recipient::Player(id) => vec![id],
+            Recipient::Others(id) => self.players.keys().copied().filter(|&other| other != id).collect(),
+            Recipient::Everyone => self.players.keys().copied().collect(),
+        }
+    }
+
+    pub fn handle(&mut self, id: usize, message: ClientMessage) -> Outbox {
+        let reject = |reason: &str| vec![(Recipient::Player(id), ServerMessage::Rejected { reason: reason.to_string() })];
+        match message {
+            ClientMessage::Join { .. } if self.players.contains_key(&id) => reject("已经加入了比赛"),
+            ClientMessage::Join { name } => {
+                let name: String = name.trim().chars().take(MAX_NAME_LENGTH).collect();
+                let name = if name.is_empty() { format!("玩家{}", id) } else { name };
+                let player = Player { name, game: self.new_game() };
+                let joined = Self::player_state(id, &player);
+                self.players.insert(id, player);
+                vec![
+                    (Recipient::Player(id), ServerMessage::Welcome { id }),
+                    (Recipient::Player(id), self.round_message()),
+                    (Recipient::Others(id), ServerMessage::Joined(joined)),
+                ]
+            }
+            ClientMessage::Move { dx, dy } => {
+                if !matches!((dx, dy), (0, -1) | (1, 0) | (0, 1) | (-1, 0)) {
+                    return reject("每次只能移动到相邻的格子");
+                }
+                if self.winner.is_some() {
+                    return reject("本轮已结束，请等待下一轮");
+                }
+                let Some(player) = self.players.get_mut(&id) else {
+                    return reject("请先加入比赛");
+                };
+                if !player.game.move_player(dx, dy) {
+                    return reject("被墙挡住了");
+                }
+                let pos = player.game.player_pos;
+                let mut outbox = vec![(
+                    Recipient::Everyone,
+                    ServerMessage::Moved { id, x: pos.x, y: pos.y, moves: player.game.moves },
+                )];
+                if player.game.has_won() {
+                    let finish = Finish {
+                        id,
+                        name: player.name.clone(),
+                        moves: player.game.moves,
+                        time: player.game.elapsed,
+                    };
+                    self.winner = Some(finish.clone());
+                    self.next_round = ROUND_DELAY;
+                    outbox.push((Recipient::Everyone, ServerMessage::Winner(finish)));
+                }
+                outbox
+            }
+        }
+    }
+
+    // 连接断开；还没加入的连接不通知其他人
+    pub fn disconnect(&mut self, id: usize) -> Outbox {
+        match self.players.remove(&id) {
+            Some(_) => vec![(Recipient::Others(id), ServerMessage::Left { id })],
+            None => Vec::new(),
+        }
+    }
+
+    // 推进计时；有人获胜一段时间后开始下一轮
+    pub fn tick(&mut self, dt: f32) -> Outbox {
+        if self.winner.is_none() {
+            for player in self.players.values_mut() {
+                player.game.tick(dt);
+            }
+            return Vec::new();
+        }
+        self.next_round -= dt;
+        if self.next_round > 0.0 {
+            return Vec::new();
+        }
+        self.params.seed = self.params.seed.wrapping_add(1);
+        self.grid = race_grid(&generator::generate(&self.params));
+        self.round += 1;
+        self.winner = None;
+        for player in self.players.values_mut() {
+            player.game = MazeGame::from_grid(self.grid.clone()).expect("生成的迷宫应当有效");
+        }
+        vec![(Recipient::Everyone, self.round_message())]
+    }
+}
+
+enum Event {
+    Connected(usize, TcpStream),
+    Line(usize, String),
+    Closed(usize),
+}
+
+// 逐行读取连接上的消息，连接关闭、出错或一行太长时报告断开
+fn read_lines(id: usize, stream: TcpStream, events: Sender<Event>) {
+    let mut reader = BufReader::new(stream);
+    loop {
+        let mut line = Vec::new();
+        match reader.by_ref().take(MAX_LINE_LENGTH as u64 + 1).read_until(b'\n', &mut line) {
+            Ok(0) | Err(_) => break,
+            Ok(_) => {}
+        }
+        if line.last() == Some(&b'\n') {
+            line.pop();
+        } else if line.len() > MAX_LINE_LENGTH {
+            let _ = reader.get_ref().shutdown(Shutdown::Both);
+            break;
+        }
+        let Ok(line) = String::from_utf8(line) else {
+            break;
+        };
+        if !line.trim().is_empty() && events.send(Event::Line(id, line)).is_err() {
+            return;
+        }
+    }
+    let _ = events.send(Event::Closed(id));
+}
+
+// 每个连接一个写线程，服务器主循环只把消息放进队列，不会被写不动的连接卡住
+struct Writer {
+    stream: TcpStream,
+    lines: SyncSender<String>,
+}
+
+impl Writer {
+    fn spawn(stream: TcpStream) -> Option<Writer> {
+        let _ = stream.set_nodelay(true);
+        let mut output = stream.try_clone().ok()?;
+        let (lines, queue) = mpsc::sync_channel::<String>(MAX_QUEUED);
+        thread::spawn(move || {
+            for line in queue {
+                if output.write_all(line.as_bytes()).is_err() {
+                    break;
+                }
+            }
+            // 写入失败或连接被移除时关闭连接，读线程随后报告断开
+            let _ = output.shutdown(Shutdown::Both);
+        });
+        Some(Writer { stream, lines })
+    }
+
+    // 队列已满说明客户端跟不上，返回 false
+    fn send(&self, line: &str) -> bool {
+        self.lines.try_send(line.to_string()).is_ok()
+    }
+}
+
+impl Drop for Writer {
+    fn drop(&mut self) {
+        // 写线程可能正阻塞在写入上，关闭连接让它退出
+        let _ = self.stream.shutdown(Shutdown::Both);
+    }
+}
+
+fn encode<T: Serialize>(message: &T) -> String {
+    serde_json::to_string(message).expect("消息应当可以序列化") + "\n"
+}
+
+// 运行服务器，直到监听失败；每个连接一个读线程和一个写线程，比赛状态只在当前线程中修改
+pub fn serve(listener: TcpListener, mut state: ServerState) {
+    let (events, inbox) = mpsc::channel();
+    thread::spawn(move || {
+        for (id, stream) in listener.incoming().filter_map(Result::ok).enumerate() {
+            let Ok(reader) = stream.try_clone() else {
+                continue;
+            };
+            if events.send(Event::Connected(id, stream)).is_err() {
+                return;
+            }
+            let events = events.clone();
+            thread::spawn(move || read_lines(id, reader, events));
+        }
+    });
+
+    let mut writers: HashMap<usize, Writer> = HashMap::new();
+    let mut last = Instant::now();
+    loop {
+        let mut outbox = match inbox.recv_timeout(TICK) {
+            Ok(Event::Connected(id, stream)) => {
+                if let Some(writer) = Writer::spawn(stream) {
+                    writers.insert(id, writer);
+                }
+                Vec::new()
+            }
+            Ok(Event::Line(id, line)) => match serde_json::from_str(&line) {
+                Ok(message) => state.handle(id, message),
+                Err(e) => {
+                    let reason = format!("无法解析消息: {}", e);
+                    vec![(Recipient::Player(id), ServerMessage::Rejected { reason })]
+                }
+            },
+            Ok(Event::Closed(id)) => {
+                writers.remove(&id);
+                state.disconnect(id)
+            }
+            Err(RecvTimeoutError::Timeout) => Vec::new(),
+            Err(RecvTimeoutError::Disconnected) => return,
+        };
+        let now = Instant::now();
+        outbox.extend(state.tick((now - last).as_secs_f32()));
+        last = now;
+
+        for (to, message) in outbox {
+            let line = encode(&message);
+            for id in state.recipients(to) {
+                // 跟不上的连接直接移除，读线程随后报告断开
+                if writers.get(&id).is_some_and(|writer| !writer.send(&line)) {
+                    writers.remove(&id);
+                }
+            }
+        }
+    }
+}
+
+// 客户端连接：后台线程读取服务器消息，游戏每帧取出
+pub struct Client {
+    stream: TcpStream,
+    incoming: Receiver<ServerMessage>,
+    pub connected: bool,
+}
+
+impl Client {
+    pub fn connect(addr: &str, name: &str) -> Result<Client, String> {
+        let stream = TcpStream::connect(addr).map_err(|e| format!("无法连接到 {}: {}", addr, e))?;
+        let _ = stream.set_nodelay(true);
+        let reader = stream.try_clone().map_err(|e| e.to_string())?;
+        let (sender, incoming) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(reader).lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                match serde_json::from_str(&line) {
+                    Ok(message) => {
+                        if sender.send(message).is_err() {
+                            break;
+                        }
+                    }
+                    Err(e) => eprintln!("无法解析服务器消息 {}: {}", line, e),
+                }
+            }
+        });
+        let mut client = Client { stream, incoming, connected: true };
+        client.send(&ClientMessage::Join { name: name.to_string() });
+        Ok(client)
+    }
+
+    pub fn send(&mut self, message: &ClientMessage) {
+        if self.stream.write_all(encode(message).as_bytes()).is_err() {
+            self.connected = false;
+        }
+    }
+
+    // 取出目前收到的消息，不阻塞
+    pub fn poll(&mut self) -> Vec<ServerMessage> {
+        let mut messages = Vec::new();
+        loop {
+            match self.incoming.try_recv() {
+                Ok(message) => messages.push(message),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    self.connected = false;
+                    break;
+                }
+            }
+        }
+        messages
+    }
+}
+
+impl Drop for Client {
+    fn drop(&mut self) {
+        let _ = self.stream.shutdown(Shutdown::Both);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::{Algorithm, PostProcess};
+
+    fn params() -> GeneratorParams {
+        GeneratorParams {
+            algorithm: Algorithm::Backtracker,
+            width: 11,
+            height: 9,
+            seed: 7,
+            coins: 3,
+            gems: 0,
+            post: PostProcess::default(),
+        }
+    }
+
+    // 沿最短路线移动一名玩家，返回最后一步的消息
+    fn walk_to_end(state: &mut ServerState, id: usize) -> Outbox {
+        let game = state.new_game();
+        let mut path = vec![game.start_pos];
+        path.extend(game.find_shortest_path().unwrap());
+        let mut outbox = Vec::new();
+        for pair in path.windows(2) {
+            let (dx, dy) = (pair[1].x as i32 - pair[0].x as i32, pair[1].y as i32 - pair[0].y as i32);
+            outbox = state.handle(id, ClientMessage::Move { dx, dy });
+        }
+        outbox
+    }
+
+    #[test]
+    fn test_server_validates_moves_and_starts_next_round() {
+        let mut state = ServerState::new(params());
+        assert!(state.grid.iter().flatten().all(|&c| c != Cell::Coin));
+        let rejected = |outbox: &Outbox| matches!(outbox.as_slice(), [(_, ServerMessage::Rejected { .. })]);
+        assert!(rejected(&state.handle(0, ClientMessage::Move { dx: 1, dy: 0 })));
+
+        let outbox = state.handle(0, ClientMessage::Join { name: "  ".to_string() });
+        assert_eq!(outbox[0], (Recipient::Player(0), ServerMessage::Welcome { id: 0 }));
+        let outbox = state.handle(1, ClientMessage::Join { name: "小红".to_string() });
+        assert!(matches!(&outbox[1].1, ServerMessage::Round { players, .. } if players.len() == 2 && players[0].name == "玩家0"));
+        assert_eq!(state.recipients(outbox[2].0), vec![0]);
+        assert!(rejected(&state.handle(1, ClientMessage::Move { dx: 1, dy: 1 })));
+        let extreme = serde_json::from_str(r#"{"type":"move","dx":-2147483648,"dy":0}"#).unwrap();
+        assert!(rejected(&state.handle(1, extreme)));
+
+        let outbox = walk_to_end(&mut state, 1);
+        assert!(matches!(&outbox[1].1, ServerMessage::Winner(Finish { id: 1, .. })));
+        assert!(rejected(&state.handle(0, ClientMessage::Move { dx: 1, dy: 0 })));
+
+        // 中途加入的玩家收到已有的胜者
+        let outbox = state.handle(2, ClientMessage::Join { name: "晚到".to_string() });
+        assert!(matches!(&outbox[1].1, ServerMessage::Round { winner: Some(_), .. }));
+        assert_eq!(state.disconnect(2), vec![(Recipient::Others(2), ServerMessage::Left { id: 2 })]);
+        assert!(state.disconnect(5).is_empty());
+
+        let grid = state.grid.clone();
+        assert!(state.tick(ROUND_DELAY / 2.0).is_empty());
+        let outbox = state.tick(ROUND_DELAY);
+        assert!(matches!(&outbox[..], [(Recipient::Everyone, ServerMessage::Round { round: 2, winner: None, .. })]));
+        assert_ne!(state.grid, grid);
+        assert!(state.players.values().all(|p| p.game.moves == 0));
+    }
+
+    #[test]
+    fn test_clients_race_over_localhost() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        thread::spawn(move || serve(listener, ServerState::new(params())));
+
+        // 等待直到收到满足条件的消息
+        let wait = |client: &mut Client, found: &dyn Fn(&ServerMessage) -> bool| {
+            let deadline = Instant::now() + Duration::from_secs(5);
+            while Instant::now() < deadline {
+                if client.poll().iter().any(found) {
+                    return true;
+                }
+                thread::sleep(Duration::from_millis(10));
+            }
+            false
+        };
+
+        let mut first = Client::connect(&addr, "一号").unwrap();
+        assert!(wait(&mut first, &|m| matches!(m, ServerMessage::Round { .. })));
+        let mut second = Client::connect(&addr, "二号").unwrap();
+        assert!(wait(&mut second, &|m| matches!(m, ServerMessage::Round { players, .. } if players.len() == 2)));
+        assert!(wait(&mut first, &|m| matches!(m, ServerMessage::Joined(p) if p.name == "二号")));
+
+        // 起点周围总有一个方向是通的，其余方向被服务器拒绝
+        for (dx, dy) in [(0, -1), (1, 0), (0, 1), (-1, 0)] {
+            second.send(&ClientMessage::Move { dx, dy });
+        }
+        assert!(wait(&mut first, &|m| matches!(m, ServerMessage::Moved { id: 1, moves: 1, .. })));
+        second.stream.write_all(b"not json\n").unwrap();
+        assert!(wait(&mut second, &|m| matches!(m, ServerMessage::Rejected { reason } if reason.contains("解析"))));
+
+        // 过长的一行直接断开连接
+        second.stream.write_all(&[b'x'; MAX_LINE_LENGTH + 1]).unwrap();
+        assert!(wait(&mut first, &|m| *m == ServerMessage::Left { id: 1 }));
+        assert!(first.connected);
+    }
+}
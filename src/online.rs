@@ -0,0 +1,197 @@
+// 联网竞速的客户端：按服务器消息维护比赛状态并绘制（协议见 net.rs）。
+// 自己的移动要等服务器确认后才显示，其他玩家画成带名字的圆点。
+
+use crate::audio::Effect;
+use crate::i18n::{fill, tr, Text};
+use crate::net::{Client, ClientMessage, Finish, PlayerState, ServerMessage};
+use crate::theme::Style;
+use crate::{draw_label, MazeGame, Position};
+use macroquad::prelude::*;
+use std::collections::BTreeMap;
+
+// 其他玩家的颜色，按编号依次使用
+const PLAYER_COLORS: [Color; 6] = [ORANGE, SKYBLUE, MAGENTA, LIME, GOLD, PINK];
+
+pub struct Online {
+    client: Client,
+    id: Option<usize>,
+    round: u32,
+    game: Option<MazeGame>, // 收到迷宫后创建，自己的位置随服务器确认的移动更新
+    players: BTreeMap<usize, PlayerState>,
+    winner: Option<Finish>,
+    message: Option<String>, // 服务器最近一次拒绝的原因
+}
+
+impl Online {
+    pub fn new(client: Client) -> Self {
+        Online {
+            client,
+            id: None,
+            round: 0,
+            game: None,
+            players: BTreeMap::new(),
+            winner: None,
+            message: None,
+        }
+    }
+
+    pub fn send_move(&mut self, dx: i32, dy: i32) {
+        self.client.send(&ClientMessage::Move { dx, dy });
+    }
+
+    // 处理收到的消息，返回需要播放的音效
+    pub fn update(&mut self, dt: f32) -> Vec<Effect> {
+        let messages = self.client.poll();
+        let effects = messages.into_iter().filter_map(|message| self.apply(message)).collect();
+        if let Some(game) = &mut self.game {
+            if self.winner.is_none() {
+                game.tick(dt);
+            }
+        }
+        effects
+    }
+
+    fn apply(&mut self, message: ServerMessage) -> Option<Effect> {
+        match message {
+            ServerMessage::Welcome { id } => self.id = Some(id),
+            ServerMessage::Round { round, maze, players, winner } => {
+                self.round = round;
+                self.game = match MazeGame::from_text(&maze.join("\n")) {
+                    Ok(game) => Some(game),
+                    Err(e) => {
+                        self.message = Some(e);
+                        None
+                    }
+                };
+                self.players = players.into_iter().map(|p| (p.id, p)).collect();
+                self.winner = winner;
+                self.message = None;
+            }
+            ServerMessage::Joined(player) => {
+                self.players.insert(player.id, player);
+            }
+            ServerMessage::Left { id } => {
+                self.players.remove(&id);
+            }
+            ServerMessage::Moved { id, x, y, moves } => {
+                // 位置超出迷宫的消息不可信，直接忽略
+                if self.game.as_ref().is_some_and(|game| x >= game.width || y >= game.height) {
+                    return None;
+                }
+                if let Some(player) = self.players.get_mut(&id) {
+                    (player.x, player.y, player.moves) = (x, y, moves);
+                }
+                if Some(id) == self.id {
+                    self.message = None;
+                    if let Some(game) = &mut self.game {
+                        // 按确认的位置走一步，这样脚下的格子和走过的路线也会更新
+                        let target = Position { x, y };
+                        let (dx, dy) = (x as i32 - game.player_pos.x as i32, y as i32 - game.player_pos.y as i32);
+                        if !game.move_player(dx, dy) || game.player_pos != target {
+                            game.player_pos = target;
+                        }
+                        game.moves = moves;
+                    }
+                    return Some(Effect::Step);
+                }
+            }
+            ServerMessage::Winner(finish) => {
+                self.winner = Some(finish);
+                return Some(Effect::Fanfare);
+            }
+            ServerMessage::Rejected { reason } => {
+                self.message = Some(reason);
+                return Some(Effect::Bump);
+            }
+        }
+        None
+    }
+
+    pub fn render(&self, style: &Style) {
+        let t = |text| tr(style.language, text);
+        let mut lines = vec![t(Text::OnlineHelp).to_string()];
+        let mut height = 0.0;
+        if let Some(game) = &self.game {
+            let cell_size = game.cell_size(style.max_cell_size);
+            height = game.height as f32 * cell_size;
+            game.render_board(style, cell_size);
+            for (index, player) in self.players.values().filter(|p| Some(p.id) != self.id).enumerate() {
+                let center = vec2(player.x as f32 + 0.5, player.y as f32 + 0.5) * cell_size;
+                draw_circle(center.x, center.y, cell_size * 0.4, PLAYER_COLORS[index % PLAYER_COLORS.len()]);
+                draw_text(&player.name, center.x - cell_size * 0.5, center.y - cell_size * 0.5, 16.0, style.palette.text);
+            }
+            lines.push(fill(t(Text::OnlineStatus), &[&self.round, &self.players.len()]));
+            let names: Vec<String> = self
+                .players
+                .values()
+                .map(|p| fill(t(Text::OnlinePlayer), &[&p.name, &p.moves]))
+                .collect();
+            lines.push(names.join("  "));
+        } else {
+            lines.push(t(Text::OnlineWaiting).to_string());
+        }
+        if let Some(winner) = &self.winner {
+            let time = format!("{:.1}", winner.time);
+            lines.push(fill(t(Text::OnlineWinner), &[&winner.name, &winner.moves, &time]));
+        }
+        if !self.client.connected {
+            lines.push(t(Text::OnlineDisconnected).to_string());
+        } else if let Some(message) = &self.message {
+            lines.push(message.clone());
+        }
+        for (i, line) in lines.iter().enumerate() {
+            draw_label(line, 10.0, height + 30.0 + i as f32 * 25.0, style);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::net::{serve, ServerState};
+    use crate::generator::{Algorithm, GeneratorParams, PostProcess};
+    use std::net::TcpListener;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_mirror_follows_server_messages() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let params = GeneratorParams {
+            algorithm: Algorithm::Prim,
+            width: 9,
+            height: 9,
+            seed: 3,
+            coins: 0,
+            gems: 0,
+            post: PostProcess::default(),
+        };
+        std::thread::spawn(move || serve(listener, ServerState::new(params)));
+
+        let mut online = Online::new(Client::connect(&addr, "测试").unwrap());
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while online.game.is_none() && Instant::now() < deadline {
+            online.update(0.0);
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        let id = online.id.unwrap();
+        let start = online.game.as_ref().unwrap().player_pos;
+        assert_eq!(online.players[&id].name, "测试");
+
+        // 服务器确认的移动更新自己的位置，拒绝的移动只显示原因
+        let moved = ServerMessage::Moved { id, x: start.x + 1, y: start.y, moves: 1 };
+        assert_eq!(online.apply(moved), Some(Effect::Step));
+        let game = online.game.as_ref().unwrap();
+        assert_eq!((game.player_pos.x, game.moves), (start.x + 1, 1));
+        assert_eq!(online.players[&id].moves, 1);
+        let outside = ServerMessage::Moved { id, x: 1000, y: start.y, moves: 2 };
+        assert_eq!(online.apply(outside), None);
+        assert_eq!(online.game.as_ref().unwrap().player_pos.x, start.x + 1);
+        let rejected = ServerMessage::Rejected { reason: "被墙挡住了".to_string() };
+        assert_eq!(online.apply(rejected), Some(Effect::Bump));
+        assert!(online.message.is_some());
+
+        online.apply(ServerMessage::Left { id });
+        assert!(online.players.is_empty());
+    }
+}
@@ -0,0 +1,140 @@
+// 本地双人竞速：两名玩家在同一张迷宫上比谁先到终点。
+// 每名玩家是一个独立的 MazeGame（各自的位置、步数和计时），网格相同且去掉了物品，
+// 可以画在同一张地图上，也可以左右分屏、各自的镜头跟随自己。
+
+use crate::i18n::{fill, tr, Text};
+use crate::theme::Style;
+use crate::{draw_label, Cell, MazeGame, Position};
+use macroquad::prelude::*;
+
+// 二号玩家的颜色，一号玩家使用主题中的玩家颜色
+const PLAYER_TWO_COLOR: Color = ORANGE;
+const HUD_HEIGHT: f32 = 110.0;
+
+// 竞速只比到达终点，物品当作空地
+pub fn race_grid(grid: &[Vec<Cell>]) -> Vec<Vec<Cell>> {
+    grid.iter()
+        .map(|row| row.iter().map(|&c| if matches!(c, Cell::Coin | Cell::Gem) { Cell::Empty } else { c }).collect())
+        .collect()
+}
+
+pub struct Race {
+    pub players: [MazeGame; 2],
+    pub winner: Option<usize>,
+    pub split: bool, // 分屏显示
+}
+
+impl Race {
+    pub fn new(grid: &[Vec<Cell>]) -> Self {
+        let grid = race_grid(grid);
+        let player = || MazeGame::from_grid(grid.clone()).expect("竞速使用的迷宫应当有效");
+        Race { players: [player(), player()], winner: None, split: false }
+    }
+
+    // 有人到达终点后不能再移动；返回是否移动了
+    pub fn move_player(&mut self, index: usize, dx: i32, dy: i32) -> bool {
+        if self.winner.is_some() {
+            return false;
+        }
+        let moved = self.players[index].move_player(dx, dy);
+        if self.players[index].has_won() {
+            self.winner = Some(index);
+        }
+        moved
+    }
+
+    pub fn tick(&mut self, dt: f32) {
+        if self.winner.is_none() {
+            for player in &mut self.players {
+                player.tick(dt);
+            }
+        }
+    }
+
+    fn color(index: usize, style: &Style) -> Color {
+        if index == 0 { style.palette.player } else { PLAYER_TWO_COLOR }
+    }
+
+    // 用一号玩家的地图画整张迷宫（一号玩家按主题绘制），再把二号玩家画成圆形
+    fn render_board(&self, style: &Style, cell_size: f32) {
+        self.players[0].render_board(style, cell_size);
+        let Position { x, y } = self.players[1].player_pos;
+        let center = vec2(x as f32 + 0.5, y as f32 + 0.5) * cell_size;
+        draw_circle(center.x, center.y, cell_size * 0.4, PLAYER_TWO_COLOR);
+    }
+
+    pub fn render(&self, style: &Style) {
+        let view_height = (screen_height() - HUD_HEIGHT).max(1.0);
+        if self.split {
+            // 左右分屏，镜头中心跟随各自的玩家；视口的 y 从屏幕底部算起
+            let half = screen_width() / 2.0;
+            for (index, player) in self.players.iter().enumerate() {
+                let cell_size = style.max_cell_size;
+                let Position { x, y } = player.player_pos;
+                let camera = Camera2D {
+                    target: vec2(x as f32 + 0.5, y as f32 + 0.5) * cell_size,
+                    zoom: vec2(2.0 / half, -2.0 / view_height),
+                    viewport: Some((
+                        (half * index as f32) as i32,
+                        HUD_HEIGHT as i32,
+                        half as i32,
+                        view_height as i32,
+                    )),
+                    ..Default::default()
+                };
+                set_camera(&camera);
+                self.render_board(style, cell_size);
+            }
+            set_default_camera();
+            draw_line(half, 0.0, half, view_height, 3.0, style.palette.text);
+        } else {
+            let cell_size = self.players[0].cell_size(style.max_cell_size);
+            self.render_board(style, cell_size);
+        }
+
+        let t = |text| tr(style.language, text);
+        let mut lines = vec![t(Text::RaceHelp).to_string()];
+        for (index, player) in self.players.iter().enumerate() {
+            let status = fill(
+                t(Text::RacePlayerStatus),
+                &[&(index + 1), &format!("{:.1}", player.elapsed), &player.moves],
+            );
+            lines.push(status);
+        }
+        for (i, line) in lines.iter().enumerate() {
+            let y = screen_height() - HUD_HEIGHT + 25.0 + i as f32 * 25.0;
+            if i > 0 {
+                draw_circle(18.0, y - 6.0, 7.0, Race::color(i - 1, style));
+            }
+            draw_label(line, if i > 0 { 32.0 } else { 10.0 }, y, style);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_players_move_independently_and_first_wins() {
+        let game = MazeGame::from_text("#####\n#S$ #\n# # #\n#  E#\n#####").unwrap();
+        let mut race = Race::new(&game.initial_grid);
+        assert_eq!(race.players[0].coins_total, 0);
+
+        // 两名玩家可以站在同一格
+        assert!(race.move_player(0, 1, 0));
+        assert!(race.move_player(1, 1, 0));
+        assert_eq!(race.players[0].player_pos, race.players[1].player_pos);
+        assert!(!race.move_player(1, 0, -1));
+        assert_eq!((race.players[0].moves, race.players[1].moves), (1, 1));
+
+        race.tick(1.0);
+        race.move_player(1, 1, 0);
+        race.move_player(1, 0, 1);
+        race.move_player(1, 0, 1);
+        assert_eq!(race.winner, Some(1));
+        assert!(!race.move_player(0, -1, 0));
+        race.tick(1.0);
+        assert_eq!(race.players[0].elapsed, 1.0);
+    }
+}
@@ -0,0 +1,324 @@
+// 第一人称视图：Wolfenstein 式光线投射
+//
+// 直接读取 MazeGame 的方块网格，墙为 Cell::Wall 的格子，每格边长为 1，
+// 格子 (x, y) 占据 [x, x+1) × [y, y+1)。坐标系与俯视图一致（y 轴向下）。
+// 相机平滑地跟随玩家的位置和朝向，游戏状态仍然只由 MazeGame 决定，
+// 所以两种视图可以随时切换。
+
+use crate::i18n::{tr, Text};
+use crate::theme::{Palette, Style};
+use crate::minimap;
+use crate::{alt_path_color, draw_label, Cell, Facing, MazeGame, Position};
+use macroquad::prelude::*;
+use std::f32::consts::{PI, TAU};
+
+const FOV_SCALE: f32 = 0.66; // 相机平面长度，约 66° 视角
+const STRIP_WIDTH: f32 = 2.0; // 每条光线绘制的列宽（像素）
+const MAX_STEPS: usize = 256;
+const HUD_HEIGHT: f32 = 110.0;
+
+// 光线命中的墙面
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hit {
+    pub distance: f32, // 沿视线方向的垂直距离，避免鱼眼变形
+    pub x_side: bool,  // 命中的是竖直方向的墙面（光线沿 x 方向穿过格子边界）
+}
+
+fn is_wall(game: &MazeGame, x: i32, y: i32) -> bool {
+    x < 0
+        || y < 0
+        || x as usize >= game.width
+        || y as usize >= game.height
+        || game.grid[y as usize][x as usize] == Cell::Wall
+}
+
+// 数字微分分析（DDA）逐格前进直到碰到墙；ray 不必是单位向量，
+// 返回的距离以 ray 的长度为单位（ray = dir + plane * k 时即为垂直距离）
+pub fn cast(game: &MazeGame, origin: Vec2, ray: Vec2) -> Option<Hit> {
+    let mut cell_x = origin.x.floor() as i32;
+    let mut cell_y = origin.y.floor() as i32;
+    let delta_x = if ray.x == 0.0 { f32::INFINITY } else { (1.0 / ray.x).abs() };
+    let delta_y = if ray.y == 0.0 { f32::INFINITY } else { (1.0 / ray.y).abs() };
+    let (step_x, mut side_x) = if ray.x < 0.0 {
+        (-1, (origin.x - cell_x as f32) * delta_x)
+    } else {
+        (1, (cell_x as f32 + 1.0 - origin.x) * delta_x)
+    };
+    let (step_y, mut side_y) = if ray.y < 0.0 {
+        (-1, (origin.y - cell_y as f32) * delta_y)
+    } else {
+        (1, (cell_y as f32 + 1.0 - origin.y) * delta_y)
+    };
+
+    for _ in 0..MAX_STEPS {
+        let x_side = side_x < side_y;
+        if x_side {
+            side_x += delta_x;
+            cell_x += step_x;
+        } else {
+            side_y += delta_y;
+            cell_y += step_y;
+        }
+        if is_wall(game, cell_x, cell_y) {
+            let distance = if x_side { side_x - delta_x } else { side_y - delta_y };
+            return Some(Hit { distance, x_side });
+        }
+    }
+    None
+}
+
+// 朝向对应的角度（弧度，0 为向右，y 轴向下所以向下为 π/2）
+fn facing_angle(facing: Facing) -> f32 {
+    match facing {
+        Facing::Right => 0.0,
+        Facing::Down => PI / 2.0,
+        Facing::Left => PI,
+        Facing::Up => -PI / 2.0,
+    }
+}
+
+fn cell_center(pos: Position) -> Vec2 {
+    vec2(pos.x as f32 + 0.5, pos.y as f32 + 0.5)
+}
+
+// 按距离把颜色混入背景色，远处更暗
+fn fog(color: Color, background: Color, distance: f32) -> Color {
+    let t = (distance / 12.0).clamp(0.0, 0.85);
+    Color::new(
+        color.r + (background.r - color.r) * t,
+        color.g + (background.g - color.g) * t,
+        color.b + (background.b - color.b) * t,
+        color.a,
+    )
+}
+
+fn shade(color: Color, factor: f32) -> Color {
+    Color::new(color.r * factor, color.g * factor, color.b * factor, color.a)
+}
+
+// 第一人称相机
+pub struct FirstPerson {
+    pos: Vec2,
+    angle: f32,
+}
+
+impl FirstPerson {
+    // 相机直接放在玩家所在位置
+    pub fn new(game: &MazeGame) -> Self {
+        FirstPerson {
+            pos: cell_center(game.player_pos),
+            angle: facing_angle(game.facing),
+        }
+    }
+
+    fn dir(&self) -> Vec2 {
+        vec2(self.angle.cos(), self.angle.sin())
+    }
+
+    fn plane(&self) -> Vec2 {
+        vec2(-self.angle.sin(), self.angle.cos()) * FOV_SCALE
+    }
+
+    // 第一人称操作：W/S 前进后退，A/D 原地左转右转。
+    // 后退时保持朝向不变；返回是否移动了位置
+    pub fn handle_input(game: &mut MazeGame, (dx, dy): (i32, i32)) -> bool {
+        let facing = game.facing;
+        match (dx, dy) {
+            (0, -1) => {
+                let (fx, fy) = facing.delta();
+                game.move_player(fx, fy)
+            }
+            (0, 1) => {
+                let (fx, fy) = facing.delta();
+                let moved = game.move_player(-fx, -fy);
+                game.facing = facing;
+                moved
+            }
+            (d, _) => {
+                let turn = if d > 0 { 1 } else { 3 };
+                game.facing = Facing::ALL[(facing as usize + turn) % 4];
+                false
+            }
+        }
+    }
+
+    // 平滑地靠近玩家当前的位置和朝向
+    pub fn follow(&mut self, game: &MazeGame, dt: f32) {
+        let k = 1.0 - (-dt * 14.0).exp();
+        self.pos += (cell_center(game.player_pos) - self.pos) * k;
+        let diff = (facing_angle(game.facing) - self.angle + PI).rem_euclid(TAU) - PI;
+        self.angle += diff * k;
+    }
+
+    // 把地面上的点投影到屏幕：返回屏幕 x、地面所在的 y 和深度
+    fn project(&self, point: Vec2, view: Vec2) -> Option<(f32, f32, f32)> {
+        let rel = point - self.pos;
+        let depth = rel.dot(self.dir());
+        if depth < 0.2 {
+            return None;
+        }
+        let plane = self.plane();
+        let camera_x = rel.dot(plane) / plane.length_squared() / depth;
+        let x = (camera_x + 1.0) * 0.5 * view.x;
+        let floor_y = view.y * 0.5 + view.y * 0.5 / depth;
+        Some((x, floor_y, depth))
+    }
+
+    // minimap_size 为 None 时不显示小地图
+    pub fn render(&self, game: &MazeGame, style: &Style, minimap_size: Option<f32>) {
+        let palette = style.palette;
+        let view = vec2(screen_width(), (screen_height() - HUD_HEIGHT).max(1.0));
+
+        // 天花板和地面
+        draw_rectangle(0.0, 0.0, view.x, view.y * 0.5, palette.background);
+        draw_rectangle(0.0, view.y * 0.5, view.x, view.y * 0.5, shade(palette.floor, 0.8));
+
+        // 墙：每列一条光线，同时记下深度用于遮挡地面标记
+        let (dir, plane) = (self.dir(), self.plane());
+        let columns = (view.x / STRIP_WIDTH).ceil() as usize;
+        let mut depth_buffer = vec![f32::INFINITY; columns];
+        for (column, depth) in depth_buffer.iter_mut().enumerate() {
+            let camera_x = 2.0 * (column as f32 + 0.5) / columns as f32 - 1.0;
+            let Some(hit) = cast(game, self.pos, dir + plane * camera_x) else {
+                continue;
+            };
+            *depth = hit.distance;
+            let height = view.y / hit.distance.max(0.05);
+            let top = (view.y - height) * 0.5;
+            let color = if hit.x_side { palette.wall } else { shade(palette.wall, 0.7) };
+            draw_rectangle(
+                column as f32 * STRIP_WIDTH,
+                top.max(0.0),
+                STRIP_WIDTH,
+                height.min(view.y),
+                fog(color, palette.background, hit.distance),
+            );
+        }
+
+        self.render_markers(game, palette, view, &depth_buffer);
+        if let Some(size) = minimap_size {
+            minimap::render(game, palette, size, self.pos, self.dir());
+        }
+        self.render_hud(game, style, view.y);
+    }
+
+    // 地面标记（提示路径、终点）和悬浮的物品，由远及近绘制
+    fn render_markers(&self, game: &MazeGame, palette: &Palette, view: Vec2, depth_buffer: &[f32]) {
+        // (格子, 半径, 颜色, 是否贴地)
+        let mut markers: Vec<(Position, f32, Color, bool)> = Vec::new();
+        let end_color = if game.exit_locked() { palette.end_locked } else { palette.end };
+        markers.push((game.end_pos, 0.4, end_color, true));
+        if game.show_path {
+            markers.extend(
+                game.path_positions
+                    .iter()
+                    .filter(|&&pos| pos != game.player_pos && pos != game.end_pos)
+                    .map(|&pos| (pos, 0.18, palette.path, true)),
+            );
+        }
+        for (i, path) in game.alt_paths.iter().enumerate() {
+            let color = alt_path_color(i);
+            markers.extend(
+                path.iter()
+                    .filter(|pos| !game.path_positions.contains(pos))
+                    .map(|&pos| (pos, 0.12, color, true)),
+            );
+        }
+        for (y, row) in game.grid.iter().enumerate() {
+            for (x, &cell) in row.iter().enumerate() {
+                let color = match cell {
+                    Cell::Coin => palette.coin,
+                    Cell::Gem => palette.gem,
+                    _ => continue,
+                };
+                markers.push((Position { x, y }, 0.15, color, false));
+            }
+        }
+
+        let mut projected: Vec<_> = markers
+            .into_iter()
+            .filter_map(|(pos, radius, color, on_floor)| {
+                let (x, floor_y, depth) = self.project(cell_center(pos), view)?;
+                let column = (x / STRIP_WIDTH) as usize;
+                let visible = depth_buffer.get(column).is_some_and(|&wall| depth < wall);
+                visible.then_some((x, floor_y, depth, radius, color, on_floor))
+            })
+            .collect();
+        projected.sort_by(|a, b| b.2.total_cmp(&a.2));
+
+        let scale = view.x * 0.5 / FOV_SCALE;
+        for (x, floor_y, depth, radius, color, on_floor) in projected {
+            let color = fog(color, palette.background, depth);
+            let rx = radius * scale / depth;
+            if on_floor {
+                // 地面上的圆在透视下压扁成椭圆
+                let near = view.y * 0.5 / (depth - radius).max(0.2);
+                let far = view.y * 0.5 / (depth + radius);
+                draw_ellipse(x, floor_y, rx, ((near - far) * 0.5).max(1.0), 0.0, color);
+            } else {
+                // 物品悬浮在半腰
+                let y = view.y * 0.5 + view.y * 0.2 / depth;
+                draw_circle(x, y, rx, color);
+            }
+        }
+    }
+
+    fn render_hud(&self, game: &MazeGame, style: &Style, top: f32) {
+        let [items_status, timer_status] = game.status_lines(style.language);
+        let lines = [tr(style.language, Text::FirstPersonHelp), &items_status, &timer_status];
+        for (i, line) in lines.iter().enumerate() {
+            draw_label(line, 10.0, top + 30.0 + i as f32 * 25.0, style);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn corridor() -> MazeGame {
+        MazeGame::from_text("#######\n#S   E#\n#######").unwrap()
+    }
+
+    #[test]
+    fn test_cast_measures_perpendicular_distance() {
+        let game = corridor();
+        let origin = vec2(1.5, 1.5);
+        let hit = cast(&game, origin, vec2(1.0, 0.0)).unwrap();
+        assert!((hit.distance - 4.5).abs() < 1e-4);
+        assert!(hit.x_side);
+
+        let hit = cast(&game, origin, vec2(0.0, -1.0)).unwrap();
+        assert!((hit.distance - 0.5).abs() < 1e-4);
+        assert!(!hit.x_side);
+
+        // 斜着的光线（dir + plane）命中时给出的是沿 dir 的距离
+        let hit = cast(&game, origin, vec2(1.0, 0.5)).unwrap();
+        assert!((hit.distance - 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_turn_and_move_relative_to_facing() {
+        let mut game = corridor();
+        game.facing = Facing::Up;
+        assert!(!FirstPerson::handle_input(&mut game, (0, -1)));
+        FirstPerson::handle_input(&mut game, (1, 0));
+        assert_eq!(game.facing, Facing::Right);
+        assert!(FirstPerson::handle_input(&mut game, (0, -1)));
+        assert!(FirstPerson::handle_input(&mut game, (0, -1)));
+        assert!(FirstPerson::handle_input(&mut game, (0, 1)));
+        assert_eq!(game.player_pos, Position { x: 2, y: 1 });
+        assert_eq!(game.facing, Facing::Right);
+        FirstPerson::handle_input(&mut game, (-1, 0));
+        FirstPerson::handle_input(&mut game, (-1, 0));
+        assert_eq!(game.facing, Facing::Left);
+
+        // 相机最终停在玩家位置、朝向玩家朝向
+        let mut camera = FirstPerson::new(&corridor());
+        for _ in 0..120 {
+            camera.follow(&game, 1.0 / 60.0);
+        }
+        assert!((camera.pos - vec2(2.5, 1.5)).length() < 1e-3);
+        assert!((camera.dir() - vec2(-1.0, 0.0)).length() < 1e-3);
+    }
+}
@@ -0,0 +1,226 @@
+// 路线规划
+//
+// 收集路线：求"先收集全部物品再前往终点"的最短访问顺序（小规模旅行商问题）。
+// dist[i][j] 为兴趣点 i 到 j 的最短步数，None 表示不可达。
+// 约定 0 号点为出发点，最后一个点为终点，中间的都是待收集物品。
+//
+// 多条路线：Yen 算法求前 k 条互不相同的最短简单路径（每步代价为 1）。
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+
+// 物品数量不超过该值时用状态压缩DP求精确解，否则退化为最近邻贪心
+const EXACT_LIMIT: usize = 12;
+
+// 返回物品的访问顺序（不含出发点和终点）；无法全部到达时返回 None
+pub fn plan_visit_order(dist: &[Vec<Option<usize>>]) -> Option<Vec<usize>> {
+    let n = dist.len();
+    if n < 2 {
+        return None;
+    }
+    let items: Vec<usize> = (1..n - 1).collect();
+
+    if items.is_empty() {
+        return dist[0][n - 1].map(|_| Vec::new());
+    }
+
+    if items.len() <= EXACT_LIMIT {
+        held_karp(dist, &items)
+    } else {
+        nearest_neighbor(dist, &items)
+    }
+}
+
+// 状态压缩DP：best[mask][k] 表示访问完 mask 中的物品且停在第 k 个物品时的最短步数
+fn held_karp(dist: &[Vec<Option<usize>>], items: &[usize]) -> Option<Vec<usize>> {
+    let k = items.len();
+    let end = dist.len() - 1;
+    let full = (1usize << k) - 1;
+    let mut best = vec![vec![usize::MAX; k]; 1 << k];
+    let mut prev = vec![vec![usize::MAX; k]; 1 << k];
+
+    for (i, &item) in items.iter().enumerate() {
+        if let Some(d) = dist[0][item] {
+            best[1 << i][i] = d;
+        }
+    }
+
+    for mask in 1..=full {
+        for last in 0..k {
+            let cost = best[mask][last];
+            if cost == usize::MAX || mask & (1 << last) == 0 {
+                continue;
+            }
+            for next in 0..k {
+                if mask & (1 << next) != 0 {
+                    continue;
+                }
+                if let Some(d) = dist[items[last]][items[next]] {
+                    let next_mask = mask | (1 << next);
+                    if cost + d < best[next_mask][next] {
+                        best[next_mask][next] = cost + d;
+                        prev[next_mask][next] = last;
+                    }
+                }
+            }
+        }
+    }
+
+    // 选出加上最后一段到终点后总长最短的结尾物品
+    let mut last = (0..k)
+        .filter(|&i| best[full][i] != usize::MAX)
+        .filter_map(|i| dist[items[i]][end].map(|d| (best[full][i] + d, i)))
+        .min()?
+        .1;
+
+    // 回溯访问顺序
+    let mut order = Vec::with_capacity(k);
+    let mut mask = full;
+    loop {
+        order.push(items[last]);
+        let before = prev[mask][last];
+        mask &= !(1 << last);
+        if mask == 0 {
+            break;
+        }
+        last = before;
+    }
+    order.reverse();
+    Some(order)
+}
+
+// 最近邻贪心：每次前往最近的未收集物品
+fn nearest_neighbor(dist: &[Vec<Option<usize>>], items: &[usize]) -> Option<Vec<usize>> {
+    let end = dist.len() - 1;
+    let mut remaining = items.to_vec();
+    let mut order = Vec::with_capacity(items.len());
+    let mut current = 0;
+
+    while !remaining.is_empty() {
+        let (index, _) = remaining
+            .iter()
+            .enumerate()
+            .filter_map(|(i, &item)| dist[current][item].map(|d| (i, d)))
+            .min_by_key(|&(_, d)| d)?;
+        current = remaining.swap_remove(index);
+        order.push(current);
+    }
+
+    dist[current][end].map(|_| order)
+}
+
+// 广度优先搜索 from 到 to 的最短路径（含两端），跳过被禁用的节点和边
+fn bfs_avoiding<N, F>(from: N, to: N, neighbors: &F, nodes: &HashSet<N>, edges: &HashSet<(N, N)>) -> Option<Vec<N>>
+where
+    N: Copy + Eq + Hash,
+    F: Fn(N) -> Vec<N>,
+{
+    let mut parent = HashMap::from([(from, from)]);
+    let mut queue = VecDeque::from([from]);
+    while let Some(current) = queue.pop_front() {
+        if current == to {
+            let mut path = vec![to];
+            let mut step = to;
+            while step != from {
+                step = parent[&step];
+                path.push(step);
+            }
+            path.reverse();
+            return Some(path);
+        }
+        for next in neighbors(current) {
+            if nodes.contains(&next) || edges.contains(&(current, next)) || parent.contains_key(&next) {
+                continue;
+            }
+            parent.insert(next, current);
+            queue.push_back(next);
+        }
+    }
+    None
+}
+
+// 按长度从短到长返回最多 k 条 from 到 to 的不同路径（含两端）
+pub fn k_shortest_paths<N, F>(from: N, to: N, k: usize, neighbors: F) -> Vec<Vec<N>>
+where
+    N: Copy + Eq + Hash,
+    F: Fn(N) -> Vec<N>,
+{
+    let none = HashSet::new();
+    let Some(first) = bfs_avoiding(from, to, &neighbors, &none, &HashSet::new()) else {
+        return Vec::new();
+    };
+    let mut found = vec![first];
+    let mut candidates: Vec<Vec<N>> = Vec::new();
+
+    while found.len() < k {
+        let last = found.last().expect("至少有一条路径");
+        // 依次以上一条路径上的每个点为分叉点，禁止已知路径在此处走过的边
+        for spur in 0..last.len() - 1 {
+            let root = &last[..=spur];
+            let edges: HashSet<(N, N)> = found
+                .iter()
+                .filter(|path| path.len() > spur + 1 && &path[..=spur] == root)
+                .map(|path| (path[spur], path[spur + 1]))
+                .collect();
+            let nodes: HashSet<N> = root[..spur].iter().copied().collect();
+            if let Some(tail) = bfs_avoiding(last[spur], to, &neighbors, &nodes, &edges) {
+                let path: Vec<N> = root[..spur].iter().copied().chain(tail).collect();
+                if !found.contains(&path) && !candidates.contains(&path) {
+                    candidates.push(path);
+                }
+            }
+        }
+        let Some(shortest) = (0..candidates.len()).min_by_key(|&i| candidates[i].len()) else {
+            break;
+        };
+        found.push(candidates.remove(shortest));
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 一维直线上的点，距离为坐标差
+    fn line_distances(coords: &[usize]) -> Vec<Vec<Option<usize>>> {
+        coords
+            .iter()
+            .map(|&a| coords.iter().map(|&b| Some(a.abs_diff(b))).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_exact_order_on_line() {
+        // 出发点 0，物品在 5、2、8，终点 10
+        let dist = line_distances(&[0, 5, 2, 8, 10]);
+        assert_eq!(plan_visit_order(&dist), Some(vec![2, 1, 3]));
+    }
+
+    #[test]
+    fn test_unreachable_item() {
+        let mut dist = line_distances(&[0, 3, 6]);
+        dist[0][1] = None;
+        dist[2][1] = None;
+        assert_eq!(plan_visit_order(&dist), None);
+    }
+
+    #[test]
+    fn test_k_shortest_paths_on_grid() {
+        // 3x3 网格上从左上角到右下角有 6 条最短路径
+        let neighbors = |(x, y): (i32, i32)| {
+            [(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)]
+                .into_iter()
+                .filter(|&(x, y)| (0..3).contains(&x) && (0..3).contains(&y))
+                .collect::<Vec<_>>()
+        };
+        let paths = k_shortest_paths((0, 0), (2, 2), 8, neighbors);
+        assert_eq!(paths.len(), 8);
+        assert!(paths[..6].iter().all(|p| p.len() == 5));
+        assert!(paths[6..].iter().all(|p| p.len() == 7));
+        let distinct: HashSet<_> = paths.iter().collect();
+        assert_eq!(distinct.len(), 8);
+
+        assert!(k_shortest_paths(0, 3, 3, |n: i32| if n < 2 { vec![n + 1] } else { vec![] }).is_empty());
+    }
+}
@@ -0,0 +1,1065 @@
+// 场景状态机：标题菜单、设置、关卡选择、游戏中、暂停、结算、迷宫编辑器
+
+use crate::agents::AutoPlay;
+use crate::assets::{self, Assets};
+use crate::audio::{Audio, Effect};
+use crate::editor::Editor;
+use crate::effects::Effects;
+use crate::generator;
+use crate::markers;
+use crate::i18n::{fill, tr, Text};
+use crate::level::Campaign;
+use crate::menu::{draw_overlay, read_menu_input, Menu, MenuInput};
+use crate::metrics::Metrics;
+use crate::minimap;
+use crate::net::Client;
+use crate::online::Online;
+use crate::race::Race;
+use crate::raycast::FirstPerson;
+use crate::solvers::{self, Animation, Solver};
+use crate::settings::{self, Entry, Settings, WallStyle};
+use crate::theme::{Fonts, Style, Themes};
+use crate::topology::{Shape, ShapedGame};
+use crate::trail;
+use crate::{draw_label, MazeGame, Position};
+use macroquad::prelude::*;
+use std::path::PathBuf;
+
+// 场景
+enum Scene {
+    Title(Menu),
+    Settings { menu: Menu, back: Box<Scene> },
+    LevelSelect { menu: Menu, back: Box<Scene> },
+    Playing,
+    Paused(Menu),
+    Results(Menu),
+    Editor,
+}
+
+// 菜单动作
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Action {
+    Play,
+    QuickPlay,
+    Race,
+    Resume,
+    Restart,
+    NextLevel,
+    LevelSelect,
+    Settings,
+    Editor,
+    Title,
+    Quit,
+}
+
+impl Action {
+    fn text(self) -> Text {
+        match self {
+            Action::Play => Text::PlayCampaign,
+            Action::QuickPlay => Text::QuickPlay,
+            Action::Race => Text::Race,
+            Action::Resume => Text::Resume,
+            Action::Restart => Text::RestartLevel,
+            Action::NextLevel => Text::NextLevel,
+            Action::LevelSelect => Text::SelectLevel,
+            Action::Settings => Text::Settings,
+            Action::Editor => Text::Editor,
+            Action::Title => Text::BackToTitle,
+            Action::Quit => Text::Quit,
+        }
+    }
+}
+
+// 按住的方向键，用于连续移动
+struct HeldKey {
+    key: KeyCode,
+    dir: (i32, i32),
+    timer: f32, // 距离下一次重复移动的剩余时间
+}
+
+// 一号玩家的移动键；双人竞速时二号玩家使用方向键
+const WASD: [(KeyCode, (i32, i32)); 4] = [
+    (KeyCode::W, (0, -1)),
+    (KeyCode::S, (0, 1)),
+    (KeyCode::A, (-1, 0)),
+    (KeyCode::D, (1, 0)),
+];
+const ARROWS: [(KeyCode, (i32, i32)); 4] = [
+    (KeyCode::Up, (0, -1)),
+    (KeyCode::Down, (0, 1)),
+    (KeyCode::Left, (-1, 0)),
+    (KeyCode::Right, (1, 0)),
+];
+// 六边形、三角形和圆形网格的斜向移动键（多层迷宫中 Q 和 E 用来上下楼）
+const DIAGONALS: [(KeyCode, (i32, i32)); 4] = [
+    (KeyCode::Q, (-1, -1)),
+    (KeyCode::E, (1, -1)),
+    (KeyCode::Z, (-1, 1)),
+    (KeyCode::C, (1, 1)),
+];
+
+// 战役中过关后自动进入下一关前停留在结算界面的秒数
+const AUTO_ADVANCE_DELAY: f32 = 3.0;
+
+pub struct App {
+    campaign: Campaign,
+    game: MazeGame,
+    settings: Settings,
+    settings_path: Option<PathBuf>, // 设置文件位置，为 None 时不保存
+    themes: Themes,
+    assets: Assets,
+    fonts: Fonts,
+    reload_timer: f32, // 距离下一次检查资源变化的时间
+    scene: Scene,
+    held: [Option<HeldKey>; 2], // 两名玩家各自按住的键，单人游戏只用第一个
+    quick_play_seed: Option<u64>, // 快速游戏时为当前随机迷宫的种子，否则为战役模式
+    shaped: Option<ShapedGame>,   // 非方格形状的快速游戏，此时不使用 game
+    first_person: Option<FirstPerson>, // 第一人称视图的相机，为 None 时是俯视图
+    metrics: Option<Result<Metrics, String>>, // 按 I 显示的当前关卡难度指标，关卡开始时计算
+    editor: Editor,                           // 离开编辑器后保留内容
+    audio: Audio,
+    effects: Effects,
+    solver: Option<Animation>, // 按 L 演示的解法，只用于俯视图
+    race: Option<Race>,        // 双人竞速，此时 game 只提供迷宫
+    online: Option<Online>,    // 联网竞速，离开后回到标题菜单
+    autoplay: Option<AutoPlay>, // 按 G 由智能体代替玩家移动，只用于俯视图
+    advance_timer: Option<f32>, // 战役过关后距离自动进入下一关的时间
+}
+
+impl App {
+    pub fn new(
+        campaign: Campaign,
+        settings: Settings,
+        settings_path: Option<PathBuf>,
+        assets: Assets,
+        fonts: Fonts,
+        themes: Themes,
+        audio: Audio,
+    ) -> Self {
+        let mut settings = settings;
+        if !themes.contains(&settings.theme) {
+            settings.theme = Settings::default().theme;
+        }
+        // 战役进度保存在设置文件旁边
+        let mut campaign = campaign;
+        if let Some(path) = settings_path.as_ref().map(|path| path.with_file_name("progress.json")) {
+            campaign.load_progress(&path);
+        }
+        let mut app = App {
+            game: campaign.build_current(),
+            campaign,
+            themes,
+            settings,
+            settings_path,
+            assets,
+            fonts,
+            reload_timer: 0.0,
+            scene: Scene::Title(Menu::new()),
+            held: [None, None],
+            quick_play_seed: None,
+            shaped: None,
+            first_person: None,
+            metrics: None,
+            editor: Editor::new(settings::editor_maze_path()),
+            audio,
+            effects: Effects::default(),
+            solver: None,
+            race: None,
+            online: None,
+            autoplay: None,
+            advance_timer: None,
+        };
+        app.apply_settings();
+        app.audio.start_music(&app.settings);
+        app
+    }
+
+    pub fn background(&self) -> Color {
+        self.themes.get(&self.settings.theme).palette.background
+    }
+
+    fn style(&self) -> Style<'_> {
+        let theme = self.themes.get(&self.settings.theme);
+        Style {
+            fonts: &self.fonts,
+            palette: &theme.palette,
+            atlas: theme.atlas.as_ref(),
+            max_cell_size: self.settings.cell_size,
+            language: self.settings.language,
+        }
+    }
+
+    // 当前场景菜单中的动作
+    fn actions(&self) -> Vec<Action> {
+        match &self.scene {
+            Scene::Title(_) => vec![
+                Action::Play,
+                Action::QuickPlay,
+                Action::Race,
+                Action::LevelSelect,
+                Action::Settings,
+                Action::Editor,
+                Action::Quit,
+            ],
+            Scene::Paused(_) => vec![
+                Action::Resume,
+                Action::Restart,
+                Action::LevelSelect,
+                Action::Settings,
+                Action::Title,
+            ],
+            Scene::Results(_) if self.quick_play_seed.is_some() || !self.campaign.is_last() => {
+                vec![Action::NextLevel, Action::Restart, Action::LevelSelect, Action::Title]
+            }
+            Scene::Results(_) => vec![Action::Restart, Action::LevelSelect, Action::Title],
+            _ => Vec::new(),
+        }
+    }
+
+    // 把设置同步到当前游戏
+    fn apply_settings(&mut self) {
+        self.game.hint_mode = self.settings.hint_mode;
+        self.game.hint_routes = self.settings.hint_routes;
+        self.game.trail_overlay = self.settings.trail;
+        self.game.set_thin_walls(self.settings.wall_style == WallStyle::Thin);
+        self.audio.apply(&self.settings);
+    }
+
+    // 开始（或重新开始）当前关卡
+    fn start_level(&mut self) {
+        let settings = &self.settings;
+        // 双人竞速只使用方格迷宫
+        self.shaped = match self.quick_play_seed {
+            Some(seed) if settings.shape != Shape::Square && self.race.is_none() => Some(ShapedGame::new(
+                settings.shape,
+                settings.maze_width,
+                settings.maze_height,
+                seed,
+            )),
+            _ => None,
+        };
+        self.game = match self.quick_play_seed {
+            Some(seed) => {
+                let params = self.settings.quick_play_params(seed);
+                MazeGame::from_grid(generator::generate(&params)).expect("生成的迷宫应当有效")
+            }
+            None => self.campaign.build_current(),
+        };
+        // 设置关闭时终点总是开启，否则按关卡自己的规则（见关卡清单的 require_all）
+        self.game.require_all_items &= self.settings.require_all_items;
+        self.apply_settings();
+        if self.race.is_some() {
+            self.race = Some(Race::new(&self.game.initial_grid));
+        }
+        if self.first_person.is_some() {
+            self.first_person = Some(FirstPerson::new(&self.game));
+        }
+        if self.metrics.is_some() {
+            self.metrics = Some(Metrics::analyze(&self.game));
+        }
+        self.effects.clear();
+        self.solver = None;
+        self.autoplay = None;
+        self.held = [None, None];
+        self.scene = Scene::Playing;
+    }
+
+    // 加入联网竞速（见 net.rs），比赛状态完全由服务器决定
+    pub fn join_online(&mut self, client: Client) {
+        self.online = Some(Online::new(client));
+        self.held = [None, None];
+        self.scene = Scene::Playing;
+    }
+
+    // 执行菜单动作；返回 false 表示退出游戏
+    fn perform(&mut self, action: Action) -> bool {
+        self.advance_timer = None;
+        match action {
+            Action::Play => {
+                self.quick_play_seed = None;
+                self.race = None;
+                self.start_level();
+            }
+            Action::QuickPlay | Action::Race => {
+                self.quick_play_seed = Some(random_seed());
+                // 占位，start_level 会用新生成的迷宫重新创建
+                self.race = (action == Action::Race).then(|| Race::new(&self.game.initial_grid));
+                self.start_level();
+            }
+            Action::Restart => self.start_level(),
+            Action::Resume => self.scene = Scene::Playing,
+            Action::NextLevel => {
+                if self.quick_play_seed.is_some() {
+                    self.quick_play_seed = Some(random_seed());
+                } else {
+                    self.campaign.advance();
+                    self.save_progress();
+                }
+                self.start_level();
+            }
+            Action::LevelSelect | Action::Settings => {
+                let back = std::mem::replace(&mut self.scene, Scene::Playing);
+                let mut menu = Menu::new();
+                self.scene = if action == Action::Settings {
+                    Scene::Settings { menu, back: Box::new(back) }
+                } else {
+                    menu.cursor = self.campaign.current;
+                    Scene::LevelSelect { menu, back: Box::new(back) }
+                };
+            }
+            Action::Editor => self.scene = Scene::Editor,
+            Action::Title => self.scene = Scene::Title(Menu::new()),
+            Action::Quit => return false,
+        }
+        true
+    }
+
+    // 处理菜单类场景的输入；返回 false 表示退出游戏
+    fn handle_menu_input(&mut self, input: MenuInput) -> bool {
+        let actions = self.actions();
+        let settings_len = Entry::ALL.len();
+        let levels_len = self.campaign.levels.len();
+        let themes = self.themes.names();
+
+        match &mut self.scene {
+            Scene::Title(menu) | Scene::Results(menu) => {
+                if let Some(index) = menu.handle(input, actions.len()) {
+                    return self.perform(actions[index]);
+                }
+            }
+            Scene::Paused(menu) => {
+                if input == MenuInput::Back {
+                    self.scene = Scene::Playing;
+                } else if let Some(index) = menu.handle(input, actions.len()) {
+                    return self.perform(actions[index]);
+                }
+            }
+            Scene::Settings { menu, .. } => {
+                // 最后一项为"返回"
+                let entry = Entry::ALL.get(menu.cursor).copied();
+                let selected = menu.handle(input, settings_len + 1);
+                match (input, entry) {
+                    (MenuInput::Left, Some(entry)) => self.settings.adjust(entry, -1, &themes),
+                    (MenuInput::Right, Some(entry)) => self.settings.adjust(entry, 1, &themes),
+                    _ => {}
+                }
+                // 调整音量时立即能听到变化
+                self.audio.apply(&self.settings);
+                if input == MenuInput::Back || selected == Some(settings_len) {
+                    self.go_back();
+                }
+            }
+            Scene::LevelSelect { menu, .. } => {
+                if input == MenuInput::Back {
+                    self.go_back();
+                } else if let Some(index) = menu.handle(input, levels_len) {
+                    if self.campaign.select(index) {
+                        self.save_progress();
+                        self.quick_play_seed = None;
+                        self.race = None;
+                        self.start_level();
+                    }
+                }
+            }
+            Scene::Playing | Scene::Editor => {}
+        }
+        true
+    }
+
+    fn save_settings(&self) {
+        if let Some(path) = &self.settings_path {
+            if let Err(e) = self.settings.save(path) {
+                eprintln!("保存设置失败: {}", e);
+            }
+        }
+    }
+
+    fn save_progress(&self) {
+        if let Some(path) = &self.settings_path {
+            if let Err(e) = self.campaign.save_progress(&path.with_file_name("progress.json")) {
+                eprintln!("保存战役进度失败: {}", e);
+            }
+        }
+    }
+
+    // 从设置或关卡选择返回上一个场景；离开设置界面时保存设置
+    fn go_back(&mut self) {
+        let scene = std::mem::replace(&mut self.scene, Scene::Playing);
+        self.scene = match scene {
+            Scene::Settings { back, .. } => {
+                self.save_settings();
+                *back
+            }
+            Scene::LevelSelect { back, .. } => *back,
+            other => other,
+        };
+        self.apply_settings();
+    }
+
+    // 读取第 player 名玩家的移动输入
+    fn movement_input(&mut self, player: usize) -> Option<(i32, i32)> {
+        let diagonals = player == 0 && self.shaped.as_ref().is_some_and(|g| g.shape.uses_diagonals());
+        let keys = match (player, diagonals) {
+            (0, true) => [WASD, DIAGONALS].concat(),
+            (0, false) => WASD.to_vec(),
+            _ => ARROWS.to_vec(),
+        };
+        read_movement(&keys, &mut self.held[player], self.settings.key_repeat.timing())
+    }
+
+    fn update_playing(&mut self) {
+        if self.online.is_some() {
+            self.update_online();
+            return;
+        }
+        if is_key_pressed(KeyCode::Escape) {
+            self.held = [None, None];
+            self.scene = Scene::Paused(Menu::new());
+            return;
+        }
+        if self.race.is_some() {
+            self.update_race();
+            return;
+        }
+
+        let toggle_path = is_key_pressed(KeyCode::P);
+        let reset = is_key_pressed(KeyCode::R);
+        if is_key_pressed(KeyCode::T) {
+            // 游戏中直接切换主题
+            self.settings.adjust(Entry::Theme, 1, &self.themes.names());
+            self.save_settings();
+        }
+        // 第一人称视图只支持方格迷宫
+        if is_key_pressed(KeyCode::V) && self.shaped.is_none() {
+            self.autoplay = None;
+            self.first_person = match self.first_person {
+                Some(_) => None,
+                None => Some(FirstPerson::new(&self.game)),
+            };
+        }
+        if is_key_pressed(KeyCode::I) {
+            self.metrics = match self.metrics {
+                Some(_) => None,
+                None => Some(Metrics::analyze(&self.game)),
+            };
+        }
+        if is_key_pressed(KeyCode::M) {
+            self.settings.minimap = !self.settings.minimap;
+            self.save_settings();
+        }
+        if is_key_pressed(KeyCode::B) {
+            self.settings.adjust(Entry::Trail, 1, &[]);
+            self.game.trail_overlay = self.settings.trail;
+            self.save_settings();
+        }
+        if is_key_pressed(KeyCode::N) {
+            self.settings.adjust(Entry::Mute, 1, &[]);
+            self.audio.apply(&self.settings);
+            self.save_settings();
+        }
+        for (key, delta) in [(KeyCode::Minus, -1), (KeyCode::Equal, 1)] {
+            if is_key_pressed(key) {
+                self.settings.adjust(Entry::MinimapSize, delta, &[]);
+                self.save_settings();
+            }
+        }
+        // 记号只用于方格迷宫；鼠标点击只在俯视图中有效
+        if self.shaped.is_none() {
+            if is_key_pressed(KeyCode::F) {
+                let pos = self.game.player_pos;
+                markers::cycle(&mut self.game, pos);
+            }
+            if is_key_pressed(KeyCode::X) {
+                markers::fill_dead_ends(&mut self.game);
+            }
+            if self.first_person.is_none() {
+                let cell_size = self.game.cell_size(self.settings.cell_size);
+                let (mx, my) = mouse_position();
+                let pos = Position { x: (mx / cell_size) as usize, y: (my / cell_size) as usize };
+                if mx >= 0.0 && my >= 0.0 {
+                    if is_mouse_button_pressed(MouseButton::Left) {
+                        markers::cycle(&mut self.game, pos);
+                    } else if is_mouse_button_pressed(MouseButton::Right) {
+                        markers::remove(&mut self.game, pos);
+                    }
+                }
+            }
+        }
+        let dt = get_frame_time();
+        let mut step = self.movement_input(0);
+        // 手动移动时停止自动游戏
+        if step.is_some() {
+            self.autoplay = None;
+        } else if is_key_pressed(KeyCode::G) && self.shaped.is_none() && self.first_person.is_none() {
+            self.autoplay = match self.autoplay {
+                Some(_) => None,
+                None => Some(AutoPlay::new(self.settings.agent)),
+            };
+        }
+        if let Some(autoplay) = &mut self.autoplay {
+            step = autoplay.update(&self.game, dt, self.settings.agent_speed as f32);
+        }
+        if toggle_path {
+            self.audio.play(Effect::Chime, &self.settings);
+        }
+
+        if reset {
+            self.effects.clear();
+            self.solver = None;
+            self.autoplay = None;
+        }
+        // 从玩家当前位置依次演示各种解法，最后一种之后关闭
+        if is_key_pressed(KeyCode::L) && self.shaped.is_none() && self.first_person.is_none() {
+            let next = match &self.solver {
+                None => Some(Solver::ALL[0]),
+                Some(animation) => Solver::ALL.iter().skip_while(|&&s| s != animation.solver()).nth(1).copied(),
+            };
+            self.solver = next.map(|solver| Animation::new(solvers::solve(&self.game, solver)));
+        }
+        if let Some(animation) = &mut self.solver {
+            animation.update(dt);
+        }
+
+        // 有移动输入时：Some(true) 为走了一步，Some(false) 为被挡住
+        let mut moved = None;
+        let before = self.game.player_pos;
+        let won = match &mut self.shaped {
+            Some(shaped) => {
+                if toggle_path {
+                    shaped.toggle_path();
+                }
+                if reset {
+                    shaped.reset_game();
+                }
+                if let Some((dx, dy)) = step {
+                    moved = Some(shaped.move_player(dx, dy));
+                }
+                if is_key_pressed(KeyCode::E) {
+                    shaped.climb(1);
+                }
+                if is_key_pressed(KeyCode::Q) {
+                    shaped.climb(-1);
+                }
+                shaped.tick(dt);
+                shaped.has_won()
+            }
+            None => {
+                if toggle_path {
+                    self.game.toggle_path();
+                }
+                if reset {
+                    self.game.reset_game();
+                }
+                match (step, &mut self.first_person) {
+                    // 第一人称下 A/D 是原地转身，不算被挡住
+                    (Some(step), Some(_)) => {
+                        let forward = FirstPerson::handle_input(&mut self.game, step);
+                        moved = (forward || step.0 == 0).then_some(forward);
+                    }
+                    (Some((dx, dy)), None) => {
+                        moved = Some(self.game.move_player(dx, dy));
+                    }
+                    (None, _) => {}
+                }
+                if let Some(camera) = &mut self.first_person {
+                    camera.follow(&self.game, dt);
+                }
+                self.game.tick(dt);
+                self.game.has_won()
+            }
+        };
+
+        match (moved, step) {
+            (Some(true), _) => {
+                self.audio.play(Effect::Step, &self.settings);
+                if self.shaped.is_none() {
+                    let after = self.game.player_pos;
+                    let dir = (
+                        (after.x as i32 - before.x as i32).signum(),
+                        (after.y as i32 - before.y as i32).signum(),
+                    );
+                    self.effects.step(before, dir);
+                }
+            }
+            (Some(false), Some(step)) => {
+                self.audio.play(Effect::Bump, &self.settings);
+                self.effects.bump(step);
+            }
+            _ => {}
+        }
+        if won {
+            self.audio.play(Effect::Fanfare, &self.settings);
+            // 俯视图中彩纸从终点喷出，其他视图从屏幕中央
+            let end = self.game.end_pos;
+            let top_down = self.shaped.is_none() && self.first_person.is_none();
+            let origin = if top_down {
+                vec2(end.x as f32 + 0.5, end.y as f32 + 0.5) * self.game.cell_size(self.settings.cell_size)
+            } else {
+                vec2(screen_width(), screen_height()) / 2.0
+            };
+            let palette = &self.themes.get(&self.settings.theme).palette;
+            self.effects.celebrate(origin, top_down.then_some(end), palette);
+            if self.quick_play_seed.is_none() {
+                self.campaign.complete_current();
+                self.save_progress();
+                if !self.campaign.is_last() {
+                    self.advance_timer = Some(AUTO_ADVANCE_DELAY);
+                }
+            }
+            self.held = [None, None];
+            self.scene = Scene::Results(Menu::new());
+        }
+    }
+
+    // 联网竞速：移动发给服务器，按服务器的回复播放音效；Esc 断开并回到标题菜单
+    fn update_online(&mut self) {
+        if is_key_pressed(KeyCode::Escape) {
+            self.online = None;
+            self.held = [None, None];
+            self.scene = Scene::Title(Menu::new());
+            return;
+        }
+        let step = self.movement_input(0);
+        let Some(online) = &mut self.online else {
+            return;
+        };
+        if let Some((dx, dy)) = step {
+            online.send_move(dx, dy);
+        }
+        for effect in online.update(get_frame_time()) {
+            self.audio.play(effect, &self.settings);
+            if effect == Effect::Fanfare {
+                let palette = &self.themes.get(&self.settings.theme).palette;
+                self.effects.celebrate(vec2(screen_width(), screen_height()) / 2.0, None, palette);
+            }
+        }
+    }
+
+    // 双人竞速：两名玩家同时移动，先到终点的获胜
+    fn update_race(&mut self) {
+        let steps = [self.movement_input(0), self.movement_input(1)];
+        if is_key_pressed(KeyCode::N) {
+            self.settings.adjust(Entry::Mute, 1, &[]);
+            self.audio.apply(&self.settings);
+            self.save_settings();
+        }
+        let Some(race) = &mut self.race else {
+            return;
+        };
+        if is_key_pressed(KeyCode::V) {
+            race.split = !race.split;
+        }
+        if is_key_pressed(KeyCode::R) {
+            let split = race.split;
+            *race = Race::new(&self.game.initial_grid);
+            race.split = split;
+        }
+        for (player, step) in steps.into_iter().enumerate() {
+            if let Some((dx, dy)) = step {
+                let effect = if race.move_player(player, dx, dy) { Effect::Step } else { Effect::Bump };
+                self.audio.play(effect, &self.settings);
+            }
+        }
+        race.tick(get_frame_time());
+
+        if race.winner.is_some() {
+            self.audio.play(Effect::Fanfare, &self.settings);
+            let palette = &self.themes.get(&self.settings.theme).palette;
+            self.effects.celebrate(vec2(screen_width(), screen_height()) / 2.0, None, palette);
+            self.held = [None, None];
+            self.scene = Scene::Results(Menu::new());
+        }
+    }
+
+    // 开发时（debug 构建）每秒检查一次资源文件，有变化就重新加载
+    fn hot_reload(&mut self) {
+        self.reload_timer -= get_frame_time();
+        if self.reload_timer > 0.0 {
+            return;
+        }
+        self.reload_timer = 1.0;
+
+        let changed = self.assets.changed();
+        if changed.iter().any(|name| Fonts::is_font_asset(name)) {
+            self.fonts = Fonts::load(&mut self.assets);
+        }
+        if changed.iter().any(|name| Themes::is_theme_asset(name)) {
+            self.themes = Themes::load(&mut self.assets);
+        }
+        if changed.iter().any(|name| name == assets::CAMPAIGN) {
+            let path = self.assets.watch(assets::CAMPAIGN);
+            self.campaign.reload(path.as_deref());
+        }
+    }
+
+    // 每帧更新；返回 false 表示退出游戏
+    pub fn update(&mut self) -> bool {
+        if cfg!(debug_assertions) {
+            self.hot_reload();
+        }
+        self.effects.update(get_frame_time());
+        if let Scene::Playing = self.scene {
+            self.update_playing();
+            return true;
+        }
+        if let Scene::Editor = self.scene {
+            if !self.editor.update(self.settings.language) {
+                self.scene = Scene::Title(Menu::new());
+            }
+            return true;
+        }
+        if let Some(timer) = &mut self.advance_timer {
+            *timer -= get_frame_time();
+            if *timer <= 0.0 {
+                return self.perform(Action::NextLevel);
+            }
+        }
+        match read_menu_input() {
+            Some(input) => self.handle_menu_input(input),
+            None => true,
+        }
+    }
+
+    pub fn render(&self) {
+        let style = self.style();
+        let t = |text| tr(self.settings.language, text);
+        let labels = |actions: Vec<Action>| -> Vec<String> {
+            actions.into_iter().map(|a| t(a.text()).to_string()).collect()
+        };
+
+        match &self.scene {
+            Scene::Title(menu) => menu.render(t(Text::GameTitle), &labels(self.actions()), &style),
+            Scene::Settings { menu, .. } => {
+                let mut items = self.settings.entries();
+                items.push(t(Text::Back).to_string());
+                menu.render(t(Text::SettingsTitle), &items, &style);
+            }
+            Scene::LevelSelect { menu, .. } => {
+                let items = self.campaign.level_labels(self.settings.language);
+                menu.render(t(Text::SelectLevelTitle), &items, &style);
+            }
+            Scene::Playing => self.render_game(),
+            Scene::Editor => self.editor.render(&style),
+            Scene::Paused(menu) => {
+                self.render_game();
+                draw_overlay(&style);
+                menu.render(t(Text::Paused), &labels(self.actions()), &style);
+            }
+            Scene::Results(menu) => {
+                self.render_game();
+                draw_overlay(&style);
+                let actions = self.actions();
+                let top = 110.0 + actions.len() as f32 * 32.0 + 30.0;
+                let (title, lines) = match (&self.race, &self.shaped) {
+                    (Some(race), _) => {
+                        let winner = race.winner.unwrap_or_default() + 1;
+                        let lines = race.players.iter().enumerate().map(|(index, player)| {
+                            fill(
+                                t(Text::RacePlayerStatus),
+                                &[&(index + 1), &format!("{:.1}", player.elapsed), &player.moves],
+                            )
+                        });
+                        (fill(t(Text::RaceWinner), &[&winner]), lines.collect())
+                    }
+                    (None, shaped) => {
+                        let title = match self.quick_play_seed {
+                            Some(_) => Text::MazeSolved,
+                            None if self.campaign.is_last() => Text::CampaignComplete,
+                            None => Text::LevelComplete,
+                        };
+                        let lines = match shaped {
+                            Some(shaped) => {
+                                vec![fill(t(Text::TimerStatus), &[&format!("{:.1}", shaped.elapsed), &shaped.moves])]
+                            }
+                            None => {
+                                let visits = trail::stats(&self.game);
+                                vec![
+                                    fill(
+                                        t(Text::ResultStats),
+                                        &[
+                                            &format!("{:.1}", self.game.elapsed),
+                                            &self.game.moves,
+                                            &self.game.coins_collected,
+                                            &self.game.coins_total,
+                                            &self.game.gems_collected,
+                                            &self.game.gems_total,
+                                        ],
+                                    ),
+                                    fill(t(Text::VisitStats), &[&visits.cells, &visits.revisited, &visits.most]),
+                                ]
+                            }
+                        };
+                        (t(title).to_string(), lines)
+                    }
+                };
+                let mut lines = lines;
+                if let Some(timer) = self.advance_timer {
+                    lines.push(fill(t(Text::NextLevelIn), &[&timer.ceil()]));
+                }
+                menu.render(&title, &labels(actions), &style);
+                for (i, line) in lines.iter().enumerate() {
+                    draw_label(line, 40.0, top + i as f32 * 30.0, &style);
+                }
+            }
+        }
+        if matches!(self.scene, Scene::Playing | Scene::Paused(_) | Scene::Results(_)) {
+            self.effects.render_particles();
+        }
+
+        let missing = self.assets.missing();
+        if !missing.is_empty() {
+            let text = fill(t(Text::MissingAssets), &[&missing.join(", ")]);
+            draw_label(&text, 10.0, screen_height() - 40.0, &style);
+        }
+    }
+
+    fn render_game(&self) {
+        let style = self.style();
+        let language = self.settings.language;
+        // 撞墙抖动：平移整个画面
+        let shake = self.effects.shake_offset();
+        let shaken = |draw: &dyn Fn()| {
+            if shake != Vec2::ZERO {
+                let rect = Rect::new(-shake.x, -shake.y, screen_width(), screen_height());
+                set_camera(&Camera2D::from_display_rect(rect));
+            }
+            draw();
+            set_default_camera();
+        };
+        if let Some(online) = &self.online {
+            online.render(&style);
+            return;
+        }
+        if let Some(race) = &self.race {
+            race.render(&style);
+            let title = fill(
+                tr(language, Text::RaceTitle),
+                &[&self.game.width, &self.game.height, &self.quick_play_seed.unwrap_or_default()],
+            );
+            draw_label(&title, 10.0, screen_height() - 10.0, &style);
+            return;
+        }
+        if let Some(shaped) = &self.shaped {
+            shaken(&|| shaped.render(&style));
+            let title = fill(
+                tr(language, Text::QuickPlayShapeTitle),
+                &[&shaped.shape.display_name(language), &self.quick_play_seed.unwrap_or_default()],
+            );
+            draw_label(&title, 10.0, screen_height() - 10.0, &style);
+            return;
+        }
+
+        let minimap_size = self.settings.minimap.then_some(self.settings.minimap_size);
+        match &self.first_person {
+            Some(camera) => shaken(&|| camera.render(&self.game, &style, minimap_size)),
+            None => {
+                shaken(&|| {
+                    self.game.render(&style);
+                    let cell_size = self.game.cell_size(self.settings.cell_size);
+                    self.effects.render_world(style.palette, cell_size);
+                    if let Some(animation) = &self.solver {
+                        animation.render(style.palette, cell_size, &style);
+                    }
+                });
+                if let Some(size) = minimap_size {
+                    let player = vec2(self.game.player_pos.x as f32 + 0.5, self.game.player_pos.y as f32 + 0.5);
+                    let (dx, dy) = self.game.facing.delta();
+                    minimap::render(&self.game, style.palette, size, player, vec2(dx as f32, dy as f32));
+                }
+            }
+        }
+        if let Some(autoplay) = &self.autoplay {
+            let text = fill(tr(language, Text::AutoPlayStatus), &[&autoplay.kind.display_name(language)]);
+            draw_label(&text, screen_width() - 420.0, 25.0, &style);
+        }
+        if let Some(metrics) = &self.metrics {
+            let lines = match metrics {
+                Ok(metrics) => metrics.lines(language),
+                Err(_) => vec![tr(language, Text::Unsolvable).to_string()],
+            };
+            let mut panel = style.palette.background;
+            panel.a = 0.85;
+            draw_rectangle(0.0, 0.0, 340.0, 20.0 + lines.len() as f32 * 25.0, panel);
+            for (i, line) in lines.iter().enumerate() {
+                draw_label(line, 10.0, 25.0 + i as f32 * 25.0, &style);
+            }
+        }
+        let title = match self.quick_play_seed {
+            Some(seed) => fill(
+                tr(language, Text::QuickPlayTitle),
+                &[
+                    &self.game.width,
+                    &self.game.height,
+                    &self.settings.generator.display_name(language),
+                    &seed,
+                ],
+            ),
+            None => fill(
+                tr(language, Text::LevelTitle),
+                &[
+                    &(self.campaign.current + 1),
+                    &self.campaign.levels.len(),
+                    &self.campaign.current_level().display_name(language),
+                ],
+            ),
+        };
+        draw_label(&title, 10.0, screen_height() - 10.0, &style);
+    }
+}
+
+// 读取移动输入：按下立即移动，按住超过延迟后按间隔重复
+fn read_movement(
+    keys: &[(KeyCode, (i32, i32))],
+    held: &mut Option<HeldKey>,
+    timing: Option<(f32, f32)>,
+) -> Option<(i32, i32)> {
+    for &(key, dir) in keys {
+        if is_key_pressed(key) {
+            let timer = timing.map_or(0.0, |(delay, _)| delay);
+            *held = Some(HeldKey { key, dir, timer });
+            return Some(dir);
+        }
+    }
+
+    let (_, interval) = timing?;
+    let current = held.as_mut()?;
+    if !is_key_down(current.key) {
+        *held = None;
+        return None;
+    }
+    current.timer -= get_frame_time();
+    if current.timer <= 0.0 {
+        current.timer += interval;
+        return Some(current.dir);
+    }
+    None
+}
+
+// 用当前时间作为随机迷宫的种子
+fn random_seed() -> u64 {
+    (miniquad::date::now() * 1000.0) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn app() -> App {
+        App::new(
+            Campaign::builtin(),
+            Settings::default(),
+            None,
+            Assets::with_roots(Vec::new()),
+            Fonts::default(),
+            Themes::builtin(),
+            Audio::default(),
+        )
+    }
+
+    #[test]
+    fn test_title_to_playing_and_pause() {
+        let mut app = app();
+        assert!(matches!(app.scene, Scene::Title(_)));
+        assert!(app.handle_menu_input(MenuInput::Select));
+        assert!(matches!(app.scene, Scene::Playing));
+
+        app.scene = Scene::Paused(Menu::new());
+        app.handle_menu_input(MenuInput::Back);
+        assert!(matches!(app.scene, Scene::Playing));
+    }
+
+    #[test]
+    fn test_settings_returns_to_previous_scene() {
+        let mut app = app();
+        app.scene = Scene::Paused(Menu::new());
+        assert!(app.perform(Action::Settings));
+        app.handle_menu_input(MenuInput::Right);
+        assert_ne!(app.settings, Settings::default());
+        app.handle_menu_input(MenuInput::Back);
+        assert!(matches!(app.scene, Scene::Paused(_)));
+        assert_eq!(app.game.hint_mode, app.settings.hint_mode);
+    }
+
+    #[test]
+    fn test_unknown_theme_reset_and_switch_in_game() {
+        let settings = Settings {
+            theme: "missing".to_string(),
+            ..Default::default()
+        };
+        let mut app = App::new(
+            Campaign::builtin(),
+            settings,
+            None,
+            Assets::with_roots(Vec::new()),
+            Fonts::default(),
+            Themes::builtin(),
+            Audio::default(),
+        );
+        assert_eq!(app.settings.theme, "classic");
+        app.settings.adjust(Entry::Theme, 1, &app.themes.names());
+        assert_eq!(app.style().palette, &app.themes.get("dark").palette);
+    }
+
+    #[test]
+    fn test_quick_play_uses_settings() {
+        let mut app = app();
+        app.settings.maze_width = 15;
+        app.settings.maze_height = 11;
+        app.perform(Action::QuickPlay);
+        assert!(matches!(app.scene, Scene::Playing));
+        assert_eq!((app.game.width, app.game.height), (15, 11));
+        assert!(app.game.find_shortest_path().is_some());
+        assert!(app.shaped.is_none());
+        assert!(app.game.require_all_items);
+        app.settings.adjust(Entry::RequireAllItems, 1, &[]);
+        app.perform(Action::Restart);
+        assert!(!app.game.require_all_items && !app.game.exit_locked());
+
+        app.settings.shape = Shape::Polar;
+        app.perform(Action::NextLevel);
+        assert_eq!(app.shaped.as_ref().map(|g| g.shape), Some(Shape::Polar));
+        // 回到战役模式时不再使用形状迷宫
+        app.perform(Action::Play);
+        assert!(app.shaped.is_none());
+    }
+
+    #[test]
+    fn test_locked_level_cannot_be_selected() {
+        let mut app = app();
+        app.perform(Action::LevelSelect);
+        app.handle_menu_input(MenuInput::Down);
+        app.handle_menu_input(MenuInput::Select);
+        assert!(matches!(app.scene, Scene::LevelSelect { .. }));
+        assert!(!app.perform(Action::Quit));
+    }
+
+    #[test]
+    fn test_first_person_survives_level_restart() {
+        let mut app = app();
+        app.perform(Action::Play);
+        app.first_person = Some(FirstPerson::new(&app.game));
+        FirstPerson::handle_input(&mut app.game, (1, 0));
+        app.perform(Action::Restart);
+        assert!(app.first_person.is_some());
+        assert_eq!(app.game.moves, 0);
+    }
+
+    #[test]
+    fn test_race_uses_square_maze_and_restarts() {
+        let mut app = app();
+        app.settings.shape = Shape::Hex;
+        app.perform(Action::Race);
+        assert!(app.shaped.is_none());
+        let race = app.race.as_mut().unwrap();
+        assert_eq!(race.players[0].end_pos, app.game.end_pos);
+        race.players[1].moves = 3;
+
+        // 重新开始时保留当前迷宫，换到下一张时生成新的竞速
+        app.perform(Action::Restart);
+        assert_eq!(app.race.as_ref().map(|r| r.players[1].moves), Some(0));
+        app.perform(Action::NextLevel);
+        assert!(app.race.is_some());
+        app.perform(Action::QuickPlay);
+        assert!(app.race.is_none());
+    }
+}
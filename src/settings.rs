@@ -0,0 +1,438 @@
+// 游戏设置，保存在用户配置目录下的 JSON 文件中
+
+use crate::agents::AgentKind;
+use crate::generator::{Algorithm, GeneratorParams, PostProcess};
+use crate::i18n::{tr, Language, Text};
+use crate::theme::THEME_NAMES;
+use crate::topology::Shape;
+use crate::trail::TrailOverlay;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+// 提示路线模式
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HintMode {
+    Auto,     // 有可收集物品时显示收集路线，否则显示最短路线
+    Shortest, // 总是显示起点到终点的最短路线
+}
+
+// 按住方向键时的连续移动
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyRepeat {
+    Off,
+    Slow,
+    Fast,
+}
+
+// 墙的画法
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WallStyle {
+    Blocks, // 墙占据整个格子
+    Thin,   // 墙画在格子之间（关卡可以转换时）
+}
+
+impl KeyRepeat {
+    // 返回 (首次重复前的延迟, 重复间隔)，单位秒
+    pub fn timing(self) -> Option<(f32, f32)> {
+        match self {
+            KeyRepeat::Off => None,
+            KeyRepeat::Slow => Some((0.35, 0.15)),
+            KeyRepeat::Fast => Some((0.2, 0.06)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub hint_mode: HintMode,
+    pub key_repeat: KeyRepeat,
+    pub maze_width: usize, // 快速游戏的迷宫尺寸
+    pub maze_height: usize,
+    pub generator: Algorithm,
+    pub theme: String,
+    pub cell_size: f32, // 格子最大边长（像素），窗口放不下时会自动缩小
+    pub volume: f32,    // 音效音量，0.0 ~ 1.0
+    pub language: Language,
+    pub wall_style: WallStyle,
+    pub shape: Shape, // 快速游戏的迷宫形状
+    pub minimap: bool,
+    pub minimap_size: f32, // 小地图较长一边的像素数
+    pub braid: u32,        // 快速游戏打通的死路百分比
+    pub loops: usize,      // 快速游戏额外拆掉的墙数
+    pub hint_routes: usize, // 提示最短路线时一并显示的路线条数（含最短的一条）
+    pub music_volume: f32,  // 0.0 ~ 1.0
+    pub muted: bool,        // 游戏中按 N 切换
+    pub trail: TrailOverlay,
+    pub agent: AgentKind,   // 游戏中按 G 自动游戏时使用的智能体
+    pub agent_speed: u32,   // 自动游戏每秒的步数
+    pub require_all_items: bool, // 关闭时终点始终开启；开启时战役关卡仍按清单中的 require_all
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            hint_mode: HintMode::Auto,
+            key_repeat: KeyRepeat::Slow,
+            maze_width: 25,
+            maze_height: 17,
+            generator: Algorithm::Backtracker,
+            theme: THEME_NAMES[0].to_string(),
+            cell_size: 30.0,
+            volume: 0.8,
+            language: Language::English,
+            wall_style: WallStyle::Blocks,
+            shape: Shape::Square,
+            minimap: true,
+            minimap_size: 160.0,
+            braid: 0,
+            loops: 0,
+            hint_routes: 1,
+            music_volume: 0.5,
+            muted: false,
+            trail: TrailOverlay::Off,
+            agent: AgentKind::Bfs,
+            agent_speed: 8,
+            require_all_items: true,
+        }
+    }
+}
+
+// 设置界面中的条目，ALL 的顺序即显示顺序。
+// 游戏中的快捷键也通过条目调整：T 切换主题，- 和 = 调整小地图大小，N 静音，B 切换走过路线的显示
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Entry {
+    HintMode,
+    KeyRepeat,
+    MazeWidth,
+    MazeHeight,
+    Generator,
+    Theme,
+    CellSize,
+    Volume,
+    Language,
+    WallStyle,
+    Shape,
+    Minimap,
+    MinimapSize,
+    Braid,
+    Loops,
+    HintRoutes,
+    MusicVolume,
+    Mute,
+    Trail,
+    Agent,
+    AgentSpeed,
+    RequireAllItems,
+}
+
+impl Entry {
+    pub const ALL: [Entry; 22] = [
+        Entry::HintMode,
+        Entry::KeyRepeat,
+        Entry::MazeWidth,
+        Entry::MazeHeight,
+        Entry::Generator,
+        Entry::Theme,
+        Entry::CellSize,
+        Entry::Volume,
+        Entry::Language,
+        Entry::WallStyle,
+        Entry::Shape,
+        Entry::Minimap,
+        Entry::MinimapSize,
+        Entry::Braid,
+        Entry::Loops,
+        Entry::HintRoutes,
+        Entry::MusicVolume,
+        Entry::Mute,
+        Entry::Trail,
+        Entry::Agent,
+        Entry::AgentSpeed,
+        Entry::RequireAllItems,
+    ];
+}
+
+// 在选项列表中循环切换
+fn cycle<T: Clone + PartialEq>(options: &[T], current: &T, delta: i32) -> T {
+    let index = options.iter().position(|o| o == current).unwrap_or(0) as i32;
+    let len = options.len() as i32;
+    options[(index + delta).rem_euclid(len) as usize].clone()
+}
+
+// 按步长增减并限制在范围内
+fn step<T>(value: T, delta: i32, step: T, min: T, max: T) -> T
+where
+    T: Copy + PartialOrd + std::ops::Add<Output = T> + std::ops::Sub<Output = T>,
+{
+    let next = if delta > 0 { value + step } else if value < min + step { min } else { value - step };
+    if next < min {
+        min
+    } else if next > max {
+        max
+    } else {
+        next
+    }
+}
+
+// 配置文件路径：<用户配置目录>/maze_game/settings.json
+pub fn config_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("maze_game").join("settings.json"))
+}
+
+// 编辑器保存和读取的迷宫文件，与配置文件在同一目录
+pub fn editor_maze_path() -> Option<PathBuf> {
+    config_path().map(|path| path.with_file_name("editor_maze.txt"))
+}
+
+impl Settings {
+    // 从配置文件读取；文件不存在或无法解析时使用默认设置
+    pub fn load(path: &Path) -> Self {
+        match std::fs::read_to_string(path) {
+            Ok(text) => Settings::from_json(&text).unwrap_or_else(|e| {
+                eprintln!("配置文件 {} 无效，使用默认设置: {}", path.display(), e);
+                Settings::default()
+            }),
+            Err(_) => Settings::default(),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let text = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        std::fs::write(path, text).map_err(|e| format!("无法写入 {}: {}", path.display(), e))
+    }
+
+    fn from_json(text: &str) -> Result<Self, String> {
+        let mut settings: Settings = serde_json::from_str(text).map_err(|e| e.to_string())?;
+        // 修正超出范围的值；主题名要等主题加载后才能检查（见 App::new）
+        let defaults = Settings::default();
+        settings.maze_width = settings.maze_width.clamp(11, 61);
+        settings.maze_height = settings.maze_height.clamp(11, 41);
+        settings.cell_size = settings.cell_size.clamp(10.0, 40.0);
+        settings.volume = settings.volume.clamp(0.0, 1.0);
+        settings.music_volume = settings.music_volume.clamp(0.0, 1.0);
+        settings.minimap_size = settings.minimap_size.clamp(80.0, 320.0);
+        settings.braid = settings.braid.min(100);
+        settings.loops = settings.loops.min(40);
+        settings.hint_routes = settings.hint_routes.clamp(1, 4);
+        settings.agent_speed = settings.agent_speed.clamp(2, 30);
+        if settings.theme.is_empty() {
+            settings.theme = defaults.theme;
+        }
+        Ok(settings)
+    }
+
+    // 按当前设置生成快速游戏的迷宫参数
+    pub fn quick_play_params(&self, seed: u64) -> GeneratorParams {
+        let area = self.maze_width * self.maze_height;
+        GeneratorParams {
+            algorithm: self.generator,
+            width: self.maze_width,
+            height: self.maze_height,
+            seed,
+            coins: area / 150,
+            gems: area / 400,
+            post: PostProcess {
+                braid: self.braid as f64 / 100.0,
+                loops: self.loops,
+            },
+        }
+    }
+
+    // 设置界面中显示的条目（按当前语言）
+    pub fn entries(&self) -> Vec<String> {
+        Entry::ALL.into_iter().map(|entry| self.entry_label(entry)).collect()
+    }
+
+    // 一个条目的名称和当前值
+    pub fn entry_label(&self, entry: Entry) -> String {
+        let t = |text| tr(self.language, text);
+        let (name, value) = match entry {
+            Entry::HintMode => (
+                Text::HintRoute,
+                t(match self.hint_mode {
+                    HintMode::Auto => Text::HintAuto,
+                    HintMode::Shortest => Text::HintShortest,
+                })
+                .to_string(),
+            ),
+            Entry::KeyRepeat => (
+                Text::KeyRepeat,
+                t(match self.key_repeat {
+                    KeyRepeat::Off => Text::RepeatOff,
+                    KeyRepeat::Slow => Text::RepeatSlow,
+                    KeyRepeat::Fast => Text::RepeatFast,
+                })
+                .to_string(),
+            ),
+            Entry::MazeWidth => (Text::QuickPlayWidth, self.maze_width.to_string()),
+            Entry::MazeHeight => (Text::QuickPlayHeight, self.maze_height.to_string()),
+            Entry::Generator => (Text::Generator, self.generator.display_name(self.language).to_string()),
+            Entry::Theme => (Text::Theme, self.theme.clone()),
+            Entry::CellSize => (Text::CellSize, self.cell_size.to_string()),
+            Entry::Volume => (Text::SoundVolume, format!("{:.0}%", self.volume * 100.0)),
+            Entry::Language => (Text::LanguageLabel, self.language.native_name().to_string()),
+            Entry::WallStyle => (
+                Text::WallStyle,
+                t(match self.wall_style {
+                    WallStyle::Blocks => Text::WallBlocks,
+                    WallStyle::Thin => Text::WallThin,
+                })
+                .to_string(),
+            ),
+            Entry::Shape => (Text::Shape, self.shape.display_name(self.language).to_string()),
+            Entry::Minimap => (Text::Minimap, t(if self.minimap { Text::Shown } else { Text::Hidden }).to_string()),
+            Entry::MinimapSize => (Text::MinimapSize, self.minimap_size.to_string()),
+            Entry::Braid => (Text::Braid, format!("{}%", self.braid)),
+            Entry::Loops => (Text::Loops, self.loops.to_string()),
+            Entry::HintRoutes => (Text::HintRoutes, self.hint_routes.to_string()),
+            Entry::MusicVolume => (Text::MusicVolume, format!("{:.0}%", self.music_volume * 100.0)),
+            Entry::Mute => (Text::Mute, t(if self.muted { Text::On } else { Text::Off }).to_string()),
+            Entry::Trail => (Text::Trail, self.trail.display_name(self.language).to_string()),
+            Entry::Agent => (Text::AutoPlayAgent, self.agent.display_name(self.language).to_string()),
+            Entry::AgentSpeed => (Text::AutoPlaySpeed, self.agent_speed.to_string()),
+            Entry::RequireAllItems => {
+                (Text::RequireAllItems, t(if self.require_all_items { Text::On } else { Text::Off }).to_string())
+            }
+        };
+        format!("{}: {}", t(name), value)
+    }
+
+    // 调整一个条目，delta 为 +1 或 -1；themes 为可选的主题名
+    pub fn adjust(&mut self, entry: Entry, delta: i32, themes: &[String]) {
+        match entry {
+            Entry::HintMode => self.hint_mode = cycle(&[HintMode::Auto, HintMode::Shortest], &self.hint_mode, delta),
+            Entry::KeyRepeat => {
+                self.key_repeat = cycle(
+                    &[KeyRepeat::Off, KeyRepeat::Slow, KeyRepeat::Fast],
+                    &self.key_repeat,
+                    delta,
+                )
+            }
+            Entry::MazeWidth => self.maze_width = step(self.maze_width, delta, 2, 11, 61),
+            Entry::MazeHeight => self.maze_height = step(self.maze_height, delta, 2, 11, 41),
+            Entry::Generator => {
+                self.generator = cycle(&[Algorithm::Backtracker, Algorithm::Prim], &self.generator, delta)
+            }
+            Entry::Theme if !themes.is_empty() => self.theme = cycle(themes, &self.theme, delta),
+            Entry::Theme => {}
+            Entry::CellSize => self.cell_size = step(self.cell_size, delta, 2.0, 10.0, 40.0),
+            Entry::Volume => self.volume = (step(self.volume * 10.0, delta, 1.0, 0.0, 10.0)).round() / 10.0,
+            Entry::Language => self.language = cycle(&Language::ALL, &self.language, delta),
+            Entry::WallStyle => self.wall_style = cycle(&[WallStyle::Blocks, WallStyle::Thin], &self.wall_style, delta),
+            Entry::Shape => self.shape = cycle(&Shape::ALL, &self.shape, delta),
+            Entry::Minimap => self.minimap = !self.minimap,
+            Entry::MinimapSize => self.minimap_size = step(self.minimap_size, delta, 20.0, 80.0, 320.0),
+            Entry::Braid => self.braid = step(self.braid, delta, 10, 0, 100),
+            Entry::Loops => self.loops = step(self.loops, delta, 5, 0, 40),
+            Entry::HintRoutes => self.hint_routes = step(self.hint_routes, delta, 1, 1, 4),
+            Entry::MusicVolume => {
+                self.music_volume = (step(self.music_volume * 10.0, delta, 1.0, 0.0, 10.0)).round() / 10.0
+            }
+            Entry::Mute => self.muted = !self.muted,
+            Entry::Trail => self.trail = cycle(&TrailOverlay::ALL, &self.trail, delta),
+            Entry::Agent => self.agent = cycle(&AgentKind::ALL, &self.agent, delta),
+            Entry::AgentSpeed => self.agent_speed = step(self.agent_speed, delta, 2, 2, 30),
+            Entry::RequireAllItems => self.require_all_items = !self.require_all_items,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_adjust_cycles_both_ways() {
+        let mut settings = Settings::default();
+        settings.adjust(Entry::KeyRepeat, 1, &[]);
+        assert_eq!(settings.key_repeat, KeyRepeat::Fast);
+        settings.adjust(Entry::KeyRepeat, 1, &[]);
+        assert_eq!(settings.key_repeat, KeyRepeat::Off);
+        settings.adjust(Entry::KeyRepeat, -1, &[]);
+        assert_eq!(settings.key_repeat, KeyRepeat::Fast);
+        settings.adjust(Entry::HintMode, -1, &[]);
+        assert_eq!(settings.hint_mode, HintMode::Shortest);
+
+        let themes = vec!["classic".to_string(), "stone".to_string()];
+        settings.adjust(Entry::Theme, -1, &themes);
+        assert_eq!(settings.theme, "stone");
+    }
+
+    #[test]
+    fn test_entries_follow_language() {
+        let mut settings = Settings::default();
+        assert!(settings.entries()[0].starts_with("Hint route"));
+        settings.adjust(Entry::Language, 1, &[]);
+        assert_eq!(settings.language, Language::Chinese);
+        assert!(settings.entries()[0].starts_with("提示路线"));
+        // 每个条目都有自己的一行
+        assert_eq!(settings.entries().len(), Entry::ALL.len());
+        assert!(settings.entry_label(Entry::AgentSpeed).ends_with(": 8"));
+    }
+
+    #[test]
+    fn test_adjust_clamps_ranges() {
+        let mut settings = Settings::default();
+        for _ in 0..20 {
+            settings.adjust(Entry::Volume, 1, &[]);
+            settings.adjust(Entry::MazeWidth, -1, &[]);
+        }
+        assert_eq!(settings.volume, 1.0);
+        assert_eq!(settings.maze_width, 11);
+
+        settings.adjust(Entry::MinimapSize, -1, &[]);
+        assert_eq!(settings.minimap_size, 140.0);
+        settings.adjust(Entry::Minimap, 1, &[]);
+        assert!(!settings.minimap);
+
+        settings.adjust(Entry::Braid, 1, &[]);
+        settings.adjust(Entry::Loops, 1, &[]);
+        assert_eq!(settings.quick_play_params(1).post, PostProcess { braid: 0.1, loops: 5 });
+        settings.adjust(Entry::HintRoutes, -1, &[]);
+        assert_eq!(settings.hint_routes, 1);
+        settings.adjust(Entry::Agent, 1, &[]);
+        assert_eq!(settings.agent, AgentKind::Random);
+        for _ in 0..20 {
+            settings.adjust(Entry::AgentSpeed, 1, &[]);
+        }
+        assert_eq!(settings.agent_speed, 30);
+    }
+
+    #[test]
+    fn test_json_round_trip_and_defaults() {
+        let settings = Settings {
+            theme: "dark".to_string(),
+            language: Language::Chinese,
+            ..Default::default()
+        };
+        let text = serde_json::to_string(&settings).unwrap();
+        assert_eq!(Settings::from_json(&text).unwrap(), settings);
+
+        // 缺失的字段取默认值，越界的值被修正
+        let partial = Settings::from_json(r#"{"maze_width": 999, "theme": "nope"}"#).unwrap();
+        assert_eq!(partial.maze_width, 61);
+        assert_eq!(partial.theme, "nope");
+        assert_eq!(partial.key_repeat, KeyRepeat::Slow);
+        assert!(Settings::from_json("not json").is_err());
+    }
+
+    #[test]
+    fn test_save_and_load_file() {
+        let path = std::env::temp_dir().join(format!("maze_game_settings_{}.json", std::process::id()));
+        let settings = Settings {
+            maze_width: 31,
+            ..Default::default()
+        };
+        settings.save(&path).unwrap();
+        assert_eq!(Settings::load(&path), settings);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(Settings::load(&path), Settings::default());
+    }
+}
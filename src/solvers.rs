@@ -0,0 +1,352 @@
+// 教学用的人工解迷宫方法，与 find_shortest_path 的广度优先搜索对照：
+//   死路填充   反复填掉只有一个出口的格子，剩下的就是路线（有环路时会剩下多条）
+//   Trémaux    走过的地方做记号，退回时再记一次；只记一次的格子连成路线
+//              （这里以格子代替通道做记号，效果相当于深度优先搜索）
+//   左手/右手  一直扶着同一侧的墙走；终点不与外墙相连（在"岛"上）时会绕圈
+//   Pledge     朝固定方向走，碰墙后扶墙并累计转向，转回原方向时离开墙；
+//              用于走出迷宫，终点在内部时同样可能绕圈
+// 每种方法先一次算出全部步骤，再由 Animation 按时间逐步显示。
+
+use crate::i18n::{tr, Language, Text};
+use crate::route;
+use crate::theme::{Palette, Style};
+use crate::{draw_label, Facing, MazeGame, Position};
+use macroquad::prelude::*;
+use std::collections::HashSet;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Solver {
+    DeadEndFill,
+    Tremaux,
+    LeftHand,
+    RightHand,
+    Pledge,
+}
+
+impl Solver {
+    pub const ALL: [Solver; 5] = [Solver::DeadEndFill, Solver::Tremaux, Solver::LeftHand, Solver::RightHand, Solver::Pledge];
+
+    pub fn display_name(self, language: Language) -> &'static str {
+        let text = match self {
+            Solver::DeadEndFill => Text::SolverDeadEndFill,
+            Solver::Tremaux => Text::SolverTremaux,
+            Solver::LeftHand => Text::SolverLeftHand,
+            Solver::RightHand => Text::SolverRightHand,
+            Solver::Pledge => Text::SolverPledge,
+        };
+        tr(language, text)
+    }
+}
+
+// 求解过程中的一步
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Event {
+    Move(Position),     // 走到某个格子
+    Mark(Position, u8), // 给格子做记号：1 为走过一次，2 为排除（死路或退回）
+}
+
+// 一次求解的全部步骤和结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct Run {
+    pub solver: Solver,
+    events: Vec<Event>,
+    pub path: Option<Vec<Position>>, // 找到的路线，失败时为 None
+    pub note: Option<Text>,          // 失败原因或需要注意的地方
+}
+
+fn open(game: &MazeGame, pos: Position) -> bool {
+    game.is_open(pos)
+}
+
+fn step(pos: Position, dir: Facing) -> Option<Position> {
+    let (dx, dy) = dir.delta();
+    Some(Position { x: pos.x.checked_add_signed(dx as isize)?, y: pos.y.checked_add_signed(dy as isize)? })
+}
+
+// 右转 quarter 个 90 度，负数为左转
+fn turn(dir: Facing, quarter: i32) -> Facing {
+    Facing::ALL[(dir as i32 + quarter).rem_euclid(4) as usize]
+}
+
+fn open_neighbors(game: &MazeGame, pos: Position) -> impl Iterator<Item = Position> + '_ {
+    Facing::ALL.into_iter().filter_map(move |dir| step(pos, dir)).filter(|&n| open(game, n))
+}
+
+// 步数上限：超过说明在绕圈（Pledge 的转向计数可能一直增长，无法靠重复状态发现）
+fn step_limit(game: &MazeGame) -> usize {
+    game.width * game.height * 8
+}
+
+pub fn solve(game: &MazeGame, solver: Solver) -> Run {
+    let from = game.player_pos;
+    match solver {
+        Solver::DeadEndFill => dead_end_fill(game, from),
+        Solver::Tremaux => tremaux(game, from),
+        Solver::LeftHand => wall_follower(game, from, -1),
+        Solver::RightHand => wall_follower(game, from, 1),
+        Solver::Pledge => pledge(game, from),
+    }
+}
+
+fn dead_end_fill(game: &MazeGame, from: Position) -> Run {
+    let mut filled = vec![vec![false; game.width]; game.height];
+    let mut events = Vec::new();
+    let mut stack: Vec<Position> = (0..game.height)
+        .flat_map(|y| (0..game.width).map(move |x| Position { x, y }))
+        .filter(|&pos| open(game, pos))
+        .collect();
+    while let Some(pos) = stack.pop() {
+        if filled[pos.y][pos.x] || pos == from || pos == game.end_pos {
+            continue;
+        }
+        let exits = open_neighbors(game, pos).filter(|n| !filled[n.y][n.x]).count();
+        if exits <= 1 {
+            filled[pos.y][pos.x] = true;
+            events.push(Event::Mark(pos, 2));
+            stack.extend(open_neighbors(game, pos));
+        }
+    }
+
+    // 剩下的格子中找路线；剩下的比路线多说明有环路
+    let remaining = |pos: Position| open_neighbors(game, pos).filter(|n| !filled[n.y][n.x]).collect::<Vec<_>>();
+    let path = route::k_shortest_paths(from, game.end_pos, 1, remaining).pop();
+    let left = (0..game.height)
+        .flat_map(|y| (0..game.width).map(move |x| Position { x, y }))
+        .filter(|&pos| open(game, pos) && !filled[pos.y][pos.x])
+        .count();
+    let note = match &path {
+        None => Some(Text::SolverNoPath),
+        Some(path) if left > path.len() => Some(Text::SolverLoopsRemain),
+        Some(_) => None,
+    };
+    Run { solver: Solver::DeadEndFill, events, path, note }
+}
+
+fn tremaux(game: &MazeGame, from: Position) -> Run {
+    let mut marks = vec![vec![0u8; game.width]; game.height];
+    let mut events = vec![Event::Move(from), Event::Mark(from, 1)];
+    let mut stack = vec![from];
+    marks[from.y][from.x] = 1;
+    while let Some(&pos) = stack.last() {
+        if pos == game.end_pos {
+            return Run { solver: Solver::Tremaux, events, path: Some(stack), note: None };
+        }
+        match open_neighbors(game, pos).find(|n| marks[n.y][n.x] == 0) {
+            Some(next) => {
+                marks[next.y][next.x] = 1;
+                stack.push(next);
+                events.extend([Event::Move(next), Event::Mark(next, 1)]);
+            }
+            None => {
+                // 无路可走，记第二次并退回
+                marks[pos.y][pos.x] = 2;
+                events.push(Event::Mark(pos, 2));
+                stack.pop();
+                if let Some(&back) = stack.last() {
+                    events.push(Event::Move(back));
+                }
+            }
+        }
+    }
+    Run { solver: Solver::Tremaux, events, path: None, note: Some(Text::SolverNoPath) }
+}
+
+// hand 为 -1 时扶左手边的墙，1 时扶右手边：依次尝试转向扶墙一侧、直走、转向另一侧、掉头
+fn wall_follower(game: &MazeGame, from: Position, hand: i32) -> Run {
+    let solver = if hand < 0 { Solver::LeftHand } else { Solver::RightHand };
+    let (mut pos, mut dir) = (from, game.facing);
+    let mut events = vec![Event::Move(from)];
+    let mut path = vec![from];
+    let mut seen = HashSet::new();
+    while pos != game.end_pos {
+        // 同一位置同一朝向出现第二次，说明在绕圈
+        if !seen.insert((pos, dir)) {
+            return Run { solver, events, path: None, note: Some(Text::SolverWallLoop) };
+        }
+        let moved = [hand, 0, -hand, 2]
+            .into_iter()
+            .map(|quarter| turn(dir, quarter))
+            .find_map(|d| step(pos, d).filter(|&n| open(game, n)).map(|n| (n, d)));
+        let Some((next, d)) = moved else {
+            return Run { solver, events, path: None, note: Some(Text::SolverNoPath) };
+        };
+        (pos, dir) = (next, d);
+        events.push(Event::Move(pos));
+        path.push(pos);
+    }
+    Run { solver, events, path: Some(path), note: None }
+}
+
+fn pledge(game: &MazeGame, from: Position) -> Run {
+    // 偏好方向：朝终点相差较大的那个轴
+    let dx = game.end_pos.x as i32 - from.x as i32;
+    let dy = game.end_pos.y as i32 - from.y as i32;
+    let preferred = if dx.abs() >= dy.abs() { Facing::from_delta(dx.signum(), 0) } else { Facing::from_delta(0, dy.signum()) };
+
+    let (mut pos, mut dir, mut turns) = (from, preferred, 0);
+    let mut events = vec![Event::Move(from)];
+    let mut path = vec![from];
+    let mut seen = HashSet::new();
+    for _ in 0..step_limit(game) {
+        if pos == game.end_pos {
+            return Run { solver: Solver::Pledge, events, path: Some(path), note: None };
+        }
+        if !seen.insert((pos, dir, turns)) {
+            break;
+        }
+        let next = if turns == 0 {
+            // 不扶墙时朝偏好方向直走，碰墙就右转并开始用左手扶墙
+            match step(pos, dir).filter(|&n| open(game, n)) {
+                Some(next) => Some(next),
+                None => {
+                    dir = turn(dir, 1);
+                    turns += 1;
+                    continue;
+                }
+            }
+        } else {
+            [-1, 0, 1, 2].into_iter().find_map(|quarter| {
+                let d = turn(dir, quarter);
+                let next = step(pos, d).filter(|&n| open(game, n))?;
+                dir = d;
+                turns += quarter;
+                Some(next)
+            })
+        };
+        let Some(next) = next else {
+            break;
+        };
+        pos = next;
+        events.push(Event::Move(pos));
+        path.push(pos);
+    }
+    Run { solver: Solver::Pledge, events, path: None, note: Some(Text::SolverPledgeLoop) }
+}
+
+// 按时间逐步显示一次求解
+pub struct Animation {
+    run: Run,
+    shown: usize, // 已显示的步数
+    timer: f32,
+}
+
+impl Animation {
+    pub fn new(run: Run) -> Self {
+        Animation { run, shown: 0, timer: 0.0 }
+    }
+
+    pub fn solver(&self) -> Solver {
+        self.run.solver
+    }
+
+    pub fn finished(&self) -> bool {
+        self.shown >= self.run.events.len()
+    }
+
+    // 至少每秒 30 步，步骤多时加快，整个过程大约 8 秒
+    pub fn update(&mut self, dt: f32) {
+        let rate = (self.run.events.len() as f32 / 8.0).max(30.0);
+        self.timer += dt * rate;
+        let steps = self.timer as usize;
+        self.timer -= steps as f32;
+        self.shown = (self.shown + steps).min(self.run.events.len());
+    }
+
+    // 画在俯视图的迷宫上，并在左上角说明当前方法和结果
+    pub fn render(&self, palette: &Palette, cell_size: f32, style: &Style) {
+        let mut visited: Vec<(Position, u8)> = Vec::new();
+        let mut walker = None;
+        for event in &self.run.events[..self.shown] {
+            match *event {
+                Event::Move(pos) => {
+                    walker = Some(pos);
+                    visited.push((pos, 0));
+                }
+                Event::Mark(pos, n) => visited.push((pos, n)),
+            }
+        }
+        let cell = |pos: Position| (pos.x as f32 * cell_size, pos.y as f32 * cell_size);
+        let trail_color = Color { a: 0.35, ..palette.path };
+        for &(pos, mark) in &visited {
+            let (px, py) = cell(pos);
+            match mark {
+                0 => draw_rectangle(px, py, cell_size, cell_size, trail_color),
+                1 => draw_circle(px + cell_size / 2.0, py + cell_size / 2.0, cell_size * 0.15, palette.path),
+                _ => draw_rectangle(px, py, cell_size, cell_size, Color::new(0.2, 0.2, 0.2, 0.6)),
+            }
+        }
+        if let Some(pos) = walker.filter(|_| !self.finished()) {
+            let (px, py) = cell(pos);
+            draw_circle(px + cell_size / 2.0, py + cell_size / 2.0, cell_size * 0.35, palette.path);
+        }
+        if let (true, Some(path)) = (self.finished(), &self.run.path) {
+            for pair in path.windows(2) {
+                let a = vec2(pair[0].x as f32 + 0.5, pair[0].y as f32 + 0.5) * cell_size;
+                let b = vec2(pair[1].x as f32 + 0.5, pair[1].y as f32 + 0.5) * cell_size;
+                draw_line(a.x, a.y, b.x, b.y, (cell_size * 0.25).max(2.0), palette.path);
+            }
+        }
+
+        let t = |text| tr(style.language, text);
+        let mut lines = vec![format!(
+            "{}: {} ({}/{})",
+            t(Text::SolverTitle),
+            self.run.solver.display_name(style.language),
+            self.shown,
+            self.run.events.len()
+        )];
+        if self.finished() {
+            lines.push(t(if self.run.path.is_some() { Text::SolverFound } else { Text::SolverFailed }).to_string());
+            if let Some(note) = self.run.note {
+                lines.push(t(note).to_string());
+            }
+        }
+        let mut panel = palette.background;
+        panel.a = 0.85;
+        draw_rectangle(0.0, 0.0, screen_width().min(720.0), 15.0 + lines.len() as f32 * 25.0, panel);
+        for (i, line) in lines.iter().enumerate() {
+            draw_label(line, 10.0, 25.0 + i as f32 * 25.0, style);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 终点在一圈环路中间的岛上：扶墙走会绕着外墙回到原处
+    const ISLAND: &str = "#########\n#S      #\n# ##### #\n# #   # #\n# # E # #\n# #   # #\n# ## ## #\n#       #\n#########";
+
+    #[test]
+    fn test_solvers_on_simple_maze() {
+        let game = MazeGame::from_text("#####\n#S..#\n##.##\n#E..#\n#####").unwrap();
+        for solver in Solver::ALL {
+            let run = solve(&game, solver);
+            let path = run.path.unwrap_or_else(|| panic!("{:?} 应当找到路线", solver));
+            assert_eq!(path.first(), Some(&game.start_pos));
+            assert_eq!(path.last(), Some(&game.end_pos));
+            assert!(path.windows(2).all(|p| p[0].x.abs_diff(p[1].x) + p[0].y.abs_diff(p[1].y) == 1));
+        }
+        assert_eq!(solve(&game, Solver::DeadEndFill).note, None);
+
+        // 两条路线组成环路，死路填充无法排除其中一条
+        let game = MazeGame::from_text("#######\n#S    #\n#.###.#\n#.#E#.#\n#.#.#.#\n#.....#\n#######").unwrap();
+        let run = solve(&game, Solver::DeadEndFill);
+        assert_eq!(run.note, Some(Text::SolverLoopsRemain));
+        assert_eq!(run.path.map(|p| p.len()), Some(9));
+    }
+
+    #[test]
+    fn test_wall_follower_fails_on_island() {
+        let game = MazeGame::from_text(ISLAND).unwrap();
+        let run = solve(&game, Solver::RightHand);
+        assert_eq!((run.path, run.note), (None, Some(Text::SolverWallLoop)));
+        assert!(solve(&game, Solver::Tremaux).path.is_some());
+        assert!(solve(&game, Solver::DeadEndFill).path.is_some());
+
+        let mut animation = Animation::new(solve(&game, Solver::LeftHand));
+        assert!(!animation.finished());
+        animation.update(100.0);
+        assert!(animation.finished());
+    }
+}
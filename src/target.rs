@@ -0,0 +1,134 @@
+// 按难度目标生成迷宫
+//
+// 依次尝试不同的种子，每个种子再尝试几种后处理（打通死路、增加环路），
+// 用 Metrics 检查是否满足目标；满足就立即返回，时间用完或生成了 MAX_ATTEMPTS 个迷宫
+// 仍未满足时返回最接近目标的一个（met 为 false）。
+
+use crate::generator::{self, GeneratorParams, PostProcess};
+use crate::metrics::Metrics;
+use crate::{Cell, MazeGame};
+use std::time::{Duration, Instant};
+
+const MAX_ATTEMPTS: usize = 10_000;
+
+// 难度目标，未设置的条件不限制
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Target {
+    pub min_difficulty: Option<f64>,
+    pub max_difficulty: Option<f64>,
+    pub min_solution: Option<usize>,
+    pub max_solution: Option<usize>,
+}
+
+impl Target {
+    // 与目标的差距，满足目标时为 0
+    pub fn distance(&self, metrics: &Metrics) -> f64 {
+        let below = |value: f64, min: Option<f64>| min.map_or(0.0, |min| (min - value).max(0.0));
+        let above = |value: f64, max: Option<f64>| max.map_or(0.0, |max| (value - max).max(0.0));
+        let solution = metrics.solution_length as f64;
+        // 路线长度的差距按 10 步折合 1 点难度
+        below(metrics.difficulty, self.min_difficulty)
+            + above(metrics.difficulty, self.max_difficulty)
+            + below(solution, self.min_solution.map(|n| n as f64)) / 10.0
+            + above(solution, self.max_solution.map(|n| n as f64)) / 10.0
+    }
+}
+
+// 搜索结果
+pub struct Outcome {
+    pub grid: Vec<Vec<Cell>>,
+    pub seed: u64,
+    pub post: PostProcess,
+    pub metrics: Metrics,
+    pub met: bool,       // 是否满足目标
+    pub attempts: usize, // 尝试过的迷宫数
+}
+
+// 每个种子依次尝试的后处理（替换 params.post），从不处理到大量打通，难度大致递减
+fn post_variants(params: &GeneratorParams) -> [PostProcess; 5] {
+    let rooms = params.width * params.height / 4;
+    [
+        PostProcess::default(),
+        PostProcess { braid: 0.0, loops: rooms / 30 },
+        PostProcess { braid: 0.3, loops: 0 },
+        PostProcess { braid: 0.6, loops: rooms / 15 },
+        PostProcess { braid: 1.0, loops: rooms / 8 },
+    ]
+}
+
+// 从 params.seed 开始搜索；至少会生成一个迷宫，之后超过 budget 就停止
+pub fn search(params: &GeneratorParams, target: &Target, budget: Duration) -> Result<Outcome, String> {
+    let started = Instant::now();
+    let mut best: Option<(f64, Outcome)> = None;
+    let mut attempts = 0;
+
+    'search: for seed in params.seed..=u64::MAX {
+        for post in post_variants(params) {
+            if attempts >= MAX_ATTEMPTS {
+                break 'search;
+            }
+            let grid = generator::generate(&GeneratorParams { seed, post, ..params.clone() });
+            attempts += 1;
+            let metrics = MazeGame::from_grid(grid.clone()).and_then(|game| Metrics::analyze(&game));
+            if let Ok(metrics) = metrics {
+                let distance = target.distance(&metrics);
+                if best.as_ref().is_none_or(|(d, _)| distance < *d) {
+                    let outcome = Outcome { grid, seed, post, metrics, met: distance == 0.0, attempts };
+                    best = Some((distance, outcome));
+                }
+            }
+            let done = best.as_ref().is_some_and(|(d, _)| *d == 0.0);
+            if done || (best.is_some() && started.elapsed() >= budget) {
+                let (_, outcome) = best.expect("至少生成过一个有效迷宫");
+                return Ok(Outcome { attempts, ..outcome });
+            }
+        }
+    }
+    let (_, outcome) = best.ok_or_else(|| format!("尝试了 {} 个迷宫，没有一个有效", attempts))?;
+    Ok(Outcome { attempts, ..outcome })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::Algorithm;
+
+    fn params() -> GeneratorParams {
+        GeneratorParams {
+            algorithm: Algorithm::Backtracker,
+            width: 21,
+            height: 15,
+            seed: 1,
+            coins: 0,
+            gems: 0,
+            post: PostProcess::default(),
+        }
+    }
+
+    #[test]
+    fn test_search_meets_reachable_target() {
+        let target = Target { min_solution: Some(40), ..Default::default() };
+        let outcome = search(&params(), &target, Duration::from_secs(5)).unwrap();
+        assert!(outcome.met);
+        assert!(outcome.metrics.solution_length >= 40);
+        // 结果可以用种子和后处理重新生成
+        let again = GeneratorParams { seed: outcome.seed, post: outcome.post, ..params() };
+        assert_eq!(generator::generate(&again), outcome.grid);
+    }
+
+    #[test]
+    fn test_search_returns_closest_when_out_of_reach() {
+        let target = Target { min_solution: Some(10_000), ..Default::default() };
+        let outcome = search(&params(), &target, Duration::from_millis(50)).unwrap();
+        assert!(!outcome.met);
+        assert!(outcome.attempts >= 1);
+        assert!(target.distance(&outcome.metrics) > 0.0);
+
+        // 时间充足时尝试次数有上限，到达上限后同样返回最接近的结果
+        let tiny = GeneratorParams { width: 5, height: 5, ..params() };
+        let outcome = search(&tiny, &target, Duration::from_secs(3600)).unwrap();
+        assert!(!outcome.met);
+        assert_eq!(outcome.attempts, MAX_ATTEMPTS);
+        assert!(outcome.metrics.solution_length > 0);
+    }
+}
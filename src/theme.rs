@@ -0,0 +1,449 @@
+// 配色主题、贴图图集与渲染样式
+//
+// 除内置主题外，assets/themes/ 下的每个 .txt 文件都是一个主题，主题名为文件名：
+//
+//   base = dark                  # 未写出的颜色取自该内置主题（默认 classic）
+//   wall = #3a3a48               # 任意配色字段，#rrggbb 或 #rrggbbaa
+//   atlas = stone.png            # 可选的贴图图集，相对于主题文件所在目录
+//   tile_size = 16               # 图集中每块贴图的边长（像素）
+//   floor_variants = 4           # 地面贴图的变体数
+//
+// 图集按固定布局排列，每行依次为：
+//   第0行：16 块墙，按四周相邻的墙选取（上=1、右=2、下=4、左=8 相加）
+//   第1行：地面变体
+//   第2行：起点、终点、锁住的终点、金币、宝石、提示路线（叠加在地面上）
+//   第3行：玩家，朝上、右、下、左
+
+use crate::assets::{Assets, FONT_CJK, FONT_LATIN};
+use crate::i18n::{needs_cjk_font, Language};
+use crate::woff;
+use macroquad::prelude::*;
+
+// 内置主题名称，设置界面按此顺序切换（之后是主题文件）
+pub const THEME_NAMES: [&str; 3] = ["classic", "dark", "high-contrast"];
+
+// 主题文件所在的资源目录
+const THEME_DIR: &str = "assets/themes";
+
+// 配色方案
+#[derive(Debug, Clone, PartialEq)]
+pub struct Palette {
+    pub background: Color,
+    pub text: Color,
+    pub highlight: Color, // 菜单选中项底色
+    pub wall: Color,
+    pub floor: Color,
+    pub grid_line: Color,
+    pub start: Color,
+    pub end: Color,
+    pub end_locked: Color,
+    pub player: Color,
+    pub path: Color,
+    pub coin: Color,
+    pub gem: Color,
+}
+
+impl Palette {
+    // 按名称取内置配色，未知名称退回经典配色
+    pub fn named(name: &str) -> Self {
+        match name {
+            "dark" => Palette {
+                background: Color::from_rgba(24, 24, 32, 255),
+                text: Color::from_rgba(220, 220, 230, 255),
+                highlight: Color::from_rgba(60, 60, 80, 255),
+                wall: Color::from_rgba(40, 44, 60, 255),
+                floor: Color::from_rgba(90, 94, 110, 255),
+                grid_line: Color::from_rgba(20, 20, 28, 255),
+                start: Color::from_rgba(60, 160, 90, 255),
+                end: Color::from_rgba(200, 70, 70, 255),
+                end_locked: Color::from_rgba(110, 40, 40, 255),
+                player: Color::from_rgba(90, 170, 255, 255),
+                path: Color::from_rgba(200, 180, 60, 255),
+                coin: GOLD,
+                gem: VIOLET,
+            },
+            "high-contrast" => Palette {
+                background: BLACK,
+                text: WHITE,
+                highlight: DARKBLUE,
+                wall: BLACK,
+                floor: WHITE,
+                grid_line: GRAY,
+                start: LIME,
+                end: RED,
+                end_locked: MAROON,
+                player: BLUE,
+                path: YELLOW,
+                coin: ORANGE,
+                gem: MAGENTA,
+            },
+            _ => Palette {
+                background: WHITE,
+                text: BLACK,
+                highlight: LIGHTGRAY,
+                wall: DARKGRAY,
+                floor: LIGHTGRAY,
+                grid_line: BLACK,
+                start: GREEN,
+                end: RED,
+                end_locked: MAROON,
+                player: BLUE,
+                path: YELLOW,
+                coin: GOLD,
+                gem: PURPLE,
+            },
+        }
+    }
+
+    // 按字段名设置颜色，字段名未知时返回 false
+    fn set(&mut self, key: &str, color: Color) -> bool {
+        let field = match key {
+            "background" => &mut self.background,
+            "text" => &mut self.text,
+            "highlight" => &mut self.highlight,
+            "wall" => &mut self.wall,
+            "floor" => &mut self.floor,
+            "grid_line" => &mut self.grid_line,
+            "start" => &mut self.start,
+            "end" => &mut self.end,
+            "end_locked" => &mut self.end_locked,
+            "player" => &mut self.player,
+            "path" => &mut self.path,
+            "coin" => &mut self.coin,
+            "gem" => &mut self.gem,
+            _ => return false,
+        };
+        *field = color;
+        true
+    }
+}
+
+// 解析 #rrggbb 或 #rrggbbaa
+fn parse_color(text: &str) -> Result<Color, String> {
+    let hex = text.strip_prefix('#').ok_or_else(|| format!("颜色应以 # 开头: {}", text))?;
+    // 先确认全是十六进制数字，下面按字节切片才不会切到多字节字符中间
+    if (hex.len() != 6 && hex.len() != 8) || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(format!("颜色格式有误: {}", text));
+    }
+    let mut bytes = [255u8; 4];
+    for (i, byte) in bytes.iter_mut().take(hex.len() / 2).enumerate() {
+        *byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16)
+            .map_err(|_| format!("颜色格式有误: {}", text))?;
+    }
+    Ok(Color::from_rgba(bytes[0], bytes[1], bytes[2], bytes[3]))
+}
+
+// 主题文件中的图集设置
+#[derive(Debug, Clone, PartialEq)]
+struct AtlasDef {
+    file: String, // 资源名
+    tile_size: u32,
+    floor_variants: usize,
+}
+
+// 解析主题文件；dir 为主题文件所在的资源目录，用于定位图集
+fn parse_theme(text: &str, dir: &str) -> Result<(Palette, Option<AtlasDef>), String> {
+    let mut palette = Palette::named("classic");
+    let mut atlas_file = None;
+    let mut tile_size = 16;
+    let mut floor_variants = 1;
+
+    for (line_no, raw) in text.lines().enumerate() {
+        let line = raw.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let err = |msg: String| format!("第 {} 行: {}", line_no + 1, msg);
+        let (key, value) = line.split_once('=').ok_or_else(|| err(format!("无法识别: {}", line)))?;
+        // 颜色值本身以 # 开头，所以值只取第一个词，后面的都当作注释
+        let key = key.trim();
+        let value = value.split_whitespace().next().unwrap_or("");
+        match key {
+            "base" => {
+                if !THEME_NAMES.contains(&value) {
+                    return Err(err(format!("未知的内置主题: {}", value)));
+                }
+                palette = Palette::named(value);
+            }
+            "atlas" => atlas_file = Some(format!("{}/{}", dir, value)),
+            "tile_size" => tile_size = value.parse().map_err(|_| err(format!("无效的贴图尺寸: {}", value)))?,
+            "floor_variants" => {
+                floor_variants = value.parse().map_err(|_| err(format!("无效的地面变体数: {}", value)))?
+            }
+            _ => {
+                let color = parse_color(value).map_err(err)?;
+                if !palette.set(key, color) {
+                    return Err(err(format!("未知的字段: {}", key)));
+                }
+            }
+        }
+    }
+
+    if tile_size == 0 || !(1..=16).contains(&floor_variants) {
+        return Err("贴图尺寸须大于0，地面变体数须在 1~16 之间".to_string());
+    }
+    let atlas = atlas_file.map(|file| AtlasDef { file, tile_size, floor_variants });
+    Ok((palette, atlas))
+}
+
+// 图集中第2行的叠加贴图
+#[derive(Debug, Clone, Copy)]
+pub enum Sprite {
+    Start,
+    End,
+    EndLocked,
+    Coin,
+    Gem,
+    Path,
+}
+
+// 已加载的贴图图集
+pub struct Atlas {
+    texture: Texture2D,
+    tile_size: f32,
+    floor_variants: usize,
+}
+
+impl Atlas {
+    fn draw_tile(&self, col: usize, row: usize, x: f32, y: f32, size: f32) {
+        let source = Rect::new(
+            col as f32 * self.tile_size,
+            row as f32 * self.tile_size,
+            self.tile_size,
+            self.tile_size,
+        );
+        draw_texture_ex(
+            &self.texture,
+            x,
+            y,
+            WHITE,
+            DrawTextureParams {
+                dest_size: Some(vec2(size, size)),
+                source: Some(source),
+                ..Default::default()
+            },
+        );
+    }
+
+    // wall_mask：四周相邻的墙（上=1、右=2、下=4、左=8）
+    pub fn draw_wall(&self, wall_mask: usize, x: f32, y: f32, size: f32) {
+        self.draw_tile(wall_mask & 15, 0, x, y, size);
+    }
+
+    // 按格子坐标选取地面变体，同一格子总是同一变体
+    pub fn draw_floor(&self, cell_x: usize, cell_y: usize, x: f32, y: f32, size: f32) {
+        self.draw_tile(floor_variant(cell_x, cell_y, self.floor_variants), 1, x, y, size);
+    }
+
+    pub fn draw_sprite(&self, sprite: Sprite, x: f32, y: f32, size: f32) {
+        self.draw_tile(sprite as usize, 2, x, y, size);
+    }
+
+    // facing：0~3 依次为上、右、下、左
+    pub fn draw_player(&self, facing: usize, x: f32, y: f32, size: f32) {
+        self.draw_tile(facing & 3, 3, x, y, size);
+    }
+}
+
+// 由坐标散列出地面变体编号，避免明显的重复花纹
+fn floor_variant(x: usize, y: usize, variants: usize) -> usize {
+    let hash = (x.wrapping_mul(73_856_093) ^ y.wrapping_mul(19_349_663)) >> 3;
+    hash % variants.max(1)
+}
+
+// 主题：配色加可选的图集
+pub struct Theme {
+    pub name: String,
+    pub palette: Palette,
+    pub atlas: Option<Atlas>,
+}
+
+// 可切换的全部主题
+pub struct Themes {
+    themes: Vec<Theme>,
+}
+
+impl Themes {
+    // 只有内置配色的主题
+    pub fn builtin() -> Self {
+        let themes = THEME_NAMES
+            .iter()
+            .map(|&name| Theme {
+                name: name.to_string(),
+                palette: Palette::named(name),
+                atlas: None,
+            })
+            .collect();
+        Themes { themes }
+    }
+
+    // 内置主题加上 assets/themes/ 下的主题文件；有错误的主题文件记入缺失资源
+    pub fn load(assets: &mut Assets) -> Self {
+        let mut themes = Themes::builtin();
+        for name in assets.list(THEME_DIR) {
+            let file_name = &name[THEME_DIR.len() + 1..];
+            let Some(theme_name) = file_name.strip_suffix(".txt") else {
+                continue;
+            };
+            let Some(bytes) = assets.load(&name) else {
+                continue;
+            };
+            let parsed = String::from_utf8(bytes)
+                .map_err(|e| e.to_string())
+                .and_then(|text| parse_theme(&text, THEME_DIR));
+            let (palette, atlas_def) = match parsed {
+                Ok(parsed) => parsed,
+                Err(e) => {
+                    eprintln!("主题 {} 有误: {}", name, e);
+                    assets.mark_broken(&name);
+                    continue;
+                }
+            };
+            let atlas = atlas_def.and_then(|def| load_atlas(assets, &def));
+            themes.themes.push(Theme { name: theme_name.to_string(), palette, atlas });
+        }
+        themes
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.themes.iter().map(|t| t.name.clone()).collect()
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.themes.iter().any(|t| t.name == name)
+    }
+
+    // 按名称取主题，找不到时用第一个（经典）主题
+    pub fn get(&self, name: &str) -> &Theme {
+        self.themes.iter().find(|t| t.name == name).unwrap_or(&self.themes[0])
+    }
+
+    // 是否为主题资源（热重载时判断是否需要重新加载主题）
+    pub fn is_theme_asset(name: &str) -> bool {
+        name.starts_with(THEME_DIR)
+    }
+}
+
+fn load_atlas(assets: &mut Assets, def: &AtlasDef) -> Option<Atlas> {
+    let bytes = assets.load(&def.file)?;
+    match Image::from_file_with_format(&bytes, None) {
+        Ok(image) => {
+            let texture = Texture2D::from_image(&image);
+            texture.set_filter(FilterMode::Nearest);
+            Some(Atlas {
+                texture,
+                tile_size: def.tile_size as f32,
+                floor_variants: def.floor_variants,
+            })
+        }
+        Err(e) => {
+            eprintln!("图集 {} 加载失败: {}", def.file, e);
+            assets.mark_broken(&def.file);
+            None
+        }
+    }
+}
+
+// 已加载的字体：西文字体和中文字体（SimHei）
+#[derive(Default)]
+pub struct Fonts {
+    pub latin: Option<Font>,
+    pub cjk: Option<Font>,
+}
+
+impl Fonts {
+    pub fn load(assets: &mut Assets) -> Self {
+        Fonts {
+            latin: load_font(assets, FONT_LATIN),
+            cjk: load_font(assets, FONT_CJK),
+        }
+    }
+
+    // 是否为字体资源（热重载时判断是否需要重新加载字体）
+    pub fn is_font_asset(name: &str) -> bool {
+        name == FONT_LATIN || name == FONT_CJK
+    }
+}
+
+// 加载单个字体；WOFF 字体先解码为 TTF。无法使用时记入缺失资源
+fn load_font(assets: &mut Assets, name: &str) -> Option<Font> {
+    let bytes = assets.load(name)?;
+    let ttf = if name.ends_with(".woff") { woff::decode(&bytes) } else { Ok(bytes) };
+    match ttf.and_then(|ttf| load_ttf_font_from_bytes(&ttf).map_err(|e| e.to_string())) {
+        Ok(font) => Some(font),
+        Err(e) => {
+            eprintln!("字体 {} 加载失败: {}", name, e);
+            assets.mark_broken(name);
+            None
+        }
+    }
+}
+
+// 渲染时需要的样式信息
+pub struct Style<'a> {
+    pub fonts: &'a Fonts,
+    pub palette: &'a Palette,
+    pub atlas: Option<&'a Atlas>, // 主题带图集时用贴图绘制迷宫
+    pub max_cell_size: f32,
+    pub language: Language,
+}
+
+impl Style<'_> {
+    // 按文字内容选择字体：含中文时用中文字体，避免显示成方框
+    pub fn font_for(&self, text: &str) -> Option<&Font> {
+        if needs_cjk_font(text) {
+            self.fonts.cjk.as_ref().or(self.fonts.latin.as_ref())
+        } else {
+            self.fonts.latin.as_ref()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_theme_file() {
+        let text = "
+            # 注释
+            base = dark
+            wall = #102030      # 行尾注释
+            path = #ffff0080
+            atlas = stone.png
+            floor_variants = 4
+        ";
+        let (palette, atlas) = parse_theme(text, "assets/themes").unwrap();
+        assert_eq!(palette.wall, Color::from_rgba(0x10, 0x20, 0x30, 255));
+        assert_eq!(palette.path, Color::from_rgba(255, 255, 0, 0x80));
+        assert_eq!(palette.background, Palette::named("dark").background);
+        let atlas = atlas.unwrap();
+        assert_eq!(atlas.file, "assets/themes/stone.png");
+        assert_eq!((atlas.tile_size, atlas.floor_variants), (16, 4));
+
+        assert!(parse_theme("wall = red", ".").is_err());
+        assert!(parse_theme("shadow = #000000", ".").is_err());
+        assert!(parse_theme("base = neon", ".").is_err());
+        assert!(parse_theme("wall = #éééé", ".").is_err());
+        assert!(parse_color("#+1ff00").is_err());
+        assert!(parse_theme("atlas = a.png\nfloor_variants = 0", ".").is_err());
+    }
+
+    #[test]
+    fn test_floor_variant_in_range() {
+        for y in 0..20 {
+            for x in 0..20 {
+                assert!(floor_variant(x, y, 4) < 4);
+            }
+        }
+        assert_eq!(floor_variant(3, 5, 4), floor_variant(3, 5, 4));
+    }
+
+    #[test]
+    fn test_unknown_theme_falls_back() {
+        let themes = Themes::builtin();
+        assert_eq!(themes.names(), THEME_NAMES);
+        assert!(!themes.contains("stone"));
+        assert_eq!(themes.get("stone").name, "classic");
+    }
+}
@@ -0,0 +1,269 @@
+// 细墙迷宫：墙位于格子之间，而不是占据整个格子
+//
+// 与方块网格的对应关系：细墙迷宫的格子 (x, y) 对应方块网格的 (2x+1, 2y+1)，
+// 两个相邻格子之间的墙对应它们中间的那个方块。因此 w×h 的细墙迷宫
+// 对应 (2w+1)×(2h+1) 的方块网格，生成器产生的迷宫都可以互相转换。
+
+use crate::{Cell, Facing, Position};
+use macroquad::prelude::*;
+use std::collections::VecDeque;
+
+impl Facing {
+    pub const ALL: [Facing; 4] = [Facing::Up, Facing::Right, Facing::Down, Facing::Left];
+
+    pub fn delta(self) -> (i32, i32) {
+        match self {
+            Facing::Up => (0, -1),
+            Facing::Right => (1, 0),
+            Facing::Down => (0, 1),
+            Facing::Left => (-1, 0),
+        }
+    }
+
+    fn opposite(self) -> Facing {
+        Facing::ALL[(self as usize + 2) % 4]
+    }
+
+    fn bit(self) -> u8 {
+        1 << self as u8
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThinMaze {
+    pub width: usize,
+    pub height: usize,
+    walls: Vec<u8>, // 每个格子四边是否有墙，按 Facing 的顺序占一位
+}
+
+impl ThinMaze {
+    // 所有墙都立着的迷宫
+    pub fn new(width: usize, height: usize) -> Self {
+        ThinMaze {
+            width,
+            height,
+            walls: vec![0b1111; width * height],
+        }
+    }
+
+    // 朝某方向走一步后的格子，越界时为 None
+    pub fn step(&self, pos: Position, dir: Facing) -> Option<Position> {
+        let (dx, dy) = dir.delta();
+        let x = pos.x.checked_add_signed(dx as isize)?;
+        let y = pos.y.checked_add_signed(dy as isize)?;
+        (x < self.width && y < self.height).then_some(Position { x, y })
+    }
+
+    pub fn has_wall(&self, pos: Position, dir: Facing) -> bool {
+        self.walls[pos.y * self.width + pos.x] & dir.bit() != 0
+    }
+
+    // 设置或拆除一面墙，同时更新墙另一侧的格子；迷宫外边界的墙不能拆除
+    pub fn set_wall(&mut self, pos: Position, dir: Facing, wall: bool) {
+        let Some(other) = self.step(pos, dir) else {
+            return;
+        };
+        for (p, d) in [(pos, dir), (other, dir.opposite())] {
+            let bits = &mut self.walls[p.y * self.width + p.x];
+            if wall {
+                *bits |= d.bit();
+            } else {
+                *bits &= !d.bit();
+            }
+        }
+    }
+
+    // 能否从 pos 朝 dir 移动一格，能则返回目标格子
+    pub fn can_move(&self, pos: Position, dir: Facing) -> Option<Position> {
+        if self.has_wall(pos, dir) {
+            return None;
+        }
+        self.step(pos, dir)
+    }
+
+    // 从方块网格转换。要求尺寸为奇数、格子位置不是墙、墙角都是墙，
+    // 且起点、终点和物品都在格子位置上（墙的位置只能是墙或空地）
+    pub fn from_grid(grid: &[Vec<Cell>]) -> Result<Self, String> {
+        let block_h = grid.len();
+        let block_w = grid.first().map_or(0, Vec::len);
+        if block_w < 3 || block_h < 3 || block_w.is_multiple_of(2) || block_h.is_multiple_of(2) {
+            return Err(format!("方块网格尺寸 {}x{} 不是奇数", block_w, block_h));
+        }
+
+        let mut maze = ThinMaze::new(block_w / 2, block_h / 2);
+        for (y, row) in grid.iter().enumerate() {
+            for (x, &cell) in row.iter().enumerate() {
+                let open = cell != Cell::Wall;
+                match (x % 2, y % 2) {
+                    (1, 1) if !open => return Err(format!("格子位置 ({}, {}) 是墙", x, y)),
+                    (0, 0) if open => return Err(format!("墙角位置 ({}, {}) 不是墙", x, y)),
+                    (1, 1) | (0, 0) => {}
+                    _ if cell != Cell::Wall && cell != Cell::Empty => {
+                        return Err(format!("({}, {}) 处的 {:?} 不在格子位置上", x, y, cell))
+                    }
+                    (0, _) if open && x > 0 && x < block_w - 1 => {
+                        maze.set_wall(Position { x: x / 2 - 1, y: y / 2 }, Facing::Right, false)
+                    }
+                    (_, 0) if open && y > 0 && y < block_h - 1 => {
+                        maze.set_wall(Position { x: x / 2, y: y / 2 - 1 }, Facing::Down, false)
+                    }
+                    _ if open => return Err(format!("外墙 ({}, {}) 有缺口", x, y)),
+                    _ => {}
+                }
+            }
+        }
+        Ok(maze)
+    }
+
+    // 转换为方块网格（只有墙和空地）
+    pub fn to_grid(&self) -> Vec<Vec<Cell>> {
+        let mut grid = vec![vec![Cell::Wall; self.width * 2 + 1]; self.height * 2 + 1];
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let pos = Position { x, y };
+                let block = to_block(pos);
+                grid[block.y][block.x] = Cell::Empty;
+                if !self.has_wall(pos, Facing::Right) {
+                    grid[block.y][block.x + 1] = Cell::Empty;
+                }
+                if !self.has_wall(pos, Facing::Down) {
+                    grid[block.y + 1][block.x] = Cell::Empty;
+                }
+            }
+        }
+        grid
+    }
+
+    // 广度优先搜索最短路径，包含起点和终点
+    pub fn shortest_path(&self, from: Position, to: Position) -> Option<Vec<Position>> {
+        let mut parent = vec![None; self.width * self.height];
+        let mut queue = VecDeque::from([from]);
+        parent[from.y * self.width + from.x] = Some(from);
+
+        while let Some(current) = queue.pop_front() {
+            if current == to {
+                let mut path = vec![to];
+                let mut step = to;
+                while step != from {
+                    step = parent[step.y * self.width + step.x]?;
+                    path.push(step);
+                }
+                path.reverse();
+                return Some(path);
+            }
+            for dir in Facing::ALL {
+                if let Some(next) = self.can_move(current, dir) {
+                    let seen = &mut parent[next.y * self.width + next.x];
+                    if seen.is_none() {
+                        *seen = Some(current);
+                        queue.push_back(next);
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    // 用线段绘制墙；origin 为左上角，cell_size 为格子边长
+    pub fn render(&self, origin: Vec2, cell_size: f32, thickness: f32, color: Color) {
+        let line = |x1: usize, y1: usize, x2: usize, y2: usize| {
+            let a = origin + vec2(x1 as f32, y1 as f32) * cell_size;
+            let b = origin + vec2(x2 as f32, y2 as f32) * cell_size;
+            draw_line(a.x, a.y, b.x, b.y, thickness, color);
+        };
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let pos = Position { x, y };
+                if self.has_wall(pos, Facing::Up) {
+                    line(x, y, x + 1, y);
+                }
+                if self.has_wall(pos, Facing::Left) {
+                    line(x, y, x, y + 1);
+                }
+            }
+        }
+        // 右边界和下边界
+        line(self.width, 0, self.width, self.height);
+        line(0, self.height, self.width, self.height);
+    }
+}
+
+// 细墙格子在方块网格中的位置
+pub fn to_block(pos: Position) -> Position {
+    Position { x: pos.x * 2 + 1, y: pos.y * 2 + 1 }
+}
+
+// 细墙格子路径转换为方块网格路径（补上中间的通道，不含第一个格子）
+pub fn path_to_blocks(rooms: &[Position]) -> Vec<Position> {
+    rooms
+        .windows(2)
+        .flat_map(|pair| {
+            let (a, b) = (to_block(pair[0]), to_block(pair[1]));
+            [Position { x: (a.x + b.x) / 2, y: (a.y + b.y) / 2 }, b]
+        })
+        .collect()
+}
+
+// 方块网格位置对应的细墙格子，不在格子位置上时为 None
+pub fn from_block(pos: Position) -> Option<Position> {
+    (pos.x % 2 == 1 && pos.y % 2 == 1).then_some(Position { x: pos.x / 2, y: pos.y / 2 })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::{self, Algorithm, GeneratorParams, PostProcess};
+
+    #[test]
+    fn test_round_trip_with_block_grid() {
+        let params = GeneratorParams {
+            algorithm: Algorithm::Prim,
+            width: 21,
+            height: 15,
+            seed: 9,
+            coins: 3,
+            gems: 1,
+            post: PostProcess::default(),
+        };
+        let grid = generator::generate(&params);
+        let maze = ThinMaze::from_grid(&grid).unwrap();
+        assert_eq!((maze.width, maze.height), (10, 7));
+
+        // 转回方块网格后，除起点、终点和物品外完全一致
+        let plain: Vec<Vec<Cell>> = grid
+            .iter()
+            .map(|row| row.iter().map(|&c| if c == Cell::Wall { Cell::Wall } else { Cell::Empty }).collect())
+            .collect();
+        assert_eq!(maze.to_grid(), plain);
+
+        let end = Position { x: 9, y: 6 };
+        let path = maze.shortest_path(Position { x: 0, y: 0 }, end).unwrap();
+        assert_eq!(path.first(), Some(&Position { x: 0, y: 0 }));
+        assert_eq!(path.last(), Some(&end));
+    }
+
+    #[test]
+    fn test_walls_are_shared_between_cells() {
+        let mut maze = ThinMaze::new(3, 2);
+        let origin = Position { x: 0, y: 0 };
+        assert_eq!(maze.can_move(origin, Facing::Right), None);
+        maze.set_wall(origin, Facing::Right, false);
+        assert_eq!(maze.can_move(origin, Facing::Right), Some(Position { x: 1, y: 0 }));
+        assert!(!maze.has_wall(Position { x: 1, y: 0 }, Facing::Left));
+        // 外边界不能拆
+        maze.set_wall(origin, Facing::Up, false);
+        assert!(maze.has_wall(origin, Facing::Up));
+        assert_eq!(maze.shortest_path(origin, Position { x: 2, y: 1 }), None);
+    }
+
+    #[test]
+    fn test_rejects_grids_that_do_not_fit() {
+        // 物品在两个格子之间的通道上
+        let grid: Vec<Vec<Cell>> = ["#####", "#S$E#", "#####"]
+            .iter()
+            .map(|row| row.chars().map(|c| crate::cell_from_char(c).unwrap()).collect())
+            .collect();
+        assert!(ThinMaze::from_grid(&grid).is_err());
+        assert!(ThinMaze::from_grid(&grid[..2]).is_err());
+    }
+}
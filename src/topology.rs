@@ -0,0 +1,614 @@
+// 非矩形迷宫：格子的形状和相邻关系由 Topology 描述
+//
+// 方块网格（MazeGame）只支持正方形格子。这里的迷宫是一张图：
+// Topology 给出每个格子的轮廓多边形以及每条边另一侧的格子，
+// 生成、求解、移动和绘制都只依赖这些信息，因此六边形、三角形、
+// 圆形（极坐标）、环面和多层网格共用同一套代码。
+// 多层网格的格子带有层号，不同层之间的通道就是楼梯。
+//
+// 这些形状只用于快速游戏（ShapedGame），没有物品、小地图和第一人称视图。
+// 方块网格的 MazeGame 和 Position 仍是单层、不跨边缘的：战役、编辑器、
+// 物品、提示路线和命令行工具都不支持环面或多层迷宫。
+
+mod floors;
+mod hex;
+mod polar;
+mod torus;
+mod tri;
+
+pub use floors::FloorsTopology;
+pub use hex::HexTopology;
+pub use polar::PolarTopology;
+pub use torus::TorusTopology;
+pub use tri::TriTopology;
+
+use crate::draw_label;
+use crate::i18n::{fill, tr, Language, Text};
+use crate::theme::{Palette, Style};
+use macroquad::prelude::*;
+use macroquad::rand::RandGenerator;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashSet, VecDeque};
+
+// 网格的几何形状
+pub trait Topology {
+    fn cell_count(&self) -> usize;
+
+    // 格子轮廓：按顺序排列的顶点，以及从该顶点到下一个顶点的边另一侧的格子（边界为 None）。
+    // 坐标以格子边长为单位，左上角为原点
+    fn cell_shape(&self, cell: usize) -> Vec<(Vec2, Option<usize>)>;
+
+    // 整个迷宫的外接矩形尺寸
+    fn size(&self) -> Vec2;
+
+    fn start(&self) -> usize {
+        0
+    }
+
+    fn end(&self) -> usize {
+        self.cell_count() - 1
+    }
+
+    // 相邻格子（不考虑墙）
+    fn neighbors(&self, cell: usize) -> Vec<usize> {
+        let mut result = Vec::new();
+        for (_, neighbor) in self.cell_shape(cell) {
+            if let Some(n) = neighbor {
+                if !result.contains(&n) {
+                    result.push(n);
+                }
+            }
+        }
+        result
+    }
+
+    fn center(&self, cell: usize) -> Vec2 {
+        let shape = self.cell_shape(cell);
+        shape.iter().map(|&(v, _)| v).sum::<Vec2>() / shape.len() as f32
+    }
+
+    // 从一个格子走向相邻格子时在屏幕上的方向（用于按键映射）
+    fn direction(&self, from: usize, to: usize) -> Vec2 {
+        self.center(to) - self.center(from)
+    }
+
+    // 层数；多层网格中每层单独显示
+    fn floor_count(&self) -> usize {
+        1
+    }
+
+    fn floor(&self, _cell: usize) -> usize {
+        0
+    }
+}
+
+// 快速游戏的迷宫形状
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Shape {
+    Square, // 方块网格，使用完整的 MazeGame（物品、细墙、贴图）
+    Hex,
+    Triangle,
+    Polar,
+    Torus,  // 边缘相连
+    Floors, // 多层，层间有楼梯
+}
+
+impl Shape {
+    pub const ALL: [Shape; 6] = [
+        Shape::Square,
+        Shape::Hex,
+        Shape::Triangle,
+        Shape::Polar,
+        Shape::Torus,
+        Shape::Floors,
+    ];
+
+    pub fn display_name(self, language: Language) -> &'static str {
+        let text = match self {
+            Shape::Square => Text::ShapeSquare,
+            Shape::Hex => Text::ShapeHex,
+            Shape::Triangle => Text::ShapeTriangle,
+            Shape::Polar => Text::ShapePolar,
+            Shape::Torus => Text::ShapeTorus,
+            Shape::Floors => Text::ShapeFloors,
+        };
+        tr(language, text)
+    }
+
+    // 格子的边多于四条或斜向相邻时，四个方向键不够用，另外启用斜向移动键
+    pub fn uses_diagonals(self) -> bool {
+        matches!(self, Shape::Hex | Shape::Triangle | Shape::Polar)
+    }
+
+    // 按快速游戏的方块网格尺寸建立大致同样密度的网格
+    pub fn topology(self, width: usize, height: usize) -> Box<dyn Topology> {
+        let (cols, rows) = ((width / 2).max(2), (height / 2).max(2));
+        match self {
+            Shape::Square => Box::new(SquareTopology { width: cols, height: rows }),
+            Shape::Hex => Box::new(HexTopology { width: cols, height: rows }),
+            Shape::Triangle => Box::new(TriTopology { width: cols * 2, height: rows }),
+            Shape::Polar => Box::new(PolarTopology::new(rows)),
+            Shape::Torus => Box::new(TorusTopology { width: cols.max(3), height: rows.max(3) }),
+            Shape::Floors => Box::new(FloorsTopology { width: cols, height: rows, floors: 3 }),
+        }
+    }
+}
+
+// 正方形网格，与细墙迷宫的格子一一对应
+pub struct SquareTopology {
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Topology for SquareTopology {
+    fn cell_count(&self) -> usize {
+        self.width * self.height
+    }
+
+    fn cell_shape(&self, cell: usize) -> Vec<(Vec2, Option<usize>)> {
+        let (x, y) = (cell % self.width, cell / self.width);
+        let (fx, fy) = (x as f32, y as f32);
+        vec![
+            (vec2(fx, fy), (y > 0).then(|| cell - self.width)),
+            (vec2(fx + 1.0, fy), (x + 1 < self.width).then_some(cell + 1)),
+            (vec2(fx + 1.0, fy + 1.0), (y + 1 < self.height).then_some(cell + self.width)),
+            (vec2(fx, fy + 1.0), (x > 0).then(|| cell - 1)),
+        ]
+    }
+
+    fn size(&self) -> Vec2 {
+        vec2(self.width as f32, self.height as f32)
+    }
+}
+
+// 移动键的方向：上、右、下、左，然后是四个斜向（只在 Shape::uses_diagonals 的形状中启用）
+pub const KEY_DIRECTIONS: [(i32, i32); 8] = [(0, -1), (1, 0), (0, 1), (-1, 0), (1, -1), (1, 1), (-1, 1), (-1, -1)];
+
+// 任意网格上的迷宫：记录哪些相邻格子之间是打通的
+pub struct GraphMaze {
+    pub topology: Box<dyn Topology>,
+    open: HashSet<(usize, usize)>,
+}
+
+// 无序格子对
+fn edge(a: usize, b: usize) -> (usize, usize) {
+    (a.min(b), a.max(b))
+}
+
+impl GraphMaze {
+    // 递归回溯生成（显式栈）。多层网格中大多数时候留在本层，楼梯才不会过多
+    pub fn generate(topology: Box<dyn Topology>, seed: u64) -> Self {
+        let rng = RandGenerator::new();
+        rng.srand(seed);
+
+        let mut open = HashSet::new();
+        let mut visited = vec![false; topology.cell_count()];
+        let start = topology.start();
+        let mut stack = vec![start];
+        visited[start] = true;
+
+        while let Some(&cell) = stack.last() {
+            let (same_floor, other_floor): (Vec<usize>, Vec<usize>) = topology
+                .neighbors(cell)
+                .into_iter()
+                .filter(|&n| !visited[n])
+                .partition(|&n| topology.floor(n) == topology.floor(cell));
+            let choices = if other_floor.is_empty() || (!same_floor.is_empty() && rng.gen_range(0, 8) != 0) {
+                same_floor
+            } else {
+                other_floor
+            };
+            if choices.is_empty() {
+                stack.pop();
+                continue;
+            }
+            let next = choices[rng.gen_range(0, choices.len())];
+            open.insert(edge(cell, next));
+            visited[next] = true;
+            stack.push(next);
+        }
+        GraphMaze { topology, open }
+    }
+
+    pub fn is_open(&self, a: usize, b: usize) -> bool {
+        self.open.contains(&edge(a, b))
+    }
+
+    // 打通的相邻格子
+    pub fn passages(&self, cell: usize) -> Vec<usize> {
+        self.topology.neighbors(cell).into_iter().filter(|&n| self.is_open(cell, n)).collect()
+    }
+
+    // 广度优先搜索最短路径，不含起点
+    pub fn shortest_path(&self, from: usize, to: usize) -> Option<Vec<usize>> {
+        let mut parent = vec![None; self.topology.cell_count()];
+        let mut queue = VecDeque::from([from]);
+        parent[from] = Some(from);
+        while let Some(cell) = queue.pop_front() {
+            if cell == to {
+                break;
+            }
+            for next in self.passages(cell) {
+                if parent[next].is_none() {
+                    parent[next] = Some(cell);
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        let mut path = Vec::new();
+        let mut step = to;
+        while step != from {
+            path.push(step);
+            step = parent[step]?;
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    // 本层打通的通道按夹角从小到大分给各个按键，每个按键和每条通道只用一次，
+    // 这样与同一按键夹角相同的两条通道（如六边形的左上和右上）会分到不同的按键
+    fn key_passages(&self, cell: usize) -> Vec<((i32, i32), usize)> {
+        let topology = &self.topology;
+        let mut pairs: Vec<(f32, (i32, i32), usize)> = Vec::new();
+        for n in self.passages(cell).into_iter().filter(|&n| topology.floor(n) == topology.floor(cell)) {
+            let dir = topology.direction(cell, n).normalize();
+            for key in KEY_DIRECTIONS {
+                let alignment = vec2(key.0 as f32, key.1 as f32).normalize().dot(dir);
+                if alignment > 0.0 {
+                    pairs.push((alignment, key, n));
+                }
+            }
+        }
+        pairs.sort_by(|a, b| b.0.total_cmp(&a.0));
+        let mut assigned: Vec<((i32, i32), usize)> = Vec::new();
+        for (_, key, n) in pairs {
+            if !assigned.iter().any(|&(k, m)| k == key || m == n) {
+                assigned.push((key, n));
+            }
+        }
+        assigned
+    }
+
+    // 输入方向映射：按键方向优先走分给它的通道，
+    // 否则在本层打通的方向中选与之最接近的（夹角小于约 70°）
+    pub fn step_toward(&self, cell: usize, dir: Vec2) -> Option<usize> {
+        let dir = dir.normalize();
+        let assigned = self
+            .key_passages(cell)
+            .into_iter()
+            .find(|&(key, _)| vec2(key.0 as f32, key.1 as f32).normalize().dot(dir) > 0.999);
+        if let Some((_, n)) = assigned {
+            return Some(n);
+        }
+        let topology = &self.topology;
+        self.passages(cell)
+            .into_iter()
+            .filter(|&n| topology.floor(n) == topology.floor(cell))
+            .map(|n| (n, topology.direction(cell, n).normalize().dot(dir)))
+            .filter(|&(_, alignment)| alignment > 0.35)
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(n, _)| n)
+    }
+
+    // 楼梯：通往其他层的通道，返回层号的变化（+1 上楼，-1 下楼）
+    pub fn stairs(&self, cell: usize) -> Vec<i32> {
+        let floor = self.topology.floor(cell) as i32;
+        self.passages(cell)
+            .into_iter()
+            .map(|n| self.topology.floor(n) as i32 - floor)
+            .filter(|&delta| delta != 0)
+            .collect()
+    }
+
+    // 绘制第 floor 层：origin 为左上角，scale 为格子边长（像素）
+    pub fn render(&self, origin: Vec2, scale: f32, floor: usize, palette: &Palette, path: &[usize], player: Option<usize>) {
+        let topology = &self.topology;
+        let to_screen = |v: Vec2| origin + v * scale;
+        let cells: Vec<usize> = (0..topology.cell_count()).filter(|&c| topology.floor(c) == floor).collect();
+
+        for &cell in &cells {
+            let color = if cell == topology.start() {
+                palette.start
+            } else if cell == topology.end() {
+                palette.end
+            } else if path.contains(&cell) {
+                palette.path
+            } else {
+                palette.floor
+            };
+            // 以中心为扇心填充（各种格子相对中心都是星形的）
+            let center = to_screen(topology.center(cell));
+            let shape = topology.cell_shape(cell);
+            for i in 0..shape.len() {
+                let a = to_screen(shape[i].0);
+                let b = to_screen(shape[(i + 1) % shape.len()].0);
+                draw_triangle(center, a, b, color);
+            }
+        }
+
+        // 楼梯标记：朝上或朝下的三角形
+        for &cell in &cells {
+            for delta in self.stairs(cell) {
+                let c = to_screen(topology.center(cell)) + vec2(scale * 0.2 * delta as f32, 0.0);
+                let (tip, base) = (-0.3 * delta as f32 * scale, 0.2 * delta as f32 * scale);
+                draw_triangle(
+                    c + vec2(0.0, tip),
+                    c + vec2(-0.2 * scale, base),
+                    c + vec2(0.2 * scale, base),
+                    palette.text,
+                );
+            }
+        }
+
+        if let Some(player) = player.filter(|&p| topology.floor(p) == floor) {
+            let player_center = to_screen(topology.center(player));
+            draw_circle(player_center.x, player_center.y, scale * 0.3, palette.player);
+        }
+
+        // 墙：边界和未打通的边（环面上跨边缘的边两侧各画一次）
+        let thickness = (scale * 0.12).max(2.0);
+        for &cell in &cells {
+            let shape = topology.cell_shape(cell);
+            for i in 0..shape.len() {
+                let draw = match shape[i].1 {
+                    None => true,
+                    Some(n) => !self.is_open(cell, n),
+                };
+                if draw {
+                    let a = to_screen(shape[i].0);
+                    let b = to_screen(shape[(i + 1) % shape.len()].0);
+                    draw_line(a.x, a.y, b.x, b.y, thickness, palette.wall);
+                    draw_circle(a.x, a.y, thickness / 2.0, palette.wall);
+                }
+            }
+        }
+    }
+}
+
+// 在非矩形迷宫上进行的一局游戏（没有可收集物品）
+pub struct ShapedGame {
+    pub shape: Shape,
+    pub maze: GraphMaze,
+    pub player: usize,
+    pub moves: usize,
+    pub elapsed: f32,
+    show_path: bool,
+    path: Vec<usize>,
+}
+
+impl ShapedGame {
+    pub fn new(shape: Shape, width: usize, height: usize, seed: u64) -> Self {
+        let maze = GraphMaze::generate(shape.topology(width, height), seed);
+        ShapedGame {
+            shape,
+            player: maze.topology.start(),
+            maze,
+            moves: 0,
+            elapsed: 0.0,
+            show_path: false,
+            path: Vec::new(),
+        }
+    }
+
+    pub fn move_player(&mut self, dx: i32, dy: i32) -> bool {
+        if self.has_won() {
+            return false;
+        }
+        match self.maze.step_toward(self.player, vec2(dx as f32, dy as f32)) {
+            Some(next) => {
+                self.player = next;
+                self.moves += 1;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn has_won(&self) -> bool {
+        self.player == self.maze.topology.end()
+    }
+
+    pub fn tick(&mut self, dt: f32) {
+        if !self.has_won() {
+            self.elapsed += dt;
+        }
+    }
+
+    pub fn toggle_path(&mut self) {
+        self.show_path = !self.show_path;
+        self.path = if self.show_path {
+            self.maze
+                .shortest_path(self.player, self.maze.topology.end())
+                .unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+    }
+
+    pub fn reset_game(&mut self) {
+        self.player = self.maze.topology.start();
+        self.moves = 0;
+        self.elapsed = 0.0;
+        self.show_path = false;
+        self.path.clear();
+    }
+
+    // 站在楼梯上时换层，delta 为 +1（上楼）或 -1（下楼）
+    pub fn climb(&mut self, delta: i32) -> bool {
+        if self.has_won() {
+            return false;
+        }
+        let topology = &self.maze.topology;
+        let target = topology.floor(self.player) as i32 + delta;
+        let next = self
+            .maze
+            .passages(self.player)
+            .into_iter()
+            .find(|&n| topology.floor(n) as i32 == target);
+        match next {
+            Some(next) => {
+                self.player = next;
+                self.moves += 1;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn render(&self, style: &Style) {
+        const HUD_HEIGHT: f32 = 135.0;
+        let topology = &self.maze.topology;
+        let floors = topology.floor_count();
+        let floor = topology.floor(self.player);
+        let size = topology.size();
+
+        // 多层迷宫右侧留出相邻层的缩略图
+        let main_width = if floors > 1 { screen_width() * 0.75 } else { screen_width() };
+        let scale = (main_width / size.x)
+            .min((screen_height() - HUD_HEIGHT) / size.y)
+            .min(style.max_cell_size * 2.0);
+        self.maze.render(Vec2::ZERO, scale, floor, style.palette, &self.path, Some(self.player));
+
+        let t = |text| tr(style.language, text);
+        if floors > 1 {
+            let mini_scale = ((screen_width() - main_width - 20.0) / size.x).min(scale * 0.5);
+            let x = main_width + 10.0;
+            let mut y = 10.0;
+            // 楼上在上、楼下在下
+            for (other, label) in [(floor + 1, Text::FloorAbove), (floor.wrapping_sub(1), Text::FloorBelow)] {
+                if other < floors {
+                    draw_label(t(label), x, y + 16.0, style);
+                    self.maze.render(vec2(x, y + 24.0), mini_scale, other, style.palette, &[], None);
+                    y += size.y * mini_scale + 40.0;
+                }
+            }
+        }
+
+        let timer_status = fill(t(Text::TimerStatus), &[&format!("{:.1}", self.elapsed), &self.moves]);
+        let mut lines = vec![t(Text::HelpMove).to_string(), t(Text::HelpReset).to_string(), timer_status];
+        match self.shape {
+            Shape::Floors => lines.push(fill(t(Text::FloorStatus), &[&(floor + 1), &floors])),
+            Shape::Torus => lines.push(t(Text::WrapHint).to_string()),
+            shape if shape.uses_diagonals() => lines.push(t(Text::DiagonalHint).to_string()),
+            _ => {}
+        }
+        for (i, line) in lines.iter().enumerate() {
+            draw_label(line, 10.0, size.y * scale + 30.0 + i as f32 * 25.0, style);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 相邻关系必须对称，且共享边的两端顶点一致（环面上允许相差一个周期）
+    fn check_consistent(topology: &dyn Topology) {
+        let size = topology.size();
+        let close = |p: Vec2, q: Vec2| {
+            let d = (p - q).abs();
+            (d.x < 1e-3 || (d.x - size.x).abs() < 1e-3) && (d.y < 1e-3 || (d.y - size.y).abs() < 1e-3)
+        };
+        for cell in 0..topology.cell_count() {
+            let shape = topology.cell_shape(cell);
+            for i in 0..shape.len() {
+                let Some(n) = shape[i].1 else { continue };
+                assert!(topology.neighbors(n).contains(&cell), "{} 与 {} 的相邻关系不对称", cell, n);
+                let (a, b) = (shape[i].0, shape[(i + 1) % shape.len()].0);
+                let other = topology.cell_shape(n);
+                let shared = (0..other.len()).any(|j| {
+                    let (c, d) = (other[j].0, other[(j + 1) % other.len()].0);
+                    other[j].1 == Some(cell) && close(c, b) && close(d, a)
+                });
+                assert!(shared, "{} 与 {} 没有共享的边", cell, n);
+            }
+        }
+    }
+
+    #[test]
+    fn test_topologies_are_consistent() {
+        for shape in Shape::ALL {
+            check_consistent(shape.topology(15, 11).as_ref());
+        }
+    }
+
+    #[test]
+    fn test_generated_mazes_are_perfect_and_solvable() {
+        for shape in Shape::ALL {
+            let maze = GraphMaze::generate(shape.topology(15, 11), 3);
+            let count = maze.topology.cell_count();
+            // 生成树：边数比格子数少一，且所有格子连通
+            assert_eq!(maze.open.len(), count - 1, "{:?}", shape);
+            let path = maze.shortest_path(maze.topology.start(), maze.topology.end()).unwrap();
+            assert_eq!(path.last(), Some(&maze.topology.end()));
+        }
+    }
+
+    #[test]
+    fn test_floors_and_wrapping() {
+        let maze = GraphMaze::generate(Shape::Floors.topology(15, 11), 8);
+        let topology = &maze.topology;
+        let path = maze.shortest_path(topology.start(), topology.end()).unwrap();
+        // 起点在底层，终点在顶层，路径必然经过楼梯
+        assert_eq!(topology.floor(topology.start()), 0);
+        assert_eq!(topology.floor(topology.end()), 2);
+        assert!(path.iter().any(|&cell| maze.stairs(cell).contains(&-1)));
+
+        // 环面：最左列与最右列相邻，方向按绕过去的一步计算
+        let torus = TorusTopology { width: 5, height: 4 };
+        assert!(torus.neighbors(0).contains(&4));
+        assert!(torus.neighbors(0).contains(&15));
+        assert_eq!(torus.direction(0, 4), vec2(-1.0, 0.0));
+        assert_eq!(torus.direction(15, 0), vec2(0.0, 1.0));
+    }
+
+    #[test]
+    fn test_directions_map_to_passages() {
+        for shape in Shape::ALL {
+            let mut game = ShapedGame::new(shape, 15, 11, 5);
+            game.toggle_path();
+            for cell in game.path.clone() {
+                let topology = &game.maze.topology;
+                let delta = topology.floor(cell) as i32 - topology.floor(game.player) as i32;
+                if delta != 0 {
+                    // 多层迷宫：路径经过楼梯
+                    assert!(game.climb(delta));
+                    assert_eq!(game.player, cell);
+                    continue;
+                }
+                let dir = topology.direction(game.player, cell);
+                assert_eq!(game.maze.step_toward(game.player, dir), Some(cell), "{:?}", shape);
+                game.player = cell;
+            }
+            assert!(game.has_won());
+            game.reset_game();
+            assert_eq!(game.player, game.maze.topology.start());
+        }
+
+        // 每条打通的通道都至少有一个启用的按键能走过去
+        for shape in Shape::ALL {
+            let keys = if shape.uses_diagonals() { &KEY_DIRECTIONS[..] } else { &KEY_DIRECTIONS[..4] };
+            for seed in 0..20 {
+                let maze = GraphMaze::generate(shape.topology(25, 17), seed);
+                let topology = &maze.topology;
+                for cell in 0..topology.cell_count() {
+                    for n in maze.passages(cell).into_iter().filter(|&n| topology.floor(n) == topology.floor(cell)) {
+                        let reachable = keys
+                            .iter()
+                            .any(|&(dx, dy)| maze.step_toward(cell, vec2(dx as f32, dy as f32)) == Some(n));
+                        assert!(reachable, "{:?} 种子 {} 中 {} 到 {} 的通道没有按键", shape, seed, cell, n);
+                    }
+                }
+            }
+        }
+
+        // 正方形网格上按键方向与通道完全对应
+        let mut game = ShapedGame::new(Shape::Square, 15, 11, 5);
+        let first = game.maze.passages(game.player)[0];
+        let (dx, dy) = if first == 1 { (1, 0) } else { (0, 1) };
+        assert!(game.move_player(dx, dy));
+        assert_eq!((game.player, game.moves), (first, 1));
+    }
+}
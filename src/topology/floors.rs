@@ -0,0 +1,61 @@
+// 多层迷宫：若干层正方形网格叠在一起，上下层同一位置的格子之间可以有楼梯。
+// 层号只存在于这里的格子编号中，方块网格的 Position 没有层号。
+
+use super::Topology;
+use macroquad::prelude::*;
+
+pub struct FloorsTopology {
+    pub width: usize,
+    pub height: usize,
+    pub floors: usize,
+}
+
+impl FloorsTopology {
+    fn floor_size(&self) -> usize {
+        self.width * self.height
+    }
+}
+
+impl Topology for FloorsTopology {
+    fn cell_count(&self) -> usize {
+        self.floor_size() * self.floors
+    }
+
+    // 每一层的格子形状相同，楼梯不在轮廓上
+    fn cell_shape(&self, cell: usize) -> Vec<(Vec2, Option<usize>)> {
+        let local = cell % self.floor_size();
+        let (x, y) = (local % self.width, local / self.width);
+        let (fx, fy) = (x as f32, y as f32);
+        vec![
+            (vec2(fx, fy), (y > 0).then(|| cell - self.width)),
+            (vec2(fx + 1.0, fy), (x + 1 < self.width).then_some(cell + 1)),
+            (vec2(fx + 1.0, fy + 1.0), (y + 1 < self.height).then_some(cell + self.width)),
+            (vec2(fx, fy + 1.0), (x > 0).then(|| cell - 1)),
+        ]
+    }
+
+    fn size(&self) -> Vec2 {
+        vec2(self.width as f32, self.height as f32)
+    }
+
+    fn floor_count(&self) -> usize {
+        self.floors
+    }
+
+    fn floor(&self, cell: usize) -> usize {
+        cell / self.floor_size()
+    }
+
+    // 同层的四个方向，加上楼上和楼下的同一位置
+    fn neighbors(&self, cell: usize) -> Vec<usize> {
+        let mut result: Vec<usize> = self.cell_shape(cell).into_iter().filter_map(|(_, n)| n).collect();
+        let floor = self.floor(cell);
+        if floor + 1 < self.floors {
+            result.push(cell + self.floor_size());
+        }
+        if floor > 0 {
+            result.push(cell - self.floor_size());
+        }
+        result
+    }
+}
@@ -0,0 +1,54 @@
+// 六边形网格：尖顶六边形，奇数行向右错开半格
+
+use super::Topology;
+use macroquad::prelude::*;
+
+const SQRT3: f32 = 1.732_050_8;
+
+pub struct HexTopology {
+    pub width: usize,
+    pub height: usize,
+}
+
+impl HexTopology {
+    fn index(&self, col: i32, row: i32) -> Option<usize> {
+        let inside = col >= 0 && row >= 0 && (col as usize) < self.width && (row as usize) < self.height;
+        inside.then(|| row as usize * self.width + col as usize)
+    }
+}
+
+impl Topology for HexTopology {
+    fn cell_count(&self) -> usize {
+        self.width * self.height
+    }
+
+    fn cell_shape(&self, cell: usize) -> Vec<(Vec2, Option<usize>)> {
+        let (col, row) = ((cell % self.width) as i32, (cell / self.width) as i32);
+        let shift = row & 1; // 奇数行的斜向邻居偏右一列
+        let center = vec2(
+            SQRT3 * (col as f32 + 0.5 * shift as f32) + SQRT3 / 2.0,
+            1.5 * row as f32 + 1.0,
+        );
+        // 顶点从正上方开始顺时针，第 k 条边依次朝向右上、右、右下、左下、左、左上
+        let neighbors = [
+            (col + shift, row - 1),
+            (col + 1, row),
+            (col + shift, row + 1),
+            (col + shift - 1, row + 1),
+            (col - 1, row),
+            (col + shift - 1, row - 1),
+        ];
+        neighbors
+            .iter()
+            .enumerate()
+            .map(|(k, &(c, r))| {
+                let angle = (-90.0 + 60.0 * k as f32).to_radians();
+                (center + vec2(angle.cos(), angle.sin()), self.index(c, r))
+            })
+            .collect()
+    }
+
+    fn size(&self) -> Vec2 {
+        vec2(SQRT3 * (self.width as f32 + 0.5), 1.5 * self.height as f32 + 0.5)
+    }
+}
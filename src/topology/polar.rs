@@ -0,0 +1,130 @@
+// 圆形（极坐标）网格：中心一个圆形格子，向外一圈圈排列扇形格子。
+// 外圈格子的弧长超过约两个单位时，该圈的格子数比内圈翻倍，使格子大小大致均匀。
+
+use super::Topology;
+use macroquad::prelude::*;
+use std::f32::consts::TAU;
+
+pub struct PolarTopology {
+    counts: Vec<usize>,  // 每一圈的格子数，第0圈为中心
+    offsets: Vec<usize>, // 每一圈第一个格子的编号
+}
+
+// 弧线按这个长度切成小段绘制
+const ARC_STEP: f32 = 0.35;
+
+impl PolarTopology {
+    pub fn new(rings: usize) -> Self {
+        let mut counts = vec![1];
+        for ring in 1..rings.max(2) {
+            let previous = counts[ring - 1];
+            let count = if ring == 1 {
+                6
+            } else if TAU * ring as f32 / previous as f32 > 2.0 {
+                previous * 2
+            } else {
+                previous
+            };
+            counts.push(count);
+        }
+        let offsets = counts
+            .iter()
+            .scan(0, |total, &count| {
+                let offset = *total;
+                *total += count;
+                Some(offset)
+            })
+            .collect();
+        PolarTopology { counts, offsets }
+    }
+
+    fn rings(&self) -> usize {
+        self.counts.len()
+    }
+
+    // 格子编号 → (圈, 扇区)
+    fn locate(&self, cell: usize) -> (usize, usize) {
+        let ring = self.offsets.iter().rposition(|&offset| offset <= cell).unwrap_or(0);
+        (ring, cell - self.offsets[ring])
+    }
+
+    fn index(&self, ring: usize, sector: usize) -> usize {
+        self.offsets[ring] + sector % self.counts[ring]
+    }
+
+    fn point(&self, radius: f32, angle: f32) -> Vec2 {
+        let center = self.rings() as f32;
+        vec2(center + radius * angle.cos(), center + radius * angle.sin())
+    }
+
+    // 从 from 到 to 的弧线上的顶点（不含终点），每段都以 neighbor 为邻居
+    fn arc(&self, radius: f32, from: f32, to: f32, neighbor: Option<usize>, out: &mut Vec<(Vec2, Option<usize>)>) {
+        let steps = ((to - from).abs() * radius / ARC_STEP).ceil().max(1.0) as usize;
+        for i in 0..steps {
+            let angle = from + (to - from) * i as f32 / steps as f32;
+            out.push((self.point(radius, angle), neighbor));
+        }
+    }
+}
+
+impl Topology for PolarTopology {
+    fn cell_count(&self) -> usize {
+        self.offsets[self.rings() - 1] + self.counts[self.rings() - 1]
+    }
+
+    fn cell_shape(&self, cell: usize) -> Vec<(Vec2, Option<usize>)> {
+        let (ring, sector) = self.locate(cell);
+        let count = self.counts[ring];
+        let mut shape = Vec::new();
+
+        if ring == 0 {
+            // 中心格子：半径为1的圆，每段弧对着第1圈的一个格子
+            // 与外圈格子的内弧方向相反，保证所有格子的顶点绕行方向一致
+            let outer = self.counts[1];
+            for s in (0..outer).rev() {
+                let a0 = TAU * s as f32 / outer as f32;
+                let a1 = TAU * (s + 1) as f32 / outer as f32;
+                self.arc(1.0, a1, a0, Some(self.index(1, s)), &mut shape);
+            }
+            return shape;
+        }
+
+        let inner = ring as f32;
+        let outer = inner + 1.0;
+        let a0 = TAU * sector as f32 / count as f32;
+        let a1 = TAU * (sector + 1) as f32 / count as f32;
+
+        // 内弧：对着内圈的格子（内圈格子数为本圈的一半或相同）
+        let parent = sector * self.counts[ring - 1] / count;
+        self.arc(inner, a0, a1, Some(self.index(ring - 1, parent)), &mut shape);
+        // 顺时针一侧的径向边
+        shape.push((self.point(inner, a1), Some(self.index(ring, sector + 1))));
+        // 外弧：最外圈为边界；外圈翻倍时分成两段，各对一个外圈格子
+        if ring + 1 == self.rings() {
+            self.arc(outer, a1, a0, None, &mut shape);
+        } else if self.counts[ring + 1] == count {
+            self.arc(outer, a1, a0, Some(self.index(ring + 1, sector)), &mut shape);
+        } else {
+            let mid = (a0 + a1) / 2.0;
+            self.arc(outer, a1, mid, Some(self.index(ring + 1, sector * 2 + 1)), &mut shape);
+            self.arc(outer, mid, a0, Some(self.index(ring + 1, sector * 2)), &mut shape);
+        }
+        // 逆时针一侧的径向边
+        shape.push((self.point(outer, a0), Some(self.index(ring, sector + count - 1))));
+        shape
+    }
+
+    fn size(&self) -> Vec2 {
+        Vec2::splat(self.rings() as f32 * 2.0)
+    }
+
+    // 扇形格子的顶点分布不均匀，用极坐标中点作为中心
+    fn center(&self, cell: usize) -> Vec2 {
+        let (ring, sector) = self.locate(cell);
+        if ring == 0 {
+            return self.point(0.0, 0.0);
+        }
+        let angle = TAU * (sector as f32 + 0.5) / self.counts[ring] as f32;
+        self.point(ring as f32 + 0.5, angle)
+    }
+}
@@ -0,0 +1,50 @@
+// 环面网格：正方形格子，左右边缘和上下边缘分别相连。
+// 只有快速游戏的 ShapedGame 会跨边缘移动，MazeGame 的移动和寻路不会。
+
+use super::Topology;
+use macroquad::prelude::*;
+
+pub struct TorusTopology {
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Topology for TorusTopology {
+    fn cell_count(&self) -> usize {
+        self.width * self.height
+    }
+
+    fn cell_shape(&self, cell: usize) -> Vec<(Vec2, Option<usize>)> {
+        let (w, h) = (self.width, self.height);
+        let (x, y) = (cell % w, cell / w);
+        let (fx, fy) = (x as f32, y as f32);
+        let index = |x: usize, y: usize| Some(y * w + x);
+        vec![
+            (vec2(fx, fy), index(x, (y + h - 1) % h)),
+            (vec2(fx + 1.0, fy), index((x + 1) % w, y)),
+            (vec2(fx + 1.0, fy + 1.0), index(x, (y + 1) % h)),
+            (vec2(fx, fy + 1.0), index((x + w - 1) % w, y)),
+        ]
+    }
+
+    fn size(&self) -> Vec2 {
+        vec2(self.width as f32, self.height as f32)
+    }
+
+    // 跨越边缘的相邻格子，方向按绕过去的那一步计算
+    fn direction(&self, from: usize, to: usize) -> Vec2 {
+        let wrap = |d: i32, n: usize| {
+            let n = n as i32;
+            if d > 1 {
+                d - n
+            } else if d < -1 {
+                d + n
+            } else {
+                d
+            }
+        };
+        let dx = (to % self.width) as i32 - (from % self.width) as i32;
+        let dy = (to / self.width) as i32 - (from / self.width) as i32;
+        vec2(wrap(dx, self.width) as f32, wrap(dy, self.height) as f32)
+    }
+}
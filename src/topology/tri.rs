@@ -0,0 +1,53 @@
+// 三角形网格：每行由尖朝上和尖朝下的三角形交替组成
+
+use super::Topology;
+use macroquad::prelude::*;
+
+const HEIGHT: f32 = 0.866_025_4; // 边长为1的正三角形的高
+
+pub struct TriTopology {
+    pub width: usize,
+    pub height: usize,
+}
+
+impl TriTopology {
+    fn index(&self, col: i32, row: i32) -> Option<usize> {
+        let inside = col >= 0 && row >= 0 && (col as usize) < self.width && (row as usize) < self.height;
+        inside.then(|| row as usize * self.width + col as usize)
+    }
+}
+
+impl Topology for TriTopology {
+    fn cell_count(&self) -> usize {
+        self.width * self.height
+    }
+
+    fn cell_shape(&self, cell: usize) -> Vec<(Vec2, Option<usize>)> {
+        let (col, row) = ((cell % self.width) as i32, (cell / self.width) as i32);
+        let left = col as f32 * 0.5;
+        let top = row as f32 * HEIGHT;
+        let bottom = top + HEIGHT;
+        let right = self.index(col + 1, row);
+        let left_neighbor = self.index(col - 1, row);
+
+        if (col + row) % 2 == 0 {
+            // 尖朝上：顶点、右下、左下，底边与下一行尖朝下的三角形相邻
+            vec![
+                (vec2(left + 0.5, top), right),
+                (vec2(left + 1.0, bottom), self.index(col, row + 1)),
+                (vec2(left, bottom), left_neighbor),
+            ]
+        } else {
+            // 尖朝下：左上、右上、底部顶点，顶边与上一行尖朝上的三角形相邻
+            vec![
+                (vec2(left, top), self.index(col, row - 1)),
+                (vec2(left + 1.0, top), right),
+                (vec2(left + 0.5, bottom), left_neighbor),
+            ]
+        }
+    }
+
+    fn size(&self) -> Vec2 {
+        vec2(self.width as f32 * 0.5 + 0.5, self.height as f32 * HEIGHT)
+    }
+}
@@ -0,0 +1,119 @@
+// 走过的路线：面包屑（按顺序连接走过的格子）和热力图（按到访次数着色）。
+// 数据记录在 MazeGame::visits 和 MazeGame::trail 中，这里只负责显示和统计。
+
+use crate::i18n::{tr, Language, Text};
+use crate::theme::Palette;
+use crate::{MazeGame, Position};
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+
+// 走过路线的叠加显示方式，游戏中按 B 切换
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TrailOverlay {
+    Off,
+    Breadcrumbs,
+    Heatmap,
+}
+
+impl TrailOverlay {
+    pub const ALL: [TrailOverlay; 3] = [TrailOverlay::Off, TrailOverlay::Breadcrumbs, TrailOverlay::Heatmap];
+
+    pub fn display_name(self, language: Language) -> &'static str {
+        let text = match self {
+            TrailOverlay::Off => Text::Off,
+            TrailOverlay::Breadcrumbs => Text::Breadcrumbs,
+            TrailOverlay::Heatmap => Text::Heatmap,
+        };
+        tr(language, text)
+    }
+}
+
+// 到访统计，显示在结算界面
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VisitStats {
+    pub cells: usize,     // 到过的格子数
+    pub revisited: usize, // 到过不止一次的格子数
+    pub most: u32,        // 单个格子的最多到访次数
+}
+
+pub fn stats(game: &MazeGame) -> VisitStats {
+    let counts = || game.visits.iter().flatten().copied().filter(|&n| n > 0);
+    VisitStats {
+        cells: counts().count(),
+        revisited: counts().filter(|&n| n > 1).count(),
+        most: counts().max().unwrap_or(0),
+    }
+}
+
+// 热力图颜色：到访一次为蓝色，越多越接近红色
+fn heat_color(visits: u32, most: u32) -> Color {
+    let t = if most <= 1 { 0.0 } else { (visits - 1) as f32 / (most - 1) as f32 };
+    let (r, g, b) = if t < 0.5 {
+        (t * 2.0, t * 2.0, 1.0 - t * 2.0)
+    } else {
+        (1.0, 2.0 - t * 2.0, 0.0)
+    };
+    Color::new(r, g, b, 0.45)
+}
+
+// 画在地板之上、提示路线和玩家之下
+pub fn render(game: &MazeGame, palette: &Palette, cell_size: f32) {
+    let center = |pos: Position| vec2(pos.x as f32 + 0.5, pos.y as f32 + 0.5) * cell_size;
+    match game.trail_overlay {
+        TrailOverlay::Off => {}
+        TrailOverlay::Breadcrumbs => {
+            let mut color = palette.player;
+            color.a = 0.5;
+            for pair in game.trail.windows(2) {
+                let (a, b) = (center(pair[0]), center(pair[1]));
+                draw_line(a.x, a.y, b.x, b.y, (cell_size * 0.1).max(1.0), color);
+            }
+            for &pos in &game.trail {
+                let c = center(pos);
+                draw_circle(c.x, c.y, cell_size * 0.12, color);
+            }
+        }
+        TrailOverlay::Heatmap => {
+            let most = stats(game).most;
+            for (y, row) in game.visits.iter().enumerate() {
+                for (x, &visits) in row.iter().enumerate().filter(|(_, &n)| n > 0) {
+                    let (px, py) = (x as f32 * cell_size, y as f32 * cell_size);
+                    draw_rectangle(px, py, cell_size, cell_size, heat_color(visits, most));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Cell;
+
+    #[test]
+    fn test_visits_and_breadcrumbs() {
+        let mut game = MazeGame::from_text("#####\n#S  #\n### #\n#E  #\n#####").unwrap();
+        game.move_player(1, 0);
+        game.move_player(1, 0);
+        game.move_player(-1, 0);
+        game.move_player(1, 0);
+        assert_eq!(game.visits[1][2], 2);
+        assert_eq!(game.visits[1][3], 2);
+        assert_eq!(stats(&game), VisitStats { cells: 3, revisited: 2, most: 2 });
+        // 离开后的空地标记为走过，起点保持不变
+        assert_eq!(game.grid[1][2], Cell::Path);
+        assert_eq!(game.grid[1][1], Cell::Start);
+        assert_eq!(game.trail.len(), 5);
+
+        assert_eq!(heat_color(1, 2), Color::new(0.0, 0.0, 1.0, 0.45));
+        assert_eq!(heat_color(2, 2), Color::new(1.0, 0.0, 0.0, 0.45));
+
+        // 撞墙不计入到访，重置时清空
+        game.move_player(0, -1);
+        assert_eq!(game.trail.len(), 5);
+        game.reset_game();
+        assert_eq!(stats(&game).cells, 1);
+        assert_eq!(game.trail, vec![game.start_pos]);
+    }
+}
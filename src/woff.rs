@@ -0,0 +1,165 @@
+// WOFF 1.0 字体解码：把 WOFF 还原为 TTF/OTF，供 macroquad 加载
+//
+// 格式见 https://www.w3.org/TR/WOFF/ ，每个表单独用 zlib 压缩
+// （压缩后不比原始数据小的表则原样存放）。
+
+fn read_u16(data: &[u8], offset: usize) -> Result<u16, String> {
+    data.get(offset..offset + 2)
+        .map(|b| u16::from_be_bytes([b[0], b[1]]))
+        .ok_or_else(|| "WOFF 数据被截断".to_string())
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32, String> {
+    data.get(offset..offset + 4)
+        .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+        .ok_or_else(|| "WOFF 数据被截断".to_string())
+}
+
+// 解码 WOFF，返回 sfnt 字体数据
+pub fn decode(data: &[u8]) -> Result<Vec<u8>, String> {
+    const HEADER_SIZE: usize = 44;
+    const WOFF_ENTRY_SIZE: usize = 20;
+    const SFNT_ENTRY_SIZE: usize = 16;
+    // sfnt 表头用 u16 记录 表数 * 16，超过这个数量无法表示
+    const MAX_TABLES: usize = u16::MAX as usize / SFNT_ENTRY_SIZE;
+    // 还原后的字体大小上限；游戏用的字体远小于此，表头或表长度声称更大时视为损坏
+    const MAX_SFNT_SIZE: usize = 32 << 20;
+
+    if read_u32(data, 0)? != 0x774F_4646 {
+        return Err("不是 WOFF 字体".to_string());
+    }
+    let flavor = read_u32(data, 4)?;
+    let num_tables = read_u16(data, 12)? as usize;
+    let total_sfnt_size = read_u32(data, 16)? as usize;
+    if num_tables == 0 {
+        return Err("WOFF 中没有任何表".to_string());
+    }
+    if num_tables > MAX_TABLES {
+        return Err(format!("WOFF 中的表太多（{} 个）", num_tables));
+    }
+    if total_sfnt_size > MAX_SFNT_SIZE {
+        return Err(format!("WOFF 声称的字体太大（{} 字节）", total_sfnt_size));
+    }
+
+    // sfnt 表头中的二分查找参数
+    let mut entry_selector = 0u16;
+    while (2usize << entry_selector) <= num_tables {
+        entry_selector += 1;
+    }
+    let search_range = (1u16 << entry_selector) * 16;
+    let range_shift = num_tables as u16 * 16 - search_range;
+
+    let mut out = Vec::with_capacity(total_sfnt_size);
+    out.extend_from_slice(&flavor.to_be_bytes());
+    out.extend_from_slice(&(num_tables as u16).to_be_bytes());
+    out.extend_from_slice(&search_range.to_be_bytes());
+    out.extend_from_slice(&entry_selector.to_be_bytes());
+    out.extend_from_slice(&range_shift.to_be_bytes());
+
+    // 先写目录占位，再依次追加表数据
+    let directory_start = out.len();
+    out.resize(directory_start + num_tables * SFNT_ENTRY_SIZE, 0);
+
+    for i in 0..num_tables {
+        let entry = HEADER_SIZE + i * WOFF_ENTRY_SIZE;
+        let tag = read_u32(data, entry)?;
+        let offset = read_u32(data, entry + 4)? as usize;
+        let comp_length = read_u32(data, entry + 8)? as usize;
+        let orig_length = read_u32(data, entry + 12)? as usize;
+        let checksum = read_u32(data, entry + 16)?;
+
+        let stored = data
+            .get(offset..offset + comp_length)
+            .ok_or_else(|| format!("第 {} 个表超出文件范围", i))?;
+        let table_offset = out.len();
+        if comp_length < orig_length {
+            let limit = orig_length.min(MAX_SFNT_SIZE);
+            let table = miniz_oxide::inflate::decompress_to_vec_zlib_with_limit(stored, limit)
+                .map_err(|e| format!("第 {} 个表解压失败: {:?}", i, e))?;
+            if table.len() != orig_length {
+                return Err(format!("第 {} 个表解压后长度不符", i));
+            }
+            out.extend_from_slice(&table);
+        } else {
+            out.extend_from_slice(stored);
+        }
+        // 表数据按4字节对齐
+        while !out.len().is_multiple_of(4) {
+            out.push(0);
+        }
+        if out.len() > MAX_SFNT_SIZE {
+            return Err(format!("WOFF 还原后超过 {} 字节", MAX_SFNT_SIZE));
+        }
+
+        let record = directory_start + i * SFNT_ENTRY_SIZE;
+        out[record..record + 4].copy_from_slice(&tag.to_be_bytes());
+        out[record + 4..record + 8].copy_from_slice(&checksum.to_be_bytes());
+        out[record + 8..record + 12].copy_from_slice(&(table_offset as u32).to_be_bytes());
+        out[record + 12..record + 16].copy_from_slice(&(orig_length as u32).to_be_bytes());
+    }
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 用两个表拼出最小的 WOFF：一个压缩、一个原样存放
+    fn sample_woff(tables: &[(&[u8; 4], Vec<u8>)]) -> Vec<u8> {
+        let mut woff = vec![0u8; 44 + tables.len() * 20];
+        woff[0..4].copy_from_slice(b"wOFF");
+        woff[4..8].copy_from_slice(&0x0001_0000u32.to_be_bytes());
+        woff[12..14].copy_from_slice(&(tables.len() as u16).to_be_bytes());
+        for (i, (tag, table)) in tables.iter().enumerate() {
+            let compressed = miniz_oxide::deflate::compress_to_vec_zlib(table, 6);
+            let stored = if compressed.len() < table.len() { compressed } else { table.clone() };
+            let entry = 44 + i * 20;
+            let offset = woff.len() as u32;
+            woff[entry..entry + 4].copy_from_slice(*tag);
+            woff[entry + 4..entry + 8].copy_from_slice(&offset.to_be_bytes());
+            woff[entry + 8..entry + 12].copy_from_slice(&(stored.len() as u32).to_be_bytes());
+            woff[entry + 12..entry + 16].copy_from_slice(&(table.len() as u32).to_be_bytes());
+            woff.extend_from_slice(&stored);
+            while !woff.len().is_multiple_of(4) {
+                woff.push(0);
+            }
+        }
+        woff
+    }
+
+    #[test]
+    fn test_decode_restores_tables() {
+        let big = vec![7u8; 1000];
+        let small = vec![1u8, 2, 3];
+        let sfnt = decode(&sample_woff(&[(b"glyf", big.clone()), (b"head", small.clone())])).unwrap();
+
+        assert_eq!(read_u32(&sfnt, 0).unwrap(), 0x0001_0000);
+        assert_eq!(read_u16(&sfnt, 4).unwrap(), 2);
+        // 第一个表：解压后的数据
+        let offset = read_u32(&sfnt, 12 + 8).unwrap() as usize;
+        assert_eq!(&sfnt[offset..offset + 1000], &big[..]);
+        // 第二个表：原样存放的数据，且按4字节对齐
+        let offset = read_u32(&sfnt, 12 + 16 + 8).unwrap() as usize;
+        assert_eq!(offset % 4, 0);
+        assert_eq!(&sfnt[offset..offset + 3], &small[..]);
+    }
+
+    #[test]
+    fn test_rejects_other_formats() {
+        assert!(decode(b"\x00\x01\x00\x00").is_err());
+        assert!(decode(b"wOFF").is_err());
+        let mut many = sample_woff(&[(b"head", vec![1u8, 2, 3])]);
+        many[12..14].copy_from_slice(&5000u16.to_be_bytes());
+        assert!(decode(&many).unwrap_err().contains("太多"));
+
+        // 表头声称约 4 GiB 时直接拒绝，不会先分配内存
+        let mut huge = sample_woff(&[(b"head", vec![1u8, 2, 3])]);
+        huge[16..20].copy_from_slice(&u32::MAX.to_be_bytes());
+        assert!(decode(&huge).unwrap_err().contains("太大"));
+        // 表的原始长度声称过大时，解压最多到上限，长度对不上同样报错
+        let mut bomb = sample_woff(&[(b"glyf", vec![0u8; 100_000])]);
+        bomb[44 + 12..44 + 16].copy_from_slice(&u32::MAX.to_be_bytes());
+        assert!(decode(&bomb).is_err());
+    }
+}